@@ -0,0 +1,378 @@
+//! Adding and removing the alpha component of whole slices of pixels, e.g. `&[Rgb<u8>]` to `&mut [Rgba<u8>]`.
+//!
+//! Removing alpha by dropping it reveals the colors of transparent pixels, which are often garbage
+//! (see [`HasAlpha`]). [`flatten_slice()`] composites the pixels over a background color instead.
+//!
+//! ```
+//! use rgb::{Rgb, Rgba};
+//! use rgb::alpha::{add_alpha_slice, flatten_slice};
+//!
+//! let rgb = [Rgb::new(255u8, 0, 0); 3];
+//! let mut rgba = [Rgba::default(); 3];
+//! add_alpha_slice(&rgb, 128, &mut rgba);
+//! assert_eq!(rgba[0], Rgba::new(255, 0, 0, 128));
+//!
+//! let mut flat = [Rgb::default(); 3];
+//! flatten_slice(&rgba, Rgba::new(255, 255, 255, 255), &mut flat);
+//! assert_eq!(flat[0], Rgb::new(255, 127, 127));
+//! ```
+//!
+//! `u8` pixels that keep the order of their colors, like `Rgb<u8>` and `Rgba<u8>`, are converted with [SIMD instructions](crate::simd).
+//!
+//! With the `std` feature, [`bleed()`] fills the colors of fully transparent pixels from their visible neighbors,
+//! so that the garbage colors don't show up as fringes when the image is filtered.
+
+use crate::blend::BlendComponent;
+use crate::composite::{Composite, Operator};
+#[cfg(feature = "std")]
+use crate::image::ImageViewMut;
+use crate::simd;
+use crate::{Abgr, Argb, Bgr, Bgra, GainAlpha, GrayA, HasAlpha, Rgb, Rgba, Ycbcr, Ycbcra};
+use core::any::{Any, TypeId};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[inline(always)]
+fn is<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+/// Copies pixels to the same type with an alpha component, e.g. [`Rgb`] to [`Rgba`], setting alpha to `alpha`.
+///
+/// Pixels that already have alpha get the new `alpha` too, like [`GainAlpha::with_alpha()`].
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn add_alpha_slice<P: GainAlpha + 'static>(src: &[P], alpha: P::AlphaComponent, dst: &mut [P::GainAlpha]) {
+    assert_eq!(src.len(), dst.len());
+    if is::<P, Rgb<u8>>() || is::<P, Bgr<u8>>() || is::<P, Ycbcr<u8>>() {
+        if let Some(&alpha) = (&alpha as &dyn Any).downcast_ref::<u8>() {
+            // SAFETY: `P` is a repr(C) struct of 3 `u8`, and `P::GainAlpha` the same with a `u8` alpha appended
+            let (src, dst) = unsafe { (simd::as_bytes(src), simd::as_bytes_mut(dst)) };
+            simd::expand_bytes(src, alpha, dst);
+            return;
+        }
+    }
+    for (src, dst) in src.iter().zip(dst) {
+        *dst = src.with_alpha(alpha);
+    }
+}
+
+/// Copies pixels to a type without alpha, e.g. [`Rgba`] to [`Rgb`], dropping the alpha component.
+///
+/// The colors aren't changed. Use [`flatten_slice()`] to hide the colors of transparent pixels.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Argb, Rgb};
+///
+/// let src = [Argb::new_argb(0u16, 1, 2, 3)];
+/// let mut dst = [Rgb::default()];
+/// rgb::alpha::remove_alpha_slice(&src, &mut dst);
+/// assert_eq!(dst[0], Rgb::new(1, 2, 3));
+/// ```
+pub fn remove_alpha_slice<P, Q>(src: &[P], dst: &mut [Q])
+where
+    P: HasAlpha + Into<Q> + 'static,
+    Q: 'static,
+{
+    assert_eq!(src.len(), dst.len());
+    if (is::<P, Rgba<u8>>() && is::<Q, Rgb<u8>>()) || (is::<P, Bgra<u8>>() && is::<Q, Bgr<u8>>()) || (is::<P, Ycbcra<u8>>() && is::<Q, Ycbcr<u8>>()) {
+        // SAFETY: `P` is a repr(C) struct of 4 `u8`, and `Q` the same without the last one
+        let (src, dst) = unsafe { (simd::as_bytes(src), simd::as_bytes_mut(dst)) };
+        simd::strip_bytes(src, dst);
+        return;
+    }
+    for (&src, dst) in src.iter().zip(dst) {
+        *dst = src.into();
+    }
+}
+
+/// Composites the pixels over `background`, and copies them to a type without alpha, e.g. [`Rgba`] to [`Rgb`].
+///
+/// The background should be opaque, e.g. `Rgba::new(255, 255, 255, 255)` for white.
+/// If it isn't, the colors are composited over it all the same, and the alpha of the result is dropped.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn flatten_slice<P, Q>(src: &[P], background: P, dst: &mut [Q])
+where
+    P: Composite + Into<Q>,
+{
+    assert_eq!(src.len(), dst.len());
+    for (&src, dst) in src.iter().zip(dst) {
+        *dst = src.composite(background, Operator::SourceOver).into();
+    }
+}
+
+/// Converts RGB pixels to RGBA in the same buffer, setting alpha to `alpha`.
+///
+/// The `Vec` is grown to fit the larger pixels, and they're moved from the end, so that none is overwritten before it's moved.
+/// The allocation is reused if it's large enough, and its size is a multiple of 4 bytes.
+///
+/// ```
+/// use rgb::{Rgb, Rgba};
+///
+/// let rgb = vec![Rgb::new(1u8, 2, 3); 1000];
+/// let rgba = rgb::alpha::add_alpha_in_place(rgb, 255);
+/// assert_eq!(rgba.len(), 1000);
+/// assert_eq!(rgba[999], Rgba::new(1, 2, 3, 255));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn add_alpha_in_place(pixels: Vec<Rgb<u8>>, alpha: u8) -> Vec<Rgba<u8>> {
+    let len = pixels.len();
+    let mut pixels = core::mem::ManuallyDrop::new(pixels);
+    // SAFETY: `Rgb<u8>` is 3 bytes with alignment 1, so the allocation has the same layout as that many bytes
+    let mut bytes = unsafe { Vec::from_raw_parts(pixels.as_mut_ptr().cast::<u8>(), 3 * len, 3 * pixels.capacity()) };
+    bytes.reserve_exact(len);
+    bytes.resize(4 * len, 0);
+
+    // the pixels from `start` on can be moved at once, when their old bytes end before the new ones begin
+    let mut end = len;
+    loop {
+        let start = (3 * end).div_ceil(4);
+        if start == end {
+            break;
+        }
+        let (head, tail) = bytes.split_at_mut(4 * start);
+        simd::expand_bytes(&head[3 * start..3 * end], alpha, &mut tail[..4 * (end - start)]);
+        end = start;
+    }
+    for i in (0..end).rev() {
+        bytes.copy_within(3 * i..3 * i + 3, 4 * i);
+        bytes[4 * i + 3] = alpha;
+    }
+
+    if bytes.capacity() % 4 != 0 {
+        bytes = bytes.into_boxed_slice().into_vec();
+    }
+    let mut bytes = core::mem::ManuallyDrop::new(bytes);
+    // SAFETY: the length and capacity are multiples of 4, and `Rgba<u8>` is 4 bytes with alignment 1
+    unsafe { Vec::from_raw_parts(bytes.as_mut_ptr().cast::<Rgba<u8>>(), len, bytes.capacity() / 4) }
+}
+
+/// A pixel with straight (not premultiplied) alpha, whose colors can be [bled](bleed) into transparent pixels.
+///
+/// This trait is sealed, and implemented for [`Rgba`], [`Argb`], [`Bgra`], [`Abgr`], and [`GrayA`] with `u8`, `u16`, `f32`, or `f64` components.
+pub trait Bleed: HasAlpha + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {
+        /// Whether alpha is zero
+        fn is_transparent(&self) -> bool;
+        /// Adds the colors scaled to `0.0..=1.0` to `sum`
+        fn add_colors(&self, sum: &mut [f64; 3]);
+        /// Sets the colors from `0.0..=1.0` range
+        fn set_colors(&mut self, colors: [f64; 3]);
+    }
+}
+
+macro_rules! bleed {
+    ($name:ident, [$($bit:ident $i:literal),+]) => {
+        impl<T: BlendComponent> sealed::Sealed for $name<T> {
+            #[inline]
+            fn is_transparent(&self) -> bool {
+                self.a.to_unit() == 0.
+            }
+
+            #[inline]
+            fn add_colors(&self, sum: &mut [f64; 3]) {
+                $(sum[$i] += self.$bit.to_unit();)+
+            }
+
+            #[inline]
+            fn set_colors(&mut self, colors: [f64; 3]) {
+                $(self.$bit = T::from_unit(colors[$i]);)+
+            }
+        }
+        impl<T: BlendComponent> Bleed for $name<T> {}
+    };
+}
+
+bleed!(Rgba, [r 0, g 1, b 2]);
+bleed!(Argb, [r 0, g 1, b 2]);
+bleed!(Bgra, [r 0, g 1, b 2]);
+bleed!(Abgr, [r 0, g 1, b 2]);
+bleed!(GrayA, [v 0]);
+
+/// Replaces the colors of fully transparent pixels with the average of their nearest visible neighbors.
+///
+/// The visible pixels grow one pixel at a time into the transparent ones, like morphological dilation,
+/// until every pixel has a color. Alpha isn't changed, so the image looks the same, but filtering it
+/// (e.g. with [`resize`](crate::resize) in a different color space, or in a GPU texture sampler) doesn't
+/// blend in the colors that were hidden, and PNG compresses the smooth colors better.
+///
+/// If every pixel is transparent, their colors are set to black. Padding between rows isn't touched.
+///
+/// ```
+/// use rgb::Rgba;
+/// use rgb::image::ImageViewMut;
+///
+/// let mut sprite = [Rgba::new(0u8, 255, 0, 0), Rgba::new(255, 0, 0, 255), Rgba::new(1, 2, 3, 0)];
+/// rgb::alpha::bleed(&mut ImageViewMut::new(&mut sprite, 3, 1).unwrap());
+/// assert_eq!(sprite, [Rgba::new(255, 0, 0, 0), Rgba::new(255, 0, 0, 255), Rgba::new(255, 0, 0, 0)]);
+/// ```
+#[cfg(feature = "std")]
+pub fn bleed<P: Bleed>(img: &mut ImageViewMut<'_, P>) {
+    const EMPTY: u8 = 0;
+    const QUEUED: u8 = 1;
+    const FILLED: u8 = 2;
+
+    let (width, height) = (img.width(), img.height());
+    let neighbors = move |x: usize, y: usize| {
+        (y.saturating_sub(1)..(y + 2).min(height))
+            .flat_map(move |ny| (x.saturating_sub(1)..(x + 2).min(width)).map(move |nx| (nx, ny)))
+            .filter(move |&n| n != (x, y))
+    };
+
+    let mut state: Vec<u8> = img.rows().flatten().map(|px| if px.is_transparent() { EMPTY } else { FILLED }).collect();
+    if !state.contains(&FILLED) {
+        img.pixels_mut().for_each(|px| px.set_colors([0.; 3]));
+        return;
+    }
+
+    let mut frontier = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if state[y * width + x] != FILLED {
+                continue;
+            }
+            for (nx, ny) in neighbors(x, y) {
+                if state[ny * width + nx] == EMPTY {
+                    state[ny * width + nx] = QUEUED;
+                    frontier.push((nx, ny));
+                }
+            }
+        }
+    }
+
+    let mut colors = Vec::new();
+    let mut next = Vec::new();
+    while !frontier.is_empty() {
+        // all colors are computed before any is written, so that pixels of the same step don't affect each other
+        colors.clear();
+        colors.extend(frontier.iter().map(|&(x, y)| {
+            let mut sum = [0.; 3];
+            let mut count = 0u32;
+            for (nx, ny) in neighbors(x, y) {
+                if state[ny * width + nx] == FILLED {
+                    img[(nx, ny)].add_colors(&mut sum);
+                    count += 1;
+                }
+            }
+            sum.map(|c| c / f64::from(count))
+        }));
+        for (&(x, y), &c) in frontier.iter().zip(&colors) {
+            img[(x, y)].set_colors(c);
+            state[y * width + x] = FILLED;
+        }
+        for &(x, y) in &frontier {
+            for (nx, ny) in neighbors(x, y) {
+                if state[ny * width + nx] == EMPTY {
+                    state[ny * width + nx] = QUEUED;
+                    next.push((nx, ny));
+                }
+            }
+        }
+        core::mem::swap(&mut frontier, &mut next);
+        next.clear();
+    }
+}
+
+#[test]
+fn add_remove() {
+    use crate::{GrayA, Gray_v09};
+
+    let rgb: std::vec::Vec<_> = (0..40u8).map(|i| Bgr::new_bgr(i, 2 * i, 3 * i)).collect();
+    let mut bgra = vec![Bgra::default(); 40];
+    add_alpha_slice(&rgb, 9, &mut bgra);
+    assert_eq!(bgra[39], Bgra::new_bgra(39, 78, 117, 9));
+    let mut back = vec![Bgr::default(); 40];
+    remove_alpha_slice(&bgra, &mut back);
+    assert_eq!(back, rgb);
+
+    let mut rgba = [Rgba::new(0u16, 0, 0, 0); 2];
+    add_alpha_slice(&[Rgb::new(1u16, 2, 3), Rgb::new(4, 5, 6)], 7, &mut rgba);
+    assert_eq!(rgba[1], Rgba::new(4, 5, 6, 7));
+    // already has alpha
+    let mut replaced = [Rgba::new(0u8, 0, 0, 0)];
+    add_alpha_slice(&[Rgba::new(1u8, 2, 3, 4)], 5, &mut replaced);
+    assert_eq!(replaced[0], Rgba::new(1, 2, 3, 5));
+
+    let mut gray = [Gray_v09::new(0u8); 2];
+    remove_alpha_slice(&[GrayA::new(1u8, 2), GrayA::new(3, 4)], &mut gray);
+    assert_eq!(gray, [Gray_v09::new(1), Gray_v09::new(3)]);
+    let mut bgr = [Bgr::default()];
+    remove_alpha_slice(&[Rgba::new(1u8, 2, 3, 4)], &mut bgr);
+    assert_eq!(bgr[0], Bgr::new_bgr(3, 2, 1));
+}
+
+#[test]
+fn flatten() {
+    let src = [Rgba::new(255u8, 0, 0, 255), Rgba::new(255, 0, 0, 0), Rgba::new(0, 0, 0, 51)];
+    let mut dst = [Rgb::default(); 3];
+    flatten_slice(&src, Rgba::new(255, 255, 255, 255), &mut dst);
+    assert_eq!(dst, [Rgb::new(255, 0, 0), Rgb::new(255, 255, 255), Rgb::new(204, 204, 204)]);
+
+    let mut dst = [Rgb::default()];
+    flatten_slice(&[Bgra::new_bgra(0., 0., 1., 0.5)], Bgra::new_bgra(0., 1., 0., 1.), &mut dst);
+    assert_eq!(dst[0], Rgb::new(0.5, 0.5, 0.));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn add_in_place() {
+    for len in (0..70).chain([1000, 1001]) {
+        let rgb: Vec<_> = (0..len).map(|i| Rgb::new(i as u8, (i / 256) as u8, 7)).collect();
+        let rgba = add_alpha_in_place(rgb.clone(), 200);
+        assert_eq!(rgba.len(), len);
+        assert!(rgba.capacity() >= len);
+        for (a, b) in rgb.iter().zip(&rgba) {
+            assert_eq!(a.with_alpha(200), *b);
+        }
+    }
+
+    let mut rgb = Vec::with_capacity(400);
+    rgb.resize(300, Rgb::new(1, 2, 3));
+    let ptr = rgb.as_ptr() as usize;
+    let rgba = add_alpha_in_place(rgb, 4);
+    assert_eq!(ptr, rgba.as_ptr() as usize);
+    assert_eq!(rgba[299], Rgba::new(1, 2, 3, 4));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn bleeding() {
+    let garbage = |i: usize| Rgba::new(i as u8, 99, 99, 0);
+    // row of 5 with a padding pixel
+    let mut px: Vec<_> = (0..12).map(garbage).collect();
+    px[0] = Rgba::new(255, 0, 0, 255);
+    px[4] = Rgba::new(0, 0, 255, 1);
+    let mut img = ImageViewMut::with_stride(&mut px, 5, 2, 6).unwrap();
+    bleed(&mut img);
+    assert_eq!(img[(1, 0)], Rgba::new(255, 0, 0, 0));
+    assert_eq!(img[(3, 0)], Rgba::new(0, 0, 255, 0));
+    // the average of the red and blue pixels next to it
+    assert_eq!(img[(2, 1)], Rgba::new(128, 0, 128, 0));
+    assert_eq!(img[(4, 0)].a, 1);
+    assert_eq!(px[5], garbage(5));
+
+    let mut px = [Argb::new_argb(0u16, 1, 2, 3); 4];
+    bleed(&mut ImageViewMut::new(&mut px, 2, 2).unwrap());
+    assert_eq!(px, [Argb::new_argb(0, 0, 0, 0); 4]);
+
+    let mut px = [GrayA::new(0.25f32, 1.), GrayA::new(9., 0.), GrayA::new(9., 0.), GrayA::new(0.75, 0.5)];
+    bleed(&mut ImageViewMut::new(&mut px, 2, 2).unwrap());
+    assert_eq!(px, [GrayA::new(0.25, 1.), GrayA::new(0.5, 0.), GrayA::new(0.5, 0.), GrayA::new(0.75, 0.5)]);
+
+    let mut px: [Bgra<u8>; 0] = [];
+    bleed(&mut ImageViewMut::new(&mut px, 0, 3).unwrap());
+}
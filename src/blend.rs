@@ -0,0 +1,386 @@
+//! Blend modes from the [W3C Compositing spec](https://www.w3.org/TR/compositing-1/#blending).
+//!
+//! Blend modes define how colors of a layer mix with colors of the backdrop underneath.
+//! For pixels with alpha, the blended color is then composited over the backdrop using the
+//! usual source-over compositing, so semi-transparent layers blend only partially.
+//!
+//! ```
+//! use rgb::Rgb;
+//! use rgb::blend::{Blend, BlendMode};
+//!
+//! let backdrop = Rgb::<u8>::new(255, 128, 0);
+//! let layer = Rgb::<u8>::new(128, 128, 128);
+//! assert_eq!(layer.blend(backdrop, BlendMode::Multiply), Rgb::new(128, 64, 0));
+//! assert_eq!(layer.blend(backdrop, BlendMode::Screen), Rgb::new(255, 192, 128));
+//! ```
+//!
+//! The math is done in floating point on components scaled to `0.0..=1.0`, regardless of pixel's
+//! memory layout, so `Bgra` blends the same as `Rgba`. Integer results are rounded to the nearest value.
+//! Colors are blended as they are, without linearization, which is how image editors and browsers do it.
+
+use crate::math::sqrt;
+use crate::{Abgr, Argb, Bgr, Bgra, Grb, HetPixel, Rgb, Rgba};
+
+/// How the colors of the source and backdrop are combined.
+///
+/// See [the spec](https://www.w3.org/TR/compositing-1/#blending) for the formulas.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlendMode {
+    /// The source color replaces the backdrop.
+    #[default]
+    Normal,
+    /// The source and backdrop are multiplied. The result is always darker.
+    Multiply,
+    /// The inverted source and backdrop are multiplied. The result is always lighter.
+    Screen,
+    /// Multiplies or screens, depending on the backdrop color.
+    Overlay,
+    /// The darker of the source and backdrop.
+    Darken,
+    /// The lighter of the source and backdrop.
+    Lighten,
+    /// Brightens the backdrop to reflect the source.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the source.
+    ColorBurn,
+    /// Multiplies or screens, depending on the source color.
+    HardLight,
+    /// Darkens or lightens, depending on the source color. A softer version of hard light.
+    SoftLight,
+    /// Absolute difference between the source and backdrop.
+    Difference,
+    /// Like difference, but with lower contrast.
+    Exclusion,
+    /// Hue of the source with saturation and luminosity of the backdrop.
+    Hue,
+    /// Saturation of the source with hue and luminosity of the backdrop.
+    Saturation,
+    /// Hue and saturation of the source with luminosity of the backdrop.
+    Color,
+    /// Luminosity of the source with hue and saturation of the backdrop.
+    Luminosity,
+}
+
+impl BlendMode {
+    /// Blends `[r, g, b]` colors in `0.0..=1.0` range
+    fn blend_rgb(self, cb: [f64; 3], cs: [f64; 3]) -> [f64; 3] {
+        let separable = |f: fn(f64, f64) -> f64| [f(cb[0], cs[0]), f(cb[1], cs[1]), f(cb[2], cs[2])];
+        match self {
+            Self::Normal => cs,
+            Self::Multiply => separable(multiply),
+            Self::Screen => separable(screen),
+            Self::Overlay => separable(|cb, cs| hard_light(cs, cb)),
+            Self::Darken => separable(f64::min),
+            Self::Lighten => separable(f64::max),
+            Self::ColorDodge => separable(color_dodge),
+            Self::ColorBurn => separable(color_burn),
+            Self::HardLight => separable(hard_light),
+            Self::SoftLight => separable(soft_light),
+            Self::Difference => separable(|cb, cs| (cb - cs).abs()),
+            Self::Exclusion => separable(|cb, cs| cb + cs - 2. * cb * cs),
+            Self::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+            Self::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+            Self::Color => set_lum(cs, lum(cb)),
+            Self::Luminosity => set_lum(cb, lum(cs)),
+        }
+    }
+}
+
+#[inline]
+fn multiply(cb: f64, cs: f64) -> f64 {
+    cb * cs
+}
+
+#[inline]
+fn screen(cb: f64, cs: f64) -> f64 {
+    cb + cs - cb * cs
+}
+
+#[inline]
+fn hard_light(cb: f64, cs: f64) -> f64 {
+    if cs <= 0.5 {
+        multiply(cb, 2. * cs)
+    } else {
+        screen(cb, 2. * cs - 1.)
+    }
+}
+
+#[inline]
+fn color_dodge(cb: f64, cs: f64) -> f64 {
+    if cb <= 0. {
+        0.
+    } else if cs >= 1. {
+        1.
+    } else {
+        (cb / (1. - cs)).min(1.)
+    }
+}
+
+#[inline]
+fn color_burn(cb: f64, cs: f64) -> f64 {
+    if cb >= 1. {
+        1.
+    } else if cs <= 0. {
+        0.
+    } else {
+        1. - ((1. - cb) / cs).min(1.)
+    }
+}
+
+#[inline]
+fn soft_light(cb: f64, cs: f64) -> f64 {
+    if cs <= 0.5 {
+        cb - (1. - 2. * cs) * cb * (1. - cb)
+    } else {
+        let d = if cb <= 0.25 {
+            ((16. * cb - 12.) * cb + 4.) * cb
+        } else {
+            sqrt(cb)
+        };
+        cb + (2. * cs - 1.) * (d - cb)
+    }
+}
+
+#[inline]
+fn lum([r, g, b]: [f64; 3]) -> f64 {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+fn clip_color(c: [f64; 3]) -> [f64; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|c| {
+        let mut c = c;
+        if n < 0. {
+            c = l + (c - l) * l / (l - n);
+        }
+        if x > 1. {
+            c = l + (c - l) * (1. - l) / (x - l);
+        }
+        c
+    })
+}
+
+#[inline]
+fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+    let d = l - lum(c);
+    clip_color(c.map(|c| c + d))
+}
+
+#[inline]
+fn sat([r, g, b]: [f64; 3]) -> f64 {
+    r.max(g).max(b) - r.min(g).min(b)
+}
+
+fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+    // indices of the min, mid and max components
+    let (mut min, mut mid, mut max) = (0, 1, 2);
+    if c[min] > c[mid] {
+        core::mem::swap(&mut min, &mut mid);
+    }
+    if c[mid] > c[max] {
+        core::mem::swap(&mut mid, &mut max);
+    }
+    if c[min] > c[mid] {
+        core::mem::swap(&mut min, &mut mid);
+    }
+    let mut out = [0.; 3];
+    if c[max] > c[min] {
+        out[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        out[max] = s;
+    }
+    out
+}
+
+/// A component type that can be blended.
+///
+/// Implemented for `u8`, `u16`, `f32` and `f64`. Integers are treated as fractions of their maximum value.
+pub trait BlendComponent: Copy + 'static {
+    /// Scales the value to `0.0..=1.0` range.
+    fn to_unit(self) -> f64;
+
+    /// Scales the value from `0.0..=1.0` range. Integers are clamped and rounded.
+    fn from_unit(unit: f64) -> Self;
+}
+
+macro_rules! blend_int {
+    ($int:ty) => {
+        impl BlendComponent for $int {
+            #[inline]
+            fn to_unit(self) -> f64 {
+                f64::from(self) / f64::from(<$int>::MAX)
+            }
+
+            #[inline]
+            fn from_unit(unit: f64) -> Self {
+                (unit.clamp(0., 1.) * f64::from(<$int>::MAX) + 0.5) as $int
+            }
+        }
+    };
+}
+
+blend_int!(u8);
+blend_int!(u16);
+
+impl BlendComponent for f32 {
+    #[inline]
+    fn to_unit(self) -> f64 {
+        self.into()
+    }
+
+    #[inline]
+    fn from_unit(unit: f64) -> Self {
+        unit as f32
+    }
+}
+
+impl BlendComponent for f64 {
+    #[inline]
+    fn to_unit(self) -> f64 {
+        self
+    }
+
+    #[inline]
+    fn from_unit(unit: f64) -> Self {
+        unit
+    }
+}
+
+/// An RGB pixel that can be blended with a backdrop pixel using a [`BlendMode`].
+pub trait Blend: HetPixel {
+    /// Blends `self` as the source (top layer) with the `backdrop` pixel underneath.
+    ///
+    /// For pixels with alpha, the blended color is composited over the backdrop
+    /// as specified in the spec: where the backdrop is transparent, the source color is used as-is,
+    /// and the source alpha controls how much of the blended color covers the backdrop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::{Bgra, Rgba};
+    /// use rgb::blend::{Blend, BlendMode};
+    ///
+    /// let backdrop = Bgra::<u8>::new_bgra(0, 0, 255, 255);
+    /// let layer = Bgra::<u8>::new_bgra(255, 0, 0, 255);
+    /// assert_eq!(layer.blend(backdrop, BlendMode::Lighten), Bgra::new_bgra(255, 0, 255, 255));
+    ///
+    /// // semi-transparent layer has half of the effect
+    /// let layer = Rgba::<f32>::new(0., 0., 0., 0.5);
+    /// let backdrop = Rgba::<f32>::new(1., 0.5, 1., 1.);
+    /// assert_eq!(layer.blend(backdrop, BlendMode::Multiply), Rgba::new(0.5, 0.25, 0.5, 1.));
+    /// ```
+    #[must_use]
+    fn blend(self, backdrop: Self, mode: BlendMode) -> Self;
+}
+
+macro_rules! blend_without_alpha {
+    ($name:ident) => {
+        impl<T: BlendComponent> Blend for $name<T> {
+            #[inline]
+            fn blend(self, backdrop: Self, mode: BlendMode) -> Self {
+                let cb = [backdrop.r.to_unit(), backdrop.g.to_unit(), backdrop.b.to_unit()];
+                let cs = [self.r.to_unit(), self.g.to_unit(), self.b.to_unit()];
+                let [r, g, b] = mode.blend_rgb(cb, cs);
+                Self { r: T::from_unit(r), g: T::from_unit(g), b: T::from_unit(b) }
+            }
+        }
+    };
+}
+
+macro_rules! blend_with_alpha {
+    ($name:ident) => {
+        impl<T: BlendComponent> Blend for $name<T> {
+            #[inline]
+            fn blend(self, backdrop: Self, mode: BlendMode) -> Self {
+                let cb = [backdrop.r.to_unit(), backdrop.g.to_unit(), backdrop.b.to_unit()];
+                let cs = [self.r.to_unit(), self.g.to_unit(), self.b.to_unit()];
+                let (ab, as_) = (backdrop.a.to_unit(), self.a.to_unit());
+                let blended = mode.blend_rgb(cb, cs);
+                let out_a = as_ + ab * (1. - as_);
+                if out_a <= 0. {
+                    let zero = T::from_unit(0.);
+                    return Self { r: zero, g: zero, b: zero, a: zero };
+                }
+                let [r, g, b] = [0, 1, 2].map(|i| {
+                    let mixed = (1. - ab) * cs[i] + ab * blended[i];
+                    T::from_unit((mixed * as_ + cb[i] * ab * (1. - as_)) / out_a)
+                });
+                Self { r, g, b, a: T::from_unit(out_a) }
+            }
+        }
+    };
+}
+
+blend_without_alpha!(Rgb);
+blend_without_alpha!(Bgr);
+blend_without_alpha!(Grb);
+
+blend_with_alpha!(Rgba);
+blend_with_alpha!(Argb);
+blend_with_alpha!(Bgra);
+blend_with_alpha!(Abgr);
+
+/// Blends every pixel of `src` with the corresponding pixel of `backdrop`, storing the result in `backdrop`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Argb;
+/// use rgb::blend::{blend_slice, BlendMode};
+///
+/// let mut image = vec![Argb::<u16>::new_argb(65535, 65535, 0, 0); 4];
+/// let layer = vec![Argb::<u16>::new_argb(65535, 0, 65535, 0); 4];
+/// blend_slice(&mut image, &layer, BlendMode::Difference);
+/// assert_eq!(image[0], Argb::new_argb(65535, 65535, 65535, 0));
+/// ```
+pub fn blend_slice<P: Blend>(backdrop: &mut [P], src: &[P], mode: BlendMode) {
+    assert_eq!(backdrop.len(), src.len());
+    for (backdrop, &src) in backdrop.iter_mut().zip(src) {
+        *backdrop = src.blend(*backdrop, mode);
+    }
+}
+
+#[test]
+fn separable_modes() {
+    let b = Rgb::<f64>::new(0.2, 0.5, 1.);
+    let s = Rgb::<f64>::new(0.6, 0.5, 0.);
+    assert_eq!(s.blend(b, BlendMode::Normal), s);
+    assert_eq!(s.blend(b, BlendMode::Darken), Rgb::new(0.2, 0.5, 0.));
+    assert_eq!(s.blend(b, BlendMode::Lighten), Rgb::new(0.6, 0.5, 1.));
+    assert_eq!(s.blend(b, BlendMode::ColorDodge), Rgb::new(0.5, 1., 1.));
+    assert_eq!(s.blend(b, BlendMode::ColorBurn), Rgb::new(0., 0., 1.));
+    assert_eq!(s.blend(b, BlendMode::Overlay), Rgb::new(0.24, 0.5, 1.));
+    assert_eq!(s.blend(b, BlendMode::SoftLight).b, 1.);
+    assert_eq!(Rgb::new(1., 1., 0.5).blend(Rgb::new(0.25, 0.64, 0.3), BlendMode::SoftLight).g, 0.8);
+}
+
+#[test]
+fn non_separable_modes() {
+    let gray = Rgb::<u8>::new(128, 128, 128);
+    let red = Rgb::<u8>::new(255, 0, 0);
+    // gray has no hue or saturation
+    assert_eq!(gray.blend(red, BlendMode::Luminosity), Rgb::new(255, 74, 74));
+    assert_eq!(red.blend(gray, BlendMode::Luminosity), Rgb::new(77, 77, 77));
+    assert_eq!(gray.blend(red, BlendMode::Saturation), Rgb::new(77, 77, 77));
+    assert_eq!(gray.blend(red, BlendMode::Hue), Rgb::new(77, 77, 77));
+    assert_eq!(red.blend(gray, BlendMode::Color), Rgb::new(255, 74, 74));
+    assert_eq!(red.blend(gray, BlendMode::Hue), gray);
+    // same regardless of the memory layout
+    let bgr = Bgr::<u8>::new_bgr(0, 0, 255).blend(Bgr::new_bgr(128, 128, 128), BlendMode::Color);
+    assert_eq!(Rgb::from(bgr), Rgb::new(255, 74, 74));
+}
+
+#[test]
+fn transparent_backdrop() {
+    let src = Rgba::<u8>::new(10, 20, 30, 40);
+    let clear = Rgba::<u8>::new(0, 0, 0, 0);
+    assert_eq!(src.blend(clear, BlendMode::Multiply), src);
+    assert_eq!(clear.blend(clear, BlendMode::Screen), clear);
+    assert_eq!(clear.blend(src, BlendMode::Screen), src);
+}
@@ -1,4 +1,8 @@
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, GrayA44, Gray_v09, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, GrayA44, Gray_v09, Grb, Indexed, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{Argb1555, Bgr565, Rgb555, Rgb565, Rgba4444};
+use crate::{Bgr10A2, R11G11B10Float, Rgb10A2, Rgb9E5};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
 
 macro_rules! bytemuck {
     ($name:ident) => {
@@ -24,7 +28,27 @@ bytemuck!(Argb);
 bytemuck!(Bgra);
 bytemuck!(Abgr);
 bytemuck!(GrayA);
+bytemuck!(Ycbcr);
+bytemuck!(Ycbcra);
+bytemuck!(Rgbx);
+bytemuck!(Bgrx);
+bytemuck!(Xrgb);
+bytemuck!(Xbgr);
+bytemuck!(PremulRgba);
+bytemuck!(PremulArgb);
+bytemuck!(PremulBgra);
+bytemuck!(PremulAbgr);
+bytemuck!(Indexed);
 bytemuck_no_generic!(GrayA44);
+bytemuck_no_generic!(Rgb565);
+bytemuck_no_generic!(Bgr565);
+bytemuck_no_generic!(Rgb555);
+bytemuck_no_generic!(Argb1555);
+bytemuck_no_generic!(Rgba4444);
+bytemuck_no_generic!(Rgb10A2);
+bytemuck_no_generic!(Bgr10A2);
+bytemuck_no_generic!(R11G11B10Float);
+bytemuck_no_generic!(Rgb9E5);
 
 use crate::formats::gray_alpha::GrayAlpha_v08;
 bytemuck!(GrayAlpha_v08);
@@ -97,3 +121,13 @@ fn test_component_bytes_capable() {
         core::mem::size_of::<u8>()
     );
 }
+
+#[test]
+fn padded_cast() {
+    let bytes = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    let bgrx: &[Bgrx<u8>] = bytemuck::cast_slice(&bytes);
+    assert_eq!(bgrx[1], Bgrx::new_bgrx(5, 6, 7, 0));
+    assert_eq!(bgrx[1].x, 8);
+    let back: &[u8] = bytemuck::cast_slice(bgrx);
+    assert_eq!(back, bytes);
+}
@@ -0,0 +1,312 @@
+//! Porter-Duff compositing of pixels with alpha.
+//!
+//! The source pixel is composited with the destination (backdrop) pixel using
+//! one of the [`Operator`]s. Straight-alpha pixels like [`Rgba`] and premultiplied
+//! pixels like [`PremulRgba`] are both supported, and each is computed using its own
+//! formula, so there's no need to convert between them first.
+//!
+//! ```
+//! use rgb::Rgba;
+//! use rgb::composite::{Composite, Operator};
+//!
+//! let src = Rgba::<u8>::new(255, 0, 0, 128);
+//! let dst = Rgba::<u8>::new(0, 0, 255, 255);
+//! assert_eq!(src.composite(dst, Operator::SourceOver), Rgba::new(128, 0, 127, 255));
+//! ```
+//!
+//! Integer components are computed exactly, and the result is rounded to the nearest integer once.
+//! Results of `u8` and `u16` pixels don't depend on floating-point precision.
+
+use crate::{Abgr, Argb, Bgra, GrayA, HasAlpha, Rgba};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+
+/// A Porter-Duff compositing operator, as defined in the [W3C Compositing spec](https://www.w3.org/TR/compositing-1/#porterduffcompositingoperators).
+///
+/// In descriptions below "source" is the pixel being drawn, and "destination" is the pixel already in the image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operator {
+    /// Neither source nor destination is shown. The result is transparent.
+    Clear,
+    /// Only the source is shown.
+    #[doc(alias = "Source")]
+    Copy,
+    /// Only the destination is shown.
+    Destination,
+    /// The source is placed over the destination. This is the usual alpha blending.
+    SourceOver,
+    /// The destination is placed over the source.
+    DestinationOver,
+    /// The source is shown only where the destination is.
+    SourceIn,
+    /// The destination is shown only where the source is.
+    DestinationIn,
+    /// The source is shown only where the destination isn't.
+    SourceOut,
+    /// The destination is shown only where the source isn't.
+    DestinationOut,
+    /// The source is placed over the destination, but only where the destination is.
+    SourceAtop,
+    /// The destination is placed over the source, but only where the source is.
+    DestinationAtop,
+    /// Only the non-overlapping parts of the source and destination are shown.
+    Xor,
+    /// The source and destination are added together, clamped to the maximum.
+    #[doc(alias = "Plus")]
+    #[doc(alias = "Add")]
+    PlusLighter,
+}
+
+impl Operator {
+    /// Porter-Duff `(Fa, Fb)` factors, as fractions of `one`
+    #[inline]
+    fn factors<T>(self, src_a: T, dst_a: T, zero: T, one: T) -> (T, T)
+    where
+        T: Copy + core::ops::Sub<Output = T>,
+    {
+        match self {
+            Self::Clear => (zero, zero),
+            Self::Copy => (one, zero),
+            Self::Destination => (zero, one),
+            Self::SourceOver => (one, one - src_a),
+            Self::DestinationOver => (one - dst_a, one),
+            Self::SourceIn => (dst_a, zero),
+            Self::DestinationIn => (zero, src_a),
+            Self::SourceOut => (one - dst_a, zero),
+            Self::DestinationOut => (zero, one - src_a),
+            Self::SourceAtop => (dst_a, one - src_a),
+            Self::DestinationAtop => (one - dst_a, src_a),
+            Self::Xor => (one - dst_a, one - src_a),
+            Self::PlusLighter => (one, one),
+        }
+    }
+}
+
+/// A component type that can be used in compositing.
+///
+/// Implemented for `u8`, `u16`, `f32` and `f64`. Integers are treated as fractions of their maximum value.
+pub trait CompositeComponent: Copy + 'static {
+    /// Composites straight (not premultiplied) color and alpha components of the source and destination.
+    ///
+    /// Returns color and alpha components of the result. Fully transparent results have all components set to `0`.
+    fn composite_straight<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self);
+
+    /// Composites premultiplied color and alpha components of the source and destination.
+    ///
+    /// Returns premultiplied color and alpha components of the result.
+    fn composite_premultiplied<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self);
+}
+
+macro_rules! composite_int {
+    ($int:ty, $wide:ty) => {
+        impl CompositeComponent for $int {
+            fn composite_straight<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self) {
+                const MAX: $wide = <$int>::MAX as $wide;
+                let (sa, da) = (<$wide>::from(src_a), <$wide>::from(dst_a));
+                let (fa, fb) = op.factors(sa, da, 0, MAX);
+                // alpha is scaled by MAX², colors by MAX³
+                let mut out_a = sa * fa + da * fb;
+                if op == Operator::PlusLighter {
+                    out_a = out_a.min(MAX * MAX);
+                }
+                if out_a == 0 {
+                    return ([0; N], 0);
+                }
+                let mut out = [0; N];
+                for ((out, &s), &d) in out.iter_mut().zip(&src).zip(&dst) {
+                    let mut c = <$wide>::from(s) * sa * fa + <$wide>::from(d) * da * fb;
+                    if op == Operator::PlusLighter {
+                        c = c.min(MAX * MAX * MAX);
+                    }
+                    *out = ((c + out_a / 2) / out_a).min(MAX) as $int;
+                }
+                (out, ((out_a + MAX / 2) / MAX) as $int)
+            }
+
+            fn composite_premultiplied<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self) {
+                const MAX: $wide = <$int>::MAX as $wide;
+                let (sa, da) = (<$wide>::from(src_a), <$wide>::from(dst_a));
+                let (fa, fb) = op.factors(sa, da, 0, MAX);
+                // everything is scaled by MAX²
+                let blend = |s: Self, d: Self| {
+                    let c = <$wide>::from(s) * fa + <$wide>::from(d) * fb;
+                    ((c.min(MAX * MAX) + MAX / 2) / MAX) as $int
+                };
+                let mut out = [0; N];
+                for ((out, &s), &d) in out.iter_mut().zip(&src).zip(&dst) {
+                    *out = blend(s, d);
+                }
+                (out, blend(src_a, dst_a))
+            }
+        }
+    };
+}
+
+macro_rules! composite_float {
+    ($float:ty) => {
+        impl CompositeComponent for $float {
+            fn composite_straight<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self) {
+                let (fa, fb) = op.factors(src_a, dst_a, 0., 1.);
+                let mut out_a = src_a * fa + dst_a * fb;
+                if op == Operator::PlusLighter {
+                    out_a = out_a.min(1.);
+                }
+                if out_a <= 0. {
+                    return ([0.; N], 0.);
+                }
+                let mut out = [0.; N];
+                for ((out, &s), &d) in out.iter_mut().zip(&src).zip(&dst) {
+                    let mut c = s * src_a * fa + d * dst_a * fb;
+                    if op == Operator::PlusLighter {
+                        c = c.min(1.);
+                    }
+                    *out = c / out_a;
+                }
+                (out, out_a)
+            }
+
+            fn composite_premultiplied<const N: usize>(src: [Self; N], src_a: Self, dst: [Self; N], dst_a: Self, op: Operator) -> ([Self; N], Self) {
+                let (fa, fb) = op.factors(src_a, dst_a, 0., 1.);
+                let blend = |s: Self, d: Self| {
+                    let c = s * fa + d * fb;
+                    if op == Operator::PlusLighter { c.min(1.) } else { c }
+                };
+                let mut out = [0.; N];
+                for ((out, &s), &d) in out.iter_mut().zip(&src).zip(&dst) {
+                    *out = blend(s, d);
+                }
+                (out, blend(src_a, dst_a))
+            }
+        }
+    };
+}
+
+composite_int!(u8, u32);
+composite_int!(u16, u64);
+composite_float!(f32);
+composite_float!(f64);
+
+/// A pixel with alpha that can be composited using Porter-Duff operators.
+pub trait Composite: HasAlpha {
+    /// Composites `self` as the source pixel with the `dst` destination (backdrop) pixel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::{GrayA, PremulRgba};
+    /// use rgb::composite::{Composite, Operator};
+    ///
+    /// let src = GrayA::<f32>::new(1., 0.5);
+    /// let dst = GrayA::<f32>::new(0., 1.);
+    /// assert_eq!(src.composite(dst, Operator::SourceOver), GrayA::new(0.5, 1.));
+    /// assert_eq!(src.composite(dst, Operator::DestinationOut), GrayA::new(0., 0.5));
+    ///
+    /// let src = PremulRgba::<u8>::new(200, 0, 0, 200);
+    /// let dst = PremulRgba::<u8>::new(0, 100, 0, 100);
+    /// assert_eq!(src.composite(dst, Operator::PlusLighter), PremulRgba::new(200, 100, 0, 255));
+    /// ```
+    #[must_use]
+    fn composite(self, dst: Self, op: Operator) -> Self;
+}
+
+macro_rules! composite_pixel {
+    ($name:ident, [$($bit:ident),*], $method:ident) => {
+        impl<T: CompositeComponent> Composite for $name<T> {
+            #[inline]
+            fn composite(self, dst: Self, op: Operator) -> Self {
+                let ([$($bit),*], a) = T::$method([$(self.$bit),*], self.a, [$(dst.$bit),*], dst.a, op);
+                Self { $($bit,)* a }
+            }
+        }
+    };
+}
+
+composite_pixel!(Rgba, [r, g, b], composite_straight);
+composite_pixel!(Argb, [r, g, b], composite_straight);
+composite_pixel!(Bgra, [b, g, r], composite_straight);
+composite_pixel!(Abgr, [b, g, r], composite_straight);
+composite_pixel!(GrayA, [v], composite_straight);
+
+composite_pixel!(PremulRgba, [r, g, b], composite_premultiplied);
+composite_pixel!(PremulArgb, [r, g, b], composite_premultiplied);
+composite_pixel!(PremulBgra, [b, g, r], composite_premultiplied);
+composite_pixel!(PremulAbgr, [b, g, r], composite_premultiplied);
+
+/// Composites every pixel of `src` with the corresponding pixel of `dst`, storing the result in `dst`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Bgra;
+/// use rgb::composite::{composite_slice, Operator};
+///
+/// let mut image = vec![Bgra::<u8>::new_bgra(255, 255, 255, 255); 4];
+/// let layer = vec![Bgra::<u8>::new_bgra(0, 0, 0, 51); 4];
+/// composite_slice(&mut image, &layer, Operator::SourceOver);
+/// assert_eq!(image[0], Bgra::new_bgra(204, 204, 204, 255));
+/// ```
+pub fn composite_slice<P: Composite>(dst: &mut [P], src: &[P], op: Operator) {
+    assert_eq!(dst.len(), src.len());
+    for (dst, &src) in dst.iter_mut().zip(src) {
+        *dst = src.composite(*dst, op);
+    }
+}
+
+#[cfg(test)]
+const ALL_OPERATORS: [Operator; 13] = [
+    Operator::Clear, Operator::Copy, Operator::Destination, Operator::SourceOver, Operator::DestinationOver,
+    Operator::SourceIn, Operator::DestinationIn, Operator::SourceOut, Operator::DestinationOut,
+    Operator::SourceAtop, Operator::DestinationAtop, Operator::Xor, Operator::PlusLighter,
+];
+
+#[test]
+fn composite_int_matches_exact_float() {
+    use crate::Pixel;
+
+    let values = [0u8, 1, 2, 63, 127, 128, 200, 254, 255];
+    for op in ALL_OPERATORS {
+        for &sa in &values {
+            for &da in &values {
+                for &s in &values {
+                    for &d in &values {
+                        let src = Rgba::new(s, d, 255 - s, sa);
+                        let dst = Rgba::new(d, s, 0, da);
+                        let res = src.composite(dst, op);
+                        let fsrc = src.map(|c| f64::from(c) / 255.);
+                        let fdst = dst.map(|c| f64::from(c) / 255.);
+                        let fres = fsrc.composite(fdst, op).map(|c| c * 255.);
+                        // the result must be the exact value rounded
+                        let diff = res.map(f64::from) - fres;
+                        assert!(diff.to_array().iter().all(|d| d.abs() <= 0.5 + 1e-9), "{op:?} {src} {dst} = {res}, expected {fres}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn composite_premultiplied_matches_straight() {
+    for op in ALL_OPERATORS {
+        let src = Rgba::<f64>::new(1., 0.5, 0.25, 0.5);
+        let dst = Rgba::<f64>::new(0.25, 1., 0., 0.75);
+        let straight = src.composite(dst, op);
+        let premul = PremulRgba::from(src).composite(PremulRgba::from(dst), op);
+        let diff = Rgba::from(premul) - straight;
+        assert!(diff.r.abs() < 1e-12 && diff.g.abs() < 1e-12 && diff.b.abs() < 1e-12 && diff.a.abs() < 1e-12, "{op:?}");
+    }
+}
+
+#[test]
+fn composite_u16() {
+    let src = Rgba::<u16>::new(65535, 0, 0, 32768);
+    let dst = Rgba::<u16>::new(0, 65535, 0, 65535);
+    assert_eq!(src.composite(dst, Operator::SourceOver), Rgba::new(32768, 32767, 0, 65535));
+    assert_eq!(src.composite(dst, Operator::Clear), Rgba::new(0, 0, 0, 0));
+    assert_eq!(src.composite(dst, Operator::Copy), src);
+    assert_eq!(src.composite(dst, Operator::Destination), dst);
+}
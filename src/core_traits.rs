@@ -1,4 +1,8 @@
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::array::TryFromSliceError;
 use core::fmt;
 use core::iter::Sum;
@@ -454,6 +458,7 @@ trait_impls_without_alpha!(Rgb, 3, [r => r, g => g, b => b], "rgb({r},{g},{b})",
 trait_impls_without_alpha!(Bgr, 3, [b => b, g => g, r => r], "bgr({b},{g},{r})", "#{r:0w$X}{g:0w$X}{b:0w$X}", "#{r:0w$x}{g:0w$x}{b:0w$x}");
 trait_impls_without_alpha!(Grb, 3, [g => g, r => r, b => b], "grb({g},{r},{b})", "#{r:0w$X}{g:0w$X}{b:0w$X}", "#{r:0w$x}{g:0w$x}{b:0w$x}");
 trait_impls_without_alpha!(Gray_v09, 1, [v => v], "gray({v})", "gray(#{v:0w$X})", "gray(#{v:0w$x})");
+trait_impls_without_alpha!(Ycbcr, 3, [y => y, cb => cb, cr => cr], "ycbcr({y},{cb},{cr})", "ycbcr(#{y:0w$X}{cb:0w$X}{cr:0w$X})", "ycbcr(#{y:0w$x}{cb:0w$x}{cr:0w$x})");
 trait_impls_without_alpha!(Rgbw, 4, [r => r, g => g, b => b, white => w], "rgbw({r},{g},{b},{white})", "#{r:0w$X}{g:0w$X}{b:0w$X}{white:0w$X}", "#{r:0w$x}{g:0w$x}{b:0w$x}{white:0w$x}");
 
 use crate::formats::gray::Gray_v08;
@@ -464,6 +469,71 @@ trait_impls_with_alpha!(Argb, 4, [a => a, r => r, g => g, b => b], "argb({a},{r}
 trait_impls_with_alpha!(Bgra, 4, [b => b, g => g, r => r, a => a], "bgra({b},{g},{r},{a})", "#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X}", "#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x}");
 trait_impls_with_alpha!(Abgr, 4, [a => a, b => b, g => g, r => r], "abgr({a},{b},{g},{r})", "#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X}", "#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x}");
 trait_impls_with_alpha!(GrayA, 2, [v => v, a => a], "graya({v},{a})", "graya(#{v:0w$X}{a:0w$X})", "graya(#{v:0w$x}{a:0w$x})");
+trait_impls_with_alpha!(Ycbcra, 4, [y => y, cb => cb, cr => cr, a => a], "ycbcra({y},{cb},{cr},{a})", "ycbcra(#{y:0w$X}{cb:0w$X}{cr:0w$X}{a:0w$X})", "ycbcra(#{y:0w$x}{cb:0w$x}{cr:0w$x}{a:0w$x})");
+trait_impls_with_alpha!(PremulRgba, 4, [r => r, g => g, b => b, a => a], "premul_rgba({r},{g},{b},{a})", "premul(#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X})", "premul(#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x})");
+trait_impls_with_alpha!(PremulArgb, 4, [a => a, r => r, g => g, b => b], "premul_argb({a},{r},{g},{b})", "premul(#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X})", "premul(#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x})");
+trait_impls_with_alpha!(PremulBgra, 4, [b => b, g => g, r => r, a => a], "premul_bgra({b},{g},{r},{a})", "premul(#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X})", "premul(#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x})");
+trait_impls_with_alpha!(PremulAbgr, 4, [a => a, b => b, g => g, r => r], "premul_abgr({a},{b},{g},{r})", "premul(#{r:0w$X}{g:0w$X}{b:0w$X}{a:0w$X})", "premul(#{r:0w$x}{g:0w$x}{b:0w$x}{a:0w$x})");
+
+/// The padding isn't compared, hashed, or printed
+macro_rules! trait_impls_padded {
+    ($name:ident, [$($printas:ident => $bit:tt),*], $display:literal) => {
+        impl<T: PartialEq> PartialEq for $name<T> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                $(self.$bit == other.$bit)&&*
+            }
+        }
+
+        impl<T: Eq> Eq for $name<T> {}
+
+        impl<T: PartialOrd> PartialOrd for $name<T> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                ($(&self.$bit),*).partial_cmp(&($(&other.$bit),*))
+            }
+        }
+
+        impl<T: Ord> Ord for $name<T> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                ($(&self.$bit),*).cmp(&($(&other.$bit),*))
+            }
+        }
+
+        impl<T: Hash> Hash for $name<T> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$bit.hash(state);)*
+            }
+        }
+
+        impl<T: fmt::Display> fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, $display, $($printas = self.$bit),*)
+            }
+        }
+
+        impl<T: fmt::UpperHex> fmt::UpperHex for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let width = 2 * core::mem::size_of::<T>();
+                write!(f, "#{r:0w$X}{g:0w$X}{b:0w$X}", r = self.r, g = self.g, b = self.b, w = width)
+            }
+        }
+
+        impl<T: fmt::LowerHex> fmt::LowerHex for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let width = 2 * core::mem::size_of::<T>();
+                write!(f, "#{r:0w$x}{g:0w$x}{b:0w$x}", r = self.r, g = self.g, b = self.b, w = width)
+            }
+        }
+    };
+}
+
+trait_impls_padded!(Rgbx, [r => r, g => g, b => b], "rgbx({r},{g},{b})");
+trait_impls_padded!(Bgrx, [b => b, g => g, r => r], "bgrx({b},{g},{r})");
+trait_impls_padded!(Xrgb, [r => r, g => g, b => b], "xrgb({r},{g},{b})");
+trait_impls_padded!(Xbgr, [b => b, g => g, r => r], "xbgr({b},{g},{r})");
 
 use crate::formats::gray_alpha::GrayAlpha_v08;
 trait_impls_with_alpha!(GrayAlpha_v08, 2, [v => 0, a => 1], "graya_v0.8({v},{a})", "graya_v0.8(#{v:0w$X}{a:0w$X})", "graya_v0.8(#{v:0w$x}{a:0w$x})");
@@ -491,3 +561,22 @@ fn convert_array() {
     assert_eq!(BGRA8::from([4, 3, 2, 1]), BGRA8::new_bgra(4, 3, 2, 1));
     assert_eq!(Into::<[u8; 4]>::into(BGRA8::new_bgra(4, 3, 2, 1)), [4, 3, 2, 1]);
 }
+
+#[test]
+fn padded_ignores_padding() {
+    extern crate std;
+    use core::hash::BuildHasher;
+    use std::collections::hash_map::RandomState;
+
+    let a = Bgrx::new_bgrx(1u8, 2, 3, 0);
+    let b = Bgrx::new_bgrx(1u8, 2, 3, 255);
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), Ordering::Equal);
+    assert!(Rgbx::new(1, 2, 3, 9) < Rgbx::new(1, 2, 4, 0));
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(a), state.hash_one(b));
+    assert_ne!(a, Bgrx::new_bgrx(1, 2, 4, 0));
+
+    assert_eq!("xrgb(1,2,3)", &std::format!("{}", Xrgb::new_xrgb(255, 1, 2, 3)));
+    assert_eq!("#0302FF", &std::format!("{:X}", Xbgr::new_xbgr(7u8, 255, 2, 3)));
+}
@@ -0,0 +1,839 @@
+//! Parsing of CSS-like color syntax, such as `#ff8800` or `rgb(255 136 0 / 50%)`.
+//!
+//! The pixel types implement [`FromStr`], so colors can be parsed with [`str::parse()`]:
+//!
+//! ```
+//! use rgb::{Rgb, Rgba};
+//!
+//! let px: Rgb<u8> = "#0064FF".parse().unwrap();
+//! assert_eq!(px, Rgb::new(0, 100, 255));
+//!
+//! let px: Rgba<u8> = "rgb(0 100 255 / 50%)".parse().unwrap();
+//! assert_eq!(px, Rgba::new(0, 100, 255, 128));
+//! ```
+//!
+//! Supported syntax:
+//!
+//! * Hex `#rgb`, `#rgba`, `#rrggbb`, and `#rrggbbaa`.
+//! * `rgb()` and `rgba()` with comma-separated (legacy) or space-separated (modern) arguments, with optional `/ alpha` in the modern syntax.
+//! * `hsl()` and `hsla()`, with hue in degrees (or with `deg`, `rad`, `grad`, `turn` units), and saturation and lightness in percent.
+//! * [Named colors](crate::named), such as `rebeccapurple`, and `transparent`.
+//! * `gray()` and `graya()` for the grayscale types.
+//!
+//! As in CSS, plain numbers (without `%`) are `0`–`255` for colors and `0.0`–`1.0` for alpha, whatever the
+//! component type, so `rgba(255, 0, 0, 0.5)` is half-transparent red as `Rgba<u8>` and `Rgba<f32>` alike.
+//! Percentages, like `50%`, are fractions of the full range.
+//!
+//! ```
+//! use rgb::{Rgb, Rgba};
+//!
+//! let px: Rgba<u8> = "rgba(255, 0, 0, 0.5)".parse().unwrap();
+//! assert_eq!(px, Rgba::new(255, 0, 0, 128));
+//!
+//! let px: Rgb<f32> = "rgb(255 51 0)".parse().unwrap();
+//! assert_eq!(px, Rgb::new(1., 0.2, 0.));
+//! ```
+//!
+//! `Display` (`{}`) prints numbers in the component's own range instead, e.g. `rgba(1,2,3,4)` for `u8`
+//! and `rgba(0.1,0.2,1.5,0.25)` for `f32`. That's valid CSS with the same meaning only for `Rgb<u8>` and `Gray_v09<u8>`,
+//! so only these round-trip through `to_string().parse()`. [`parse_display()`] parses the output of all the types back exactly.
+//! Hex formatting (`{:X}`/`{:x}`) round-trips with the CSS parser too.
+//!
+//! ```
+//! use rgb::{Rgba, RGBA8};
+//! use rgb::css::parse_display;
+//!
+//! let px = RGBA8::new(1, 2, 3, 4);
+//! assert_eq!(px, parse_display(&px.to_string()).unwrap());
+//! assert_eq!(px, format!("{px:X}").parse().unwrap());
+//!
+//! let px = Rgba::<f32>::new(0.1, 0.2, 1.5, 0.25);
+//! assert_eq!(px, parse_display(&px.to_string()).unwrap());
+//! ```
+
+use crate::formats::gray::Gray_v09;
+use crate::{GrayA, Rgb, Rgba};
+use core::fmt;
+use core::ops::Range;
+use core::str::FromStr;
+
+/// Error returned when a color can't be parsed.
+///
+/// It has the byte range of the invalid part of the input.
+///
+/// ```
+/// use rgb::RGB8;
+/// use rgb::css::ParseColorErrorKind;
+///
+/// let err = "rgb(0, 1OO, 0)".parse::<RGB8>().unwrap_err();
+/// assert_eq!(err.kind(), ParseColorErrorKind::InvalidNumber);
+/// assert_eq!(err.span(), 7..10);
+/// assert_eq!(err.to_string(), "invalid number at 7..10");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError {
+    kind: ParseColorErrorKind,
+    span: Range<usize>,
+}
+
+/// The reason why a color couldn't be parsed. See [`ParseColorError::kind()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseColorErrorKind {
+    /// The string is empty or has only whitespace
+    Empty,
+    /// The string isn't any of the supported color formats, e.g. it's an unknown function name
+    UnknownFormat,
+    /// The function call is missing the closing `)`, or has something after it
+    InvalidSyntax,
+    /// A character is not a hex digit
+    InvalidHexDigit,
+    /// The hex color has an unsupported number of digits
+    InvalidHexLength,
+    /// The argument isn't a number, or has a wrong unit
+    InvalidNumber,
+    /// The value doesn't fit in the component type
+    OutOfRange,
+    /// Not enough arguments
+    MissingArgument,
+    /// Too many arguments, or alpha without the `/` in the modern syntax
+    TooManyArguments,
+    /// The arguments mix commas and spaces, or use `/` with commas
+    MixedSeparators,
+    /// The color is not opaque, but the pixel type doesn't have an alpha component
+    UnexpectedAlpha,
+}
+
+impl ParseColorError {
+    #[inline]
+    const fn new(kind: ParseColorErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    /// What went wrong
+    #[inline]
+    #[must_use]
+    pub fn kind(&self) -> ParseColorErrorKind {
+        self.kind
+    }
+
+    /// Byte range of the parsed string that contains the error
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseColorError {
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            ParseColorErrorKind::Empty => "empty color string",
+            ParseColorErrorKind::UnknownFormat => "unknown color format",
+            ParseColorErrorKind::InvalidSyntax => "invalid syntax",
+            ParseColorErrorKind::InvalidHexDigit => "invalid hex digit",
+            ParseColorErrorKind::InvalidHexLength => "hex color must have 3, 4, 6, or 8 digits",
+            ParseColorErrorKind::InvalidNumber => "invalid number",
+            ParseColorErrorKind::OutOfRange => "value out of range",
+            ParseColorErrorKind::MissingArgument => "missing argument",
+            ParseColorErrorKind::TooManyArguments => "too many arguments",
+            ParseColorErrorKind::MixedSeparators => "mixed argument separators",
+            ParseColorErrorKind::UnexpectedAlpha => "unexpected alpha",
+        })?;
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl core::error::Error for ParseColorError {}
+
+use ParseColorErrorKind as E;
+
+/// A pixel type that can be parsed from a CSS-like color string.
+///
+/// Implemented for [`Rgb`], [`Rgba`], [`Gray_v09`](crate::Gray_v09) and [`GrayA`] with `u8` or `f32` components.
+pub trait FromCss: Sized {
+    /// Parses the color. See [the module docs](self) for the supported syntax.
+    fn from_css(s: &str) -> Result<Self, ParseColorError>;
+
+    /// Parses the color like [`from_css()`](Self::from_css), but with plain numbers in the component's own range,
+    /// as printed by `Display`.
+    fn from_display(s: &str) -> Result<Self, ParseColorError>;
+}
+
+/// Parses a CSS-like color string, such as `#ff8800`, `rgb(255, 136, 0)`, or `hsl(32deg 100% 50% / 0.5)`.
+///
+/// This is the same as [`str::parse()`], and works without `std`.
+///
+/// ```
+/// use rgb::{Rgb, Rgba};
+///
+/// let px: Rgba<u8> = rgb::css::parse_css("hsl(120deg 100% 25% / 100%)").unwrap();
+/// assert_eq!(px, Rgba::new(0, 128, 0, 255));
+///
+/// let px = rgb::css::parse_css::<Rgb<f32>>("rgb(100%, 50%, 0%)").unwrap();
+/// assert_eq!(px, Rgb::new(1., 0.5, 0.));
+/// ```
+#[inline]
+pub fn parse_css<P: FromCss>(s: &str) -> Result<P, ParseColorError> {
+    P::from_css(s)
+}
+
+/// Parses the output of `Display`, where plain numbers are in the component's own range, e.g. `rgba(255,0,0,128)` for `Rgba<u8>`.
+///
+/// The rest of the syntax is the same as [`parse_css()`], but CSS colors like `rgba(255, 0, 0, 0.5)` should be parsed with that function.
+///
+/// ```
+/// use rgb::Rgba;
+///
+/// let px: Rgba<u8> = rgb::css::parse_display("rgba(255,0,0,128)").unwrap();
+/// assert_eq!(px, Rgba::new(255, 0, 0, 128));
+/// ```
+#[inline]
+pub fn parse_display<P: FromCss>(s: &str) -> Result<P, ParseColorError> {
+    P::from_display(s)
+}
+
+/// How plain numbers are interpreted
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Numbers {
+    /// `0`–`255` for colors, and `0`–`1` for alpha
+    Css,
+    /// The component's own range, as printed by `Display`
+    Own,
+}
+
+/// Component types supported by the parser
+pub trait CssComponent: Copy + 'static {
+    #[doc(hidden)]
+    const OPAQUE: Self;
+
+    /// A color number in CSS `0`–`255` range
+    #[doc(hidden)]
+    fn from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError>;
+
+    /// An alpha number in CSS `0`–`1` range
+    #[doc(hidden)]
+    fn alpha_from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError>;
+
+    /// A number in the component's own range, as printed by `Display`
+    #[doc(hidden)]
+    fn from_own_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError>;
+
+    /// A fraction of the full range, from a percentage or 8-bit hex
+    #[doc(hidden)]
+    fn from_fraction(fraction: f64, span: Range<usize>) -> Result<Self, ParseColorError>;
+}
+
+impl CssComponent for u8 {
+    const OPAQUE: Self = 255;
+
+    fn from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        let n = parse_number(text, span.clone())?;
+        if !(0. ..=255.).contains(&n) {
+            return Err(ParseColorError::new(E::OutOfRange, span));
+        }
+        Ok((n + 0.5) as u8)
+    }
+
+    fn alpha_from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        let n = parse_number(text, span.clone())?;
+        Self::from_fraction(n, span)
+    }
+
+    fn from_own_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        Self::from_number(text, span)
+    }
+
+    fn from_fraction(fraction: f64, span: Range<usize>) -> Result<Self, ParseColorError> {
+        if !(0. ..=1.).contains(&fraction) {
+            return Err(ParseColorError::new(E::OutOfRange, span));
+        }
+        Ok((fraction * 255. + 0.5) as u8)
+    }
+}
+
+impl CssComponent for f32 {
+    const OPAQUE: Self = 1.;
+
+    fn from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        Ok((parse_number(text, span)? / 255.) as f32)
+    }
+
+    fn alpha_from_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        Self::from_own_number(text, span)
+    }
+
+    fn from_own_number(text: &str, span: Range<usize>) -> Result<Self, ParseColorError> {
+        // parsed directly, so that it round-trips exactly
+        parse_number(text, span.clone())?;
+        text.parse().map_err(|_| ParseColorError::new(E::InvalidNumber, span))
+    }
+
+    fn from_fraction(fraction: f64, _: Range<usize>) -> Result<Self, ParseColorError> {
+        Ok(fraction as f32)
+    }
+}
+
+/// Only CSS-like numbers, not `inf` or `NaN`
+fn parse_number(text: &str, span: Range<usize>) -> Result<f64, ParseColorError> {
+    let err = || ParseColorError::new(E::InvalidNumber, span.clone());
+    let mut digits = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if let Some(pos) = digits.find(['e', 'E']) {
+        let exp = &digits[pos + 1..];
+        let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+        if exp.is_empty() || !exp.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(err());
+        }
+        digits = &digits[..pos];
+    }
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if (int.is_empty() && frac.is_empty()) || !int.bytes().chain(frac.bytes()).all(|c| c.is_ascii_digit()) {
+        return Err(err());
+    }
+    text.parse().map_err(|_| err())
+}
+
+/// A function argument
+#[derive(Clone)]
+struct Arg<'a> {
+    text: &'a str,
+    span: Range<usize>,
+}
+
+impl<'a> Arg<'a> {
+    fn percentage(&self) -> Option<(&'a str, Range<usize>)> {
+        self.text.strip_suffix('%').map(|num| (num, self.span.start..self.span.end - 1))
+    }
+
+    /// A color component, either a number or a percentage
+    fn component<T: CssComponent>(&self, numbers: Numbers) -> Result<T, ParseColorError> {
+        match (self.percentage(), numbers) {
+            (Some((num, span)), _) => T::from_fraction(parse_number(num, span)? / 100., self.span.clone()),
+            (None, Numbers::Css) => T::from_number(self.text, self.span.clone()),
+            (None, Numbers::Own) => T::from_own_number(self.text, self.span.clone()),
+        }
+    }
+
+    /// An alpha component, either a number or a percentage
+    fn alpha_component<T: CssComponent>(&self, numbers: Numbers) -> Result<T, ParseColorError> {
+        match (self.percentage(), numbers) {
+            (None, Numbers::Css) => T::alpha_from_number(self.text, self.span.clone()),
+            _ => self.component(numbers),
+        }
+    }
+
+    /// Hue in degrees
+    fn hue(&self) -> Result<f64, ParseColorError> {
+        let lower = |suffix: &str| {
+            let len = self.text.len().checked_sub(suffix.len())?;
+            let (num, unit) = self.text.split_at_checked(len)?;
+            unit.eq_ignore_ascii_case(suffix).then(|| (num, self.span.start..self.span.start + len))
+        };
+        let (num, span, scale) = if let Some((num, span)) = lower("grad") {
+            (num, span, 360. / 400.)
+        } else if let Some((num, span)) = lower("deg") {
+            (num, span, 1.)
+        } else if let Some((num, span)) = lower("rad") {
+            (num, span, 180. / core::f64::consts::PI)
+        } else if let Some((num, span)) = lower("turn") {
+            (num, span, 360.)
+        } else {
+            (self.text, self.span.clone(), 1.)
+        };
+        Ok(parse_number(num, span)? * scale)
+    }
+
+    /// Saturation or lightness as a 0..1 fraction
+    fn fraction_percent(&self) -> Result<f64, ParseColorError> {
+        let value = match self.percentage() {
+            Some((num, span)) => parse_number(num, span)?,
+            // CSS Color 4 allows plain numbers too
+            None => parse_number(self.text, self.span.clone())?,
+        };
+        if !(0. ..=100.).contains(&value) {
+            return Err(ParseColorError::new(E::OutOfRange, self.span.clone()));
+        }
+        Ok(value / 100.)
+    }
+}
+
+/// Up to 4 arguments of a function
+struct Args<'a> {
+    args: [Option<Arg<'a>>; 4],
+    len: usize,
+    /// span of the closing paren, for errors about missing args
+    end: Range<usize>,
+}
+
+impl<'a> Args<'a> {
+    /// `inner` is the text between the parens, starting at `offset` byte of the input.
+    /// In the modern syntax, `/ alpha` can follow the first `colors` arguments.
+    fn parse(inner: &'a str, offset: usize, colors: usize) -> Result<Self, ParseColorError> {
+        let end = offset + inner.len()..offset + inner.len() + 1;
+        let mut args = [None, None, None, None];
+        let mut len = 0;
+        let legacy = inner.contains(',');
+        let mut slash = None;
+        let mut pos = 0;
+        loop {
+            let rest = &inner[pos..];
+            let trimmed = rest.trim_start();
+            pos += rest.len() - trimmed.len();
+            if trimmed.is_empty() {
+                break;
+            }
+            let arg_len = trimmed.find(|c: char| c == ',' || c == '/' || c.is_whitespace()).unwrap_or(trimmed.len());
+            if arg_len == 0 {
+                // a separator without an argument before it
+                let span = offset + pos..offset + pos + 1;
+                if trimmed.starts_with('/') && !legacy && slash.is_none() && len == colors {
+                    slash = Some(len);
+                    pos += 1;
+                    continue;
+                }
+                return Err(ParseColorError::new(if trimmed.starts_with('/') && legacy { E::MixedSeparators } else { E::InvalidSyntax }, span));
+            }
+            let span = offset + pos..offset + pos + arg_len;
+            if len == args.len() {
+                return Err(ParseColorError::new(E::TooManyArguments, span));
+            }
+            args[len] = Some(Arg { text: &trimmed[..arg_len], span });
+            len += 1;
+            pos += arg_len;
+
+            let rest = &inner[pos..];
+            let trimmed = rest.trim_start();
+            pos += rest.len() - trimmed.len();
+            if legacy {
+                if let Some(after_comma) = trimmed.strip_prefix(',') {
+                    if after_comma.trim().is_empty() {
+                        return Err(ParseColorError::new(E::MissingArgument, end));
+                    }
+                    pos += 1;
+                } else if !trimmed.is_empty() {
+                    let span = offset + pos..offset + pos + 1;
+                    return Err(ParseColorError::new(E::MixedSeparators, span));
+                }
+            }
+        }
+        if slash.is_some() && len != colors + 1 {
+            return Err(ParseColorError::new(E::MissingArgument, end));
+        }
+        if !legacy && slash.is_none() && len > colors {
+            let span = args[colors].as_ref().map_or(end.clone(), |a| a.span.clone());
+            return Err(ParseColorError::new(E::TooManyArguments, span));
+        }
+        Ok(Self { args, len, end })
+    }
+
+    fn get(&self, n: usize) -> Result<&Arg<'a>, ParseColorError> {
+        self.args[n].as_ref().ok_or_else(|| ParseColorError::new(E::MissingArgument, self.end.clone()))
+    }
+
+    fn alpha(&self, n: usize) -> Option<&Arg<'a>> {
+        self.args.get(n)?.as_ref()
+    }
+
+    fn expect_max(&self, max: usize) -> Result<(), ParseColorError> {
+        if self.len > max {
+            let span = self.args[max].as_ref().map_or(self.end.clone(), |a| a.span.clone());
+            return Err(ParseColorError::new(E::TooManyArguments, span));
+        }
+        Ok(())
+    }
+}
+
+/// Splits `name(args)`, returning the name, its span, and the args with their offset
+fn parse_function(s: &str, offset: usize) -> Result<(&str, Range<usize>, &str, usize), ParseColorError> {
+    let Some(open) = s.find('(') else {
+        return Err(ParseColorError::new(E::UnknownFormat, offset..offset + s.len()));
+    };
+    let name = s[..open].trim_end();
+    let Some(inner) = s[open + 1..].strip_suffix(')') else {
+        let span = match s.find(')') {
+            Some(close) => offset + close + 1..offset + s.len(),
+            None => offset + s.len()..offset + s.len(),
+        };
+        return Err(ParseColorError::new(E::InvalidSyntax, span));
+    };
+    Ok((name, offset..offset + name.len(), inner, offset + open + 1))
+}
+
+/// Returns trimmed string and its offset
+fn trim(s: &str) -> Result<(&str, usize), ParseColorError> {
+    let trimmed = s.trim_start();
+    let offset = s.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+    if trimmed.is_empty() {
+        return Err(ParseColorError::new(E::Empty, 0..s.len()));
+    }
+    Ok((trimmed, offset))
+}
+
+fn hex_digits<const N: usize>(hex: &str, offset: usize) -> Result<[u8; N], ParseColorError> {
+    let mut out = [0; N];
+    for (i, (out, c)) in out.iter_mut().zip(hex.bytes()).enumerate() {
+        *out = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => {
+                let start = offset + i;
+                let len = hex[i..].chars().next().map_or(1, char::len_utf8);
+                return Err(ParseColorError::new(E::InvalidHexDigit, start..start + len));
+            },
+        };
+    }
+    Ok(out)
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa` after the `#`
+fn parse_hex(hex: &str, offset: usize) -> Result<([u8; 4], bool), ParseColorError> {
+    let d = hex_digits::<8>(hex, offset)?;
+    Ok(match hex.len() {
+        3 => ([d[0] * 17, d[1] * 17, d[2] * 17, 255], false),
+        4 => ([d[0] * 17, d[1] * 17, d[2] * 17, d[3] * 17], true),
+        6 => ([d[0] << 4 | d[1], d[2] << 4 | d[3], d[4] << 4 | d[5], 255], false),
+        8 => ([d[0] << 4 | d[1], d[2] << 4 | d[3], d[4] << 4 | d[5], d[6] << 4 | d[7]], true),
+        _ => return Err(ParseColorError::new(E::InvalidHexLength, offset - 1..offset + hex.len())),
+    })
+}
+
+fn hex_component<T: CssComponent>(value: u8, span: Range<usize>) -> Result<T, ParseColorError> {
+    T::from_fraction(f64::from(value) / 255., span)
+}
+
+/// Returns the color and whether alpha has been specified
+fn parse_rgba<T: CssComponent>(s: &str, numbers: Numbers) -> Result<(Rgba<T>, Option<Range<usize>>), ParseColorError> {
+    let (s, offset) = trim(s)?;
+    if let Some(hex) = s.strip_prefix('#') {
+        let span = offset..offset + s.len();
+        let ([r, g, b, a], has_alpha) = parse_hex(hex, offset + 1)?;
+        let px = Rgba {
+            r: hex_component(r, span.clone())?,
+            g: hex_component(g, span.clone())?,
+            b: hex_component(b, span.clone())?,
+            a: hex_component(a, span.clone())?,
+        };
+        return Ok((px, has_alpha.then_some(span)));
+    }
+
+    if !s.contains('(') {
+        let span = offset..offset + s.len();
+        if s.eq_ignore_ascii_case("transparent") {
+            let zero = hex_component(0, span.clone())?;
+            return Ok((Rgba { r: zero, g: zero, b: zero, a: zero }, Some(span)));
+        }
+        if let Some(c) = crate::named::from_name(s) {
+            let px = Rgba {
+                r: hex_component(c.r, span.clone())?,
+                g: hex_component(c.g, span.clone())?,
+                b: hex_component(c.b, span.clone())?,
+                a: T::OPAQUE,
+            };
+            return Ok((px, None));
+        }
+    }
+
+    let (name, name_span, inner, inner_offset) = parse_function(s, offset)?;
+    let is_hsl = name.eq_ignore_ascii_case("hsl") || name.eq_ignore_ascii_case("hsla");
+    if !is_hsl && !name.eq_ignore_ascii_case("rgb") && !name.eq_ignore_ascii_case("rgba") {
+        return Err(ParseColorError::new(E::UnknownFormat, name_span));
+    }
+    let args = Args::parse(inner, inner_offset, 3)?;
+    args.expect_max(4)?;
+    let (a, alpha_span) = match args.alpha(3) {
+        Some(a) => (a.alpha_component(numbers)?, Some(a.span.clone())),
+        None => (T::OPAQUE, None),
+    };
+    let px = if is_hsl {
+        let h = args.get(0)?.hue()?;
+        let s = args.get(1)?.fraction_percent()?;
+        let l = args.get(2)?.fraction_percent()?;
+        let [r, g, b] = hsl_to_rgb(h, s, l);
+        Rgba {
+            r: T::from_fraction(r, args.get(0)?.span.clone())?,
+            g: T::from_fraction(g, args.get(1)?.span.clone())?,
+            b: T::from_fraction(b, args.get(2)?.span.clone())?,
+            a,
+        }
+    } else {
+        Rgba {
+            r: args.get(0)?.component(numbers)?,
+            g: args.get(1)?.component(numbers)?,
+            b: args.get(2)?.component(numbers)?,
+            a,
+        }
+    };
+    Ok((px, alpha_span))
+}
+
+/// As specified in CSS Color 4
+fn hsl_to_rgb(hue: f64, sat: f64, light: f64) -> [f64; 3] {
+    let mut hue = hue % 360.;
+    if hue < 0. {
+        hue += 360.;
+    }
+    let a = sat * light.min(1. - light);
+    [0., 8., 4.].map(|n| {
+        let k = (n + hue / 30.) % 12.;
+        (light - a * (k - 3.).min(9. - k).clamp(-1., 1.)).clamp(0., 1.)
+    })
+}
+
+/// Returns the color and whether alpha has been specified
+fn parse_graya<T: CssComponent>(s: &str, numbers: Numbers) -> Result<(GrayA<T>, Option<Range<usize>>), ParseColorError> {
+    let (s, offset) = trim(s)?;
+    let (name, name_span, inner, inner_offset) = parse_function(s, offset)?;
+    let with_alpha = name.eq_ignore_ascii_case("graya");
+    if !with_alpha && !name.eq_ignore_ascii_case("gray") {
+        return Err(ParseColorError::new(E::UnknownFormat, name_span));
+    }
+    let args = Args::parse(inner, inner_offset, 1)?;
+    let first = args.get(0)?;
+    // gray(#vv) and graya(#vvaa) from the hex formatting
+    if let Some(hex) = first.text.strip_prefix('#') {
+        args.expect_max(1)?;
+        let digits = hex_digits::<4>(hex, first.span.start + 1)?;
+        let expected_len = if with_alpha { 4 } else { 2 };
+        if hex.len() != expected_len {
+            return Err(ParseColorError::new(E::InvalidHexLength, first.span.clone()));
+        }
+        let v = hex_component(digits[0] << 4 | digits[1], first.span.clone())?;
+        if with_alpha {
+            let a = hex_component(digits[2] << 4 | digits[3], first.span.clone())?;
+            return Ok((GrayA { v, a }, Some(first.span.clone())));
+        }
+        return Ok((GrayA { v, a: T::OPAQUE }, None));
+    }
+    args.expect_max(2)?;
+    let v = first.component(numbers)?;
+    let (a, alpha_span) = match args.alpha(1) {
+        Some(a) => (a.alpha_component(numbers)?, Some(a.span.clone())),
+        None => (T::OPAQUE, None),
+    };
+    Ok((GrayA { v, a }, alpha_span))
+}
+
+fn expect_opaque<T: CssComponent + PartialEq>(a: T, alpha_span: Option<Range<usize>>) -> Result<(), ParseColorError> {
+    match alpha_span {
+        Some(span) if a != T::OPAQUE => Err(ParseColorError::new(E::UnexpectedAlpha, span)),
+        _ => Ok(()),
+    }
+}
+
+fn rgb_from_rgba<T: CssComponent + PartialEq>((px, alpha_span): (Rgba<T>, Option<Range<usize>>)) -> Result<Rgb<T>, ParseColorError> {
+    expect_opaque(px.a, alpha_span)?;
+    Ok(Rgb { r: px.r, g: px.g, b: px.b })
+}
+
+fn gray_from_graya<T: CssComponent + PartialEq>((px, alpha_span): (GrayA<T>, Option<Range<usize>>)) -> Result<Gray_v09<T>, ParseColorError> {
+    expect_opaque(px.a, alpha_span)?;
+    Ok(Gray_v09 { v: px.v })
+}
+
+macro_rules! from_css {
+    ($comp:ty) => {
+        impl FromCss for Rgba<$comp> {
+            #[inline]
+            fn from_css(s: &str) -> Result<Self, ParseColorError> {
+                parse_rgba(s, Numbers::Css).map(|(px, _)| px)
+            }
+
+            #[inline]
+            fn from_display(s: &str) -> Result<Self, ParseColorError> {
+                parse_rgba(s, Numbers::Own).map(|(px, _)| px)
+            }
+        }
+
+        impl FromCss for Rgb<$comp> {
+            #[inline]
+            fn from_css(s: &str) -> Result<Self, ParseColorError> {
+                rgb_from_rgba(parse_rgba::<$comp>(s, Numbers::Css)?)
+            }
+
+            #[inline]
+            fn from_display(s: &str) -> Result<Self, ParseColorError> {
+                rgb_from_rgba(parse_rgba::<$comp>(s, Numbers::Own)?)
+            }
+        }
+
+        impl FromCss for GrayA<$comp> {
+            #[inline]
+            fn from_css(s: &str) -> Result<Self, ParseColorError> {
+                parse_graya(s, Numbers::Css).map(|(px, _)| px)
+            }
+
+            #[inline]
+            fn from_display(s: &str) -> Result<Self, ParseColorError> {
+                parse_graya(s, Numbers::Own).map(|(px, _)| px)
+            }
+        }
+
+        impl FromCss for Gray_v09<$comp> {
+            #[inline]
+            fn from_css(s: &str) -> Result<Self, ParseColorError> {
+                gray_from_graya(parse_graya::<$comp>(s, Numbers::Css)?)
+            }
+
+            #[inline]
+            fn from_display(s: &str) -> Result<Self, ParseColorError> {
+                gray_from_graya(parse_graya::<$comp>(s, Numbers::Own)?)
+            }
+        }
+
+        impl FromStr for Rgba<$comp> {
+            type Err = ParseColorError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, ParseColorError> {
+                parse_css(s)
+            }
+        }
+
+        impl FromStr for Rgb<$comp> {
+            type Err = ParseColorError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, ParseColorError> {
+                parse_css(s)
+            }
+        }
+
+        impl FromStr for GrayA<$comp> {
+            type Err = ParseColorError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, ParseColorError> {
+                parse_css(s)
+            }
+        }
+
+        impl FromStr for Gray_v09<$comp> {
+            type Err = ParseColorError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, ParseColorError> {
+                parse_css(s)
+            }
+        }
+    };
+}
+
+from_css!(u8);
+from_css!(f32);
+
+#[test]
+fn parse_hex_colors() {
+    assert_eq!(Ok(Rgb::<u8>::new(0x11, 0x22, 0x33)), "#123".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(0x11, 0x22, 0x33, 0x44)), "#1234".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(0xAB, 0xcd, 0xEF, 255)), " #abCDef ".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(1, 2, 3)), "#010203ff".parse());
+    assert_eq!(Ok(Rgb::<f32>::new(1., 0., 1.)), "#f0f".parse());
+    assert_eq!(E::UnexpectedAlpha, "#010203fe".parse::<Rgb<u8>>().unwrap_err().kind());
+    assert_eq!(E::InvalidHexLength, "#12345".parse::<Rgb<u8>>().unwrap_err().kind());
+    assert_eq!(E::InvalidHexLength, "#".parse::<Rgb<u8>>().unwrap_err().kind());
+    assert_eq!(ParseColorError::new(E::InvalidHexDigit, 4..6), "#123ą56".parse::<Rgb<u8>>().unwrap_err());
+    assert_eq!(ParseColorError::new(E::Empty, 0..2), "  ".parse::<Rgb<u8>>().unwrap_err());
+}
+
+#[test]
+fn parse_functions() {
+    assert_eq!(Ok(Rgb::<u8>::new(0, 100, 255)), "rgb(0,100,255)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(0, 100, 255)), "RGB( 0 , 100 , 255 )".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(0, 100, 255)), "rgb(0 100 255)".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(0, 100, 255, 0)), "rgb(0 100 255/0)".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(0, 128, 255, 191)), "rgba(0%, 50%, 100%, 75%)".parse());
+    assert_eq!(Ok(Rgba::<f32>::new(0.2, 0.5, 1., 1.)), "rgb(51 50% 100%)".parse());
+    assert_eq!(Ok(Rgb::<f32>::new(1., 0., 0.)), "rgb(255, 0, 0)".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(255, 0, 0, 128)), "rgba(255, 0, 0, 0.5)".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(255, 0, 0, 128)), "rgb(255 0 0 / 0.5)".parse());
+    assert_eq!(Ok(Rgba::<u8>::new(255, 0, 0, 128)), "rgb(255 0 0 / 50%)".parse());
+    assert_eq!(Ok(Rgba::<f32>::new(1., 0., 0., 0.5)), "rgba(255, 0, 0, 0.5)".parse());
+    assert_eq!(Ok(Rgba::<f32>::new(1., 0., 0., 0.5)), "rgb(255 0 0 / .5)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(255, 0, 0)), "hsl(0, 100%, 50%)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(0, 0, 255)), "hsl(-120 100 50)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(0, 255, 255)), "hsla(0.5turn 100% 50% / 100%)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(128, 128, 128)), "hsl(1rad 0% 50%)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(0, 255, 0)), "hsl(133.333grad 100% 50%)".parse());
+    assert_eq!(Ok(Rgb::<u8>::new(102, 51, 153)), " RebeccaPurple ".parse());
+    assert_eq!(Ok(Rgba::<f32>::new(0., 0., 0., 0.)), "transparent".parse());
+    assert_eq!(E::UnexpectedAlpha, "transparent".parse::<Rgb<u8>>().unwrap_err().kind());
+    assert_eq!(Ok(Gray_v09::<u8>::new(5)), "gray(5)".parse());
+    assert_eq!(Ok(GrayA::<u8>::new(5, 255)), "gray(5)".parse());
+    assert_eq!(Ok(GrayA::<u8>::new(5, 153)), "graya(5,0.6)".parse());
+    assert_eq!(Ok(GrayA::<f32>::new(0.5, 0.25)), "graya(50% / 0.25)".parse());
+    assert_eq!(Ok(GrayA::<u8>::new(0xAB, 0xCD)), "graya(#abcd)".parse());
+    assert_eq!(Ok(Gray_v09::<u8>::new(0xAB)), "gray(#AB)".parse());
+}
+
+#[test]
+fn parse_errors() {
+    let err = |s: &str| s.parse::<Rgba<u8>>().unwrap_err();
+    assert_eq!(ParseColorError::new(E::UnknownFormat, 0..3), err("rbg(1,2,3)"));
+    assert_eq!(ParseColorError::new(E::UnknownFormat, 0..4), err("reed"));
+    assert_eq!(ParseColorError::new(E::InvalidSyntax, 9..9), err("rgb(1,2,3"));
+    assert_eq!(ParseColorError::new(E::InvalidSyntax, 10..11), err("rgb(1,2,3)x"));
+    assert_eq!(ParseColorError::new(E::OutOfRange, 6..9), err("rgb(1,256,3)"));
+    assert_eq!(ParseColorError::new(E::OutOfRange, 6..10), err("rgb(1,101%,3)"));
+    assert_eq!(ParseColorError::new(E::InvalidNumber, 6..9), err("rgb(1,inf,3)"));
+    assert_eq!(ParseColorError::new(E::InvalidNumber, 6..8), err("rgb(1,1e,3)"));
+    assert_eq!(ParseColorError::new(E::InvalidNumber, 6..7), err("rgb(1,.,3)"));
+    assert_eq!(ParseColorError::new(E::MissingArgument, 7..8), err("rgb(1,2)"));
+    assert_eq!(ParseColorError::new(E::MissingArgument, 8..9), err("rgb(1,2,)"));
+    assert_eq!(ParseColorError::new(E::InvalidSyntax, 6..7), err("rgb(1,,2,3)"));
+    assert_eq!(ParseColorError::new(E::TooManyArguments, 12..13), err("rgb(1,2,3,4,5)"));
+    assert_eq!(ParseColorError::new(E::MixedSeparators, 8..9), err("rgb(1,2 3)"));
+    assert_eq!(ParseColorError::new(E::MixedSeparators, 9..10), err("rgb(1,2,3/4)"));
+    assert_eq!(ParseColorError::new(E::TooManyArguments, 10..11), err("rgb(1 2 3 4)"));
+    assert_eq!(ParseColorError::new(E::MissingArgument, 11..12), err("rgb(1 2 3 /)"));
+    assert_eq!(ParseColorError::new(E::InvalidSyntax, 6..7), err("rgb(1 / 2 3)"));
+    assert_eq!(ParseColorError::new(E::InvalidNumber, 4..8), err("hsl(1red 0% 0%)"));
+    assert_eq!(ParseColorError::new(E::OutOfRange, 10..11), err("rgb(1,2,3,2)"));
+    assert_eq!(ParseColorError::new(E::UnexpectedAlpha, 12..15), "rgb(1 2 3 / 0.5)".parse::<Rgb<u8>>().unwrap_err());
+    assert_eq!(ParseColorError::new(E::UnknownFormat, 0..3), "rgb(1 2 3)".parse::<GrayA<u8>>().unwrap_err());
+    assert_eq!(ParseColorError::new(E::TooManyArguments, 7..8), "gray(1 2)".parse::<Gray_v09<u8>>().unwrap_err());
+    assert_eq!(ParseColorError::new(E::UnexpectedAlpha, 8..11), "graya(1,0.5)".parse::<Gray_v09<u8>>().unwrap_err());
+}
+
+#[test]
+fn parse_roundtrip() {
+    use std::format;
+    use std::string::ToString;
+
+    for px in [Rgba::<u8>::new(0, 1, 2, 3), Rgba::new(255, 128, 127, 0), Rgba::new(0, 0, 0, 255)] {
+        assert_eq!(Ok(px), parse_display(&format!("{px}")));
+        assert_eq!(Ok(px), format!("{px:X}").parse());
+        assert_eq!(Ok(px), format!("{px:x}").parse());
+        let rgb = Rgb::from(px);
+        assert_eq!(Ok(rgb), parse_display(&format!("{rgb}")));
+        assert_eq!(Ok(rgb), rgb.to_string().parse());
+        assert_eq!(Ok(rgb), format!("{rgb:X}").parse());
+        let ga = GrayA::new(px.r, px.a);
+        assert_eq!(Ok(ga), parse_display(&format!("{ga}")));
+        assert_eq!(Ok(ga), format!("{ga:X}").parse());
+        let g = Gray_v09::new(px.g);
+        assert_eq!(Ok(g), parse_display(&format!("{g}")));
+        assert_eq!(Ok(g), g.to_string().parse());
+        assert_eq!(Ok(g), format!("{g:x}").parse());
+    }
+    for px in [Rgba::<f32>::new(0.1, 1. / 3., 2.5, 1e-7), Rgba::new(-0., 0., -1., 1e30)] {
+        assert_eq!(Ok(px), parse_display(&format!("{px}")));
+        let rgb = Rgb::from(px);
+        assert_eq!(Ok(rgb), parse_display(&format!("{rgb}")));
+        let ga = GrayA::new(px.g, px.r);
+        assert_eq!(Ok(ga), parse_display(&format!("{ga}")));
+        let g = Gray_v09::new(px.b);
+        assert_eq!(Ok(g), parse_display(&format!("{g}")));
+    }
+}
+
+#[test]
+fn parse_display_numbers() {
+    assert_eq!(Ok(Rgba::<u8>::new(255, 0, 0, 128)), parse_display("rgba(255,0,0,128)"));
+    assert_eq!(Ok(Rgba::<u8>::new(255, 0, 0, 128)), parse_display("rgba(255 0 0 / 50%)"));
+    assert_eq!(Ok(Rgb::<f32>::new(0.5, 1e-3, 1.)), parse_display("rgb(.5 1e-3 100%)"));
+    assert_eq!(Ok(GrayA::<u8>::new(5, 6)), parse_display("graya(5,6)"));
+    assert_eq!(E::UnexpectedAlpha, parse_display::<Rgb<u8>>("rgba(1,2,3,4)").unwrap_err().kind());
+}
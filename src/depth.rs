@@ -0,0 +1,238 @@
+//! Conversions between component bit depths, e.g. `u8` to `u16`, that rescale the values.
+//!
+//! [`Pixel::map()`] with `u16::from` only widens the type, so `255u8` becomes `255u16`.
+//! [`ToDepth::to_depth()`] rescales the range instead, so `255u8` becomes `65535u16` and `1.0f32`:
+//!
+//! ```
+//! use rgb::{Rgb, Rgba};
+//! use rgb::depth::ToDepth;
+//!
+//! let px = Rgba::<u8>::new(255, 128, 0, 255);
+//! assert_eq!(px.to_depth::<u16>(), Rgba::new(65535, 32896, 0, 65535));
+//! assert_eq!(px.to_depth::<f32>(), Rgba::new(1., 128. / 255., 0., 1.));
+//! assert_eq!(Rgb::new(0.5f32, 2., -1.).to_depth::<u8>(), Rgb::new(128, 255, 0));
+//! ```
+//!
+//! The rules are:
+//!
+//! * `u8` to `u16` multiplies by 257, so that `0xAB` becomes `0xABAB`. It's exact, and lossless.
+//! * `u16` to `u8` divides by 257, rounding to the nearest value. It's the inverse of the above.
+//! * Integers to floats divide by the integer's maximum value, mapping it to `0.0..=1.0`.
+//! * Floats to integers clamp to `0.0..=1.0`, and round to the nearest value. NaN becomes `0`.
+//! * With the `half` feature, `f16` converts like `f32`.
+//!
+//! Premultiplied pixels can be converted too, because the rescaling is linear.
+
+use crate::Pixel;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Converts a component to a different bit depth `To`, rescaling its range.
+///
+/// See [the module docs](self) for the rules.
+pub trait ConvertDepth<To>: Copy {
+    /// Rescales the value to the range of `To`.
+    fn convert_depth(self) -> To;
+}
+
+macro_rules! same_depth {
+    ($($t:ty),*) => {$(
+        impl ConvertDepth<$t> for $t {
+            #[inline(always)]
+            fn convert_depth(self) -> $t {
+                self
+            }
+        }
+    )*};
+}
+
+same_depth!(u8, u16, f32, f64);
+
+impl ConvertDepth<u16> for u8 {
+    #[inline(always)]
+    fn convert_depth(self) -> u16 {
+        u16::from(self) * 257
+    }
+}
+
+impl ConvertDepth<u8> for u16 {
+    #[inline(always)]
+    fn convert_depth(self) -> u8 {
+        ((u32::from(self) + 128) / 257) as u8
+    }
+}
+
+macro_rules! int_float {
+    ($int:ty, $float:ty) => {
+        impl ConvertDepth<$float> for $int {
+            #[inline(always)]
+            fn convert_depth(self) -> $float {
+                <$float>::from(self) / <$float>::from(<$int>::MAX)
+            }
+        }
+
+        impl ConvertDepth<$int> for $float {
+            #[inline(always)]
+            fn convert_depth(self) -> $int {
+                if self.is_nan() {
+                    return 0;
+                }
+                (self.clamp(0., 1.) * <$float>::from(<$int>::MAX) + 0.5) as $int
+            }
+        }
+    };
+}
+
+int_float!(u8, f32);
+int_float!(u8, f64);
+int_float!(u16, f32);
+int_float!(u16, f64);
+
+impl ConvertDepth<f64> for f32 {
+    #[inline(always)]
+    fn convert_depth(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl ConvertDepth<f32> for f64 {
+    #[inline(always)]
+    fn convert_depth(self) -> f32 {
+        self as f32
+    }
+}
+
+#[cfg(feature = "half")]
+macro_rules! half_depth {
+    ($($t:ty),*) => {$(
+        impl ConvertDepth<$t> for ::half::f16 {
+            #[inline(always)]
+            fn convert_depth(self) -> $t {
+                f32::from(self).convert_depth()
+            }
+        }
+
+        impl ConvertDepth<::half::f16> for $t {
+            #[inline(always)]
+            fn convert_depth(self) -> ::half::f16 {
+                ::half::f16::from_f32(self.convert_depth())
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "half")]
+same_depth!(::half::f16);
+#[cfg(feature = "half")]
+half_depth!(u8, u16, f32);
+
+/// Converts pixels to a different component bit depth. Implemented for all pixel types.
+///
+/// See [the module docs](self) for the rules.
+pub trait ToDepth: Pixel {
+    /// Returns the same pixel type with components rescaled to the range of `U`.
+    ///
+    /// ```
+    /// use rgb::{GrayA, Rgb};
+    /// use rgb::depth::ToDepth;
+    ///
+    /// assert_eq!(Rgb::<u16>::new(0, 32896, 65535).to_depth::<u8>(), Rgb::new(0, 128, 255));
+    /// assert_eq!(GrayA::<u8>::new(51, 255).to_depth::<f32>(), GrayA::new(0.2, 1.));
+    /// ```
+    fn to_depth<U>(&self) -> Self::SelfType<U, U>
+    where
+        Self::Component: ConvertDepth<U>,
+        U: Copy + 'static;
+}
+
+impl<P: Pixel> ToDepth for P {
+    #[inline]
+    fn to_depth<U>(&self) -> Self::SelfType<U, U>
+    where
+        Self::Component: ConvertDepth<U>,
+        U: Copy + 'static,
+    {
+        self.map(ConvertDepth::convert_depth)
+    }
+}
+
+/// Converts all pixels of `src` to a different component bit depth, writing them to `dst`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{RGBA8, RGBA16};
+///
+/// let src = [RGBA8::new(1, 2, 3, 255)];
+/// let mut dst = [RGBA16::new(0, 0, 0, 0)];
+/// rgb::depth::convert_slice(&src, &mut dst);
+/// assert_eq!(dst[0], RGBA16::new(257, 514, 771, 65535));
+/// ```
+pub fn convert_slice<P, U>(src: &[P], dst: &mut [P::SelfType<U, U>])
+where
+    P: Pixel,
+    P::Component: ConvertDepth<U>,
+    U: Copy + 'static,
+{
+    assert_eq!(src.len(), dst.len());
+    for (src, dst) in src.iter().zip(dst) {
+        *dst = src.to_depth();
+    }
+}
+
+/// Converts all pixels to a new `Vec` with a different component bit depth, e.g. `&[RGB16]` to `Vec<RGB8>`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{RGB8, RGB16};
+///
+/// let src = vec![RGB16::new(0x1234, 0x5678, 0xFFFF); 10];
+/// let dst = rgb::depth::to_depth_vec::<_, u8>(&src);
+/// assert_eq!(dst[0], RGB8::new(0x12, 0x56, 0xFF));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn to_depth_vec<P, U>(src: &[P]) -> Vec<P::SelfType<U, U>>
+where
+    P: Pixel,
+    P::Component: ConvertDepth<U>,
+    U: Copy + 'static,
+{
+    src.iter().map(ToDepth::to_depth).collect()
+}
+
+#[test]
+fn depth_exact() {
+    for v in 0..=255u8 {
+        let wide: u16 = v.convert_depth();
+        assert_eq!(wide, u16::from_be_bytes([v, v]));
+        assert_eq!(ConvertDepth::<u8>::convert_depth(wide), v);
+        let f: f32 = v.convert_depth();
+        assert_eq!(ConvertDepth::<u8>::convert_depth(f), v);
+        let f: f64 = v.convert_depth();
+        assert_eq!(ConvertDepth::<u8>::convert_depth(f), v);
+    }
+    for v in 0..=65535u16 {
+        let expected = (f64::from(v) * 255. / 65535.).round() as u8;
+        assert_eq!(ConvertDepth::<u8>::convert_depth(v), expected);
+        let f: f32 = v.convert_depth();
+        assert_eq!(ConvertDepth::<u16>::convert_depth(f), v);
+    }
+    assert_eq!(ConvertDepth::<u8>::convert_depth(f32::NAN), 0);
+    assert_eq!(ConvertDepth::<u16>::convert_depth(f64::INFINITY), 65535);
+    assert_eq!(ConvertDepth::<u16>::convert_depth(-0.1f32), 0);
+}
+
+#[test]
+fn depth_pixels() {
+    use crate::{Bgra, GrayA, PremulRgba};
+
+    assert_eq!(Bgra::<u8>::new_bgra(0, 1, 2, 3).to_depth::<u16>(), Bgra::new_bgra(0, 257, 514, 771));
+    assert_eq!(GrayA::<f64>::new(1., 0.5).to_depth::<u8>(), GrayA::new(255, 128));
+    assert_eq!(PremulRgba::<u8>::new(0, 0, 255, 255).to_depth::<f32>(), PremulRgba::new(0., 0., 1., 1.));
+}
@@ -0,0 +1,390 @@
+//! Dithering for reducing the bit depth of pixels, e.g. from `Rgba<u16>` or `Rgba<f32>` to `Rgba<u8>`, or from `Rgb<u8>` to [`Rgb565`].
+//!
+//! Plain rounding to fewer levels creates visible banding in smooth gradients.
+//! Dithering adds a pattern or noise that hides the bands, and preserves the average color.
+//!
+//! * [`ordered()`] adds a fixed threshold pattern. It's fast, works on every pixel independently, and doesn't need `std`.
+//! * `error_diffusion()` spreads the rounding error to neighboring pixels. It looks smoother, but it's sequential,
+//!   and needs the `std` feature.
+//!
+//! The source can be any pixel type with `u8`, `u16`, `f32`, or `f64` components that converts
+//! to the destination's layout (e.g. `Bgra` to `Rgba`), after rescaling the components to `0.0..=1.0` like
+//! [`ToDepth::to_depth()`] does. The destination is any [`DitherTarget`].
+//!
+//! The images are given as slices of rows. The `stride` is the number of pixels between the starts of rows,
+//! which can be larger than the `width` when rows have padding.
+//!
+//! ```
+//! use rgb::{Rgb, Rgb565};
+//! use rgb::dither::{ordered, OrderedMatrix};
+//!
+//! let width = 64;
+//! let gradient: Vec<Rgb<u8>> = (0..width * 2).map(|i| Rgb::new((i % width) as u8, 128, 0)).collect();
+//! let mut out = vec![Rgb565::default(); width * 2];
+//! ordered(OrderedMatrix::Bayer8, width, &gradient, width, &mut out, width);
+//! ```
+
+use crate::depth::{ConvertDepth, ToDepth};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Pixel, Rgb, Rgba, Rgbw};
+use crate::{Argb1555, Bgr10A2, Bgr565, GrayA44, Rgb10A2, Rgb555, Rgb565, Rgba4444};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A pixel type that dithering can write to. Its components have a limited number of levels.
+pub trait DitherTarget: Copy {
+    /// A pixel type with the same components in the same order, with `f32` components in `0.0..=1.0`.
+    ///
+    /// For example, it's `Rgb<f32>` for [`Rgb565`] and `Bgra<f32>` for `Bgra<u8>`.
+    type Unit: Pixel<Component = f32>;
+
+    /// The largest value of each component, in the order of [`Self::Unit`]'s components.
+    const MAX: &'static [u16];
+
+    /// Creates the pixel from its component values, which are in the range `0..=MAX`.
+    fn from_levels(levels: &[u16]) -> Self;
+}
+
+macro_rules! dither_pixel {
+    ($name:ident, [$($bit:ident),+]) => {
+        dither_pixel!($name, u8, [$($bit),+]);
+        dither_pixel!($name, u16, [$($bit),+]);
+    };
+    ($name:ident, $t:ty, [$($bit:ident),+]) => {
+        impl DitherTarget for $name<$t> {
+            type Unit = $name<f32>;
+            const MAX: &'static [u16] = &[$({ stringify!($bit); <$t>::MAX as u16 }),+];
+
+            #[inline]
+            fn from_levels(levels: &[u16]) -> Self {
+                let &[$($bit),+] = levels else { unreachable!() };
+                Self { $($bit: $bit as $t),+ }
+            }
+        }
+    };
+}
+
+dither_pixel!(Rgb, [r, g, b]);
+dither_pixel!(Bgr, [b, g, r]);
+dither_pixel!(Grb, [g, r, b]);
+dither_pixel!(Gray_v09, [v]);
+dither_pixel!(Rgbw, [r, g, b, w]);
+dither_pixel!(Rgba, [r, g, b, a]);
+dither_pixel!(Argb, [a, r, g, b]);
+dither_pixel!(Bgra, [b, g, r, a]);
+dither_pixel!(Abgr, [a, b, g, r]);
+dither_pixel!(GrayA, [v, a]);
+
+macro_rules! dither_packed {
+    ($name:ident, $unit:ident, $new:ident, [$($bit:ident: $max:literal as $t:ty),+]) => {
+        impl DitherTarget for $name {
+            type Unit = $unit<f32>;
+            const MAX: &'static [u16] = &[$($max),+];
+
+            #[inline]
+            fn from_levels(levels: &[u16]) -> Self {
+                let &[$($bit),+] = levels else { unreachable!() };
+                Self::$new($($bit as $t),+).unwrap()
+            }
+        }
+    };
+}
+
+dither_packed!(Rgb565, Rgb, new, [r: 31 as u8, g: 63 as u8, b: 31 as u8]);
+dither_packed!(Bgr565, Bgr, new_bgr, [b: 31 as u8, g: 63 as u8, r: 31 as u8]);
+dither_packed!(Rgb555, Rgb, new, [r: 31 as u8, g: 31 as u8, b: 31 as u8]);
+dither_packed!(Argb1555, Argb, new, [a: 1 as u8, r: 31 as u8, g: 31 as u8, b: 31 as u8]);
+dither_packed!(Rgba4444, Rgba, new, [r: 15 as u8, g: 15 as u8, b: 15 as u8, a: 15 as u8]);
+dither_packed!(GrayA44, GrayA, new, [v: 15 as u8, a: 15 as u8]);
+dither_packed!(Rgb10A2, Rgba, new, [r: 1023 as u16, g: 1023 as u16, b: 1023 as u16, a: 3 as u8]);
+dither_packed!(Bgr10A2, Rgba, new, [r: 1023 as u16, g: 1023 as u16, b: 1023 as u16, a: 3 as u8]);
+
+/// The threshold pattern for [`ordered()`] dithering
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum OrderedMatrix {
+    /// 2×2 Bayer matrix. A coarse, visible cross-hatch pattern.
+    Bayer2,
+    /// 4×4 Bayer matrix
+    Bayer4,
+    /// 8×8 Bayer matrix. The finest and most regular of the Bayer patterns.
+    #[default]
+    Bayer8,
+    /// 32×32 tile of blue noise. It has no visible regular pattern, and looks like fine grain.
+    BlueNoise,
+}
+
+impl OrderedMatrix {
+    /// Threshold in `0.0..1.0`
+    #[inline]
+    fn threshold(self, x: usize, y: usize) -> f32 {
+        let (rank, size) = match self {
+            Self::Bayer2 => (u16::from(BAYER8[y % 2][x % 2] / 16), 4u16),
+            Self::Bayer4 => (u16::from(BAYER8[y % 4][x % 4] / 4), 16),
+            Self::Bayer8 => (u16::from(BAYER8[y % 8][x % 8]), 64),
+            Self::BlueNoise => (BLUE_NOISE[y % 32][x % 32], 1024),
+        };
+        (f32::from(rank) + 0.5) / f32::from(size)
+    }
+}
+
+/// Iterates over `width`-long rows of both images
+fn rows<'a, S, D>(width: usize, src: &'a [S], src_stride: usize, dst: &'a mut [D], dst_stride: usize) -> impl Iterator<Item = (&'a [S], &'a mut [D])> {
+    assert!(src_stride >= width && dst_stride >= width, "stride must be at least the width");
+    let height = if width == 0 { 0 } else { src.len().div_ceil(src_stride) };
+    let dst_height = if width == 0 { 0 } else { dst.len().div_ceil(dst_stride) };
+    assert_eq!(height, dst_height, "the images must have the same number of rows");
+    src.chunks(src_stride.max(1))
+        .zip(dst.chunks_mut(dst_stride.max(1)))
+        .take(height)
+        .map(move |(src, dst)| (&src[..width], &mut dst[..width]))
+}
+
+/// Dithers `src` into `dst` using a threshold pattern.
+///
+/// Each component gets the same threshold. Values that are already exactly representable stay unchanged.
+///
+/// # Panics
+///
+/// If the strides are smaller than `width`, the images have different numbers of rows, or the last row is shorter than `width`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{GrayA, GrayA44};
+/// use rgb::dither::{ordered, OrderedMatrix};
+///
+/// // 2 rows of 2 pixels, with 1 pixel of padding in the source
+/// let src = [GrayA::new(0.5f32, 1.), GrayA::new(0.5, 1.), GrayA::new(0., 0.),
+///            GrayA::new(0.5, 1.), GrayA::new(0.5, 1.)];
+/// let mut dst = [GrayA44::default(); 4];
+/// ordered(OrderedMatrix::Bayer2, 2, &src, 3, &mut dst, 2);
+/// // 7.5 is dithered between 7 and 8
+/// assert_eq!(dst.map(|px| px.v()), [7, 8, 8, 7]);
+/// assert_eq!(dst.map(|px| px.a()), [15; 4]);
+/// ```
+pub fn ordered<S, D>(matrix: OrderedMatrix, width: usize, src: &[S], src_stride: usize, dst: &mut [D], dst_stride: usize)
+where
+    S: Pixel,
+    S::Component: ConvertDepth<f32>,
+    S::SelfType<f32, f32>: Into<D::Unit>,
+    D: DitherTarget,
+{
+    for (y, (src_row, dst_row)) in rows(width, src, src_stride, dst, dst_stride).enumerate() {
+        for (x, (src, dst)) in src_row.iter().zip(dst_row).enumerate() {
+            let threshold = matrix.threshold(x, y);
+            let unit: D::Unit = src.to_depth::<f32>().into();
+            let mut levels = [0; 4];
+            for ((level, &v), &max) in levels.iter_mut().zip(unit.as_array().as_ref()).zip(D::MAX) {
+                let max = f32::from(max);
+                // truncation is floor for non-negative values
+                *level = (v * max + threshold).clamp(0., max) as u16;
+            }
+            *dst = D::from_levels(&levels[..D::MAX.len()]);
+        }
+    }
+}
+
+/// The error distribution pattern for [`error_diffusion()`]
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Diffusion {
+    /// Floyd–Steinberg. Spreads all of the error to 4 neighbors.
+    #[default]
+    FloydSteinberg,
+    /// Atkinson. Spreads 3/4 of the error to 6 neighbors, which gives more contrast, but loses detail in highlights and shadows.
+    Atkinson,
+}
+
+#[cfg(feature = "std")]
+impl Diffusion {
+    /// `(dx, dy, weight)` for left-to-right rows
+    fn kernel(self) -> &'static [(isize, usize, f32)] {
+        match self {
+            Self::FloydSteinberg => &[(1, 0, 7. / 16.), (-1, 1, 3. / 16.), (0, 1, 5. / 16.), (1, 1, 1. / 16.)],
+            Self::Atkinson => &[(1, 0, 1. / 8.), (2, 0, 1. / 8.), (-1, 1, 1. / 8.), (0, 1, 1. / 8.), (1, 1, 1. / 8.), (0, 2, 1. / 8.)],
+        }
+    }
+}
+
+/// Dithers `src` into `dst` by spreading the rounding error of each pixel to its neighbors.
+///
+/// Rows are processed in alternating directions (serpentine), which avoids diagonal artifacts.
+/// Errors of values outside of the representable range are not spread.
+///
+/// # Panics
+///
+/// If the strides are smaller than `width`, the images have different numbers of rows, or the last row is shorter than `width`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Rgba, RGBA8};
+/// use rgb::dither::{error_diffusion, Diffusion};
+///
+/// let src = vec![Rgba::<u16>::new(0x8080, 0x1234, 0xFFFF, 0xFFFF); 100];
+/// let mut dst = vec![RGBA8::default(); 100];
+/// error_diffusion(Diffusion::FloydSteinberg, 10, &src, 10, &mut dst, 10);
+/// assert!(dst.iter().all(|px| px.b == 255 && (px.g == 18 || px.g == 19)));
+/// ```
+#[cfg(feature = "std")]
+pub fn error_diffusion<S, D>(kernel: Diffusion, width: usize, src: &[S], src_stride: usize, dst: &mut [D], dst_stride: usize)
+where
+    S: Pixel,
+    S::Component: ConvertDepth<f32>,
+    S::SelfType<f32, f32>: Into<D::Unit>,
+    D: DitherTarget,
+{
+    diffuse(kernel, width, rows(width, src, src_stride, dst, dst_stride), |(src_row, dst_row), x, carried| {
+        let unit: D::Unit = src_row[x].to_depth::<f32>().into();
+        let mut levels = [0; 4];
+        let mut error = [0.; 4];
+        for (c, (&v, &max)) in unit.as_array().as_ref().iter().zip(D::MAX).enumerate() {
+            let max = f32::from(max);
+            let value = (v * max + carried[c]).clamp(0., max);
+            levels[c] = (value + 0.5) as u16;
+            error[c] = value - f32::from(levels[c]);
+        }
+        dst_row[x] = D::from_levels(&levels[..D::MAX.len()]);
+        error
+    });
+}
+
+/// Visits `width` pixels of every row in serpentine order, and spreads the error returned by `pixel` to the neighbors.
+///
+/// `pixel` gets the row, the `x` position, and the error carried over from the already visited neighbors.
+#[cfg(feature = "std")]
+pub(crate) fn diffuse<R>(kernel: Diffusion, width: usize, rows: impl Iterator<Item = R>, mut pixel: impl FnMut(&mut R, usize, [f32; 4]) -> [f32; 4]) {
+    let kernel = kernel.kernel();
+    // errors for the current and 2 next rows, with 2 pixels of margin on both sides
+    let row_len = width + 4;
+    let mut errors: Vec<[f32; 4]> = vec![[0.; 4]; 3 * row_len];
+    for (y, mut row) in rows.enumerate() {
+        let reverse = y % 2 == 1;
+        for i in 0..width {
+            let x = if reverse { width - 1 - i } else { i };
+            let error = pixel(&mut row, x, errors[x + 2]);
+            for &(dx, dy, weight) in kernel {
+                let dx = if reverse { -dx } else { dx };
+                let e = &mut errors[dy * row_len + (x + 2).wrapping_add_signed(dx)];
+                for (e, error) in e.iter_mut().zip(error) {
+                    *e += error * weight;
+                }
+            }
+        }
+        errors.copy_within(row_len.., 0);
+        errors[2 * row_len..].fill([0.; 4]);
+    }
+}
+
+/// 8×8 Bayer matrix. Smaller Bayer matrices are its top-left corner divided by 4 and 16.
+#[rustfmt::skip]
+static BAYER8: [[u8; 8]; 8] = [
+    [ 0, 32,  8, 40,  2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44,  4, 36, 14, 46,  6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [ 3, 35, 11, 43,  1, 33,  9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47,  7, 39, 13, 45,  5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Ranks `0..1024` of a 32×32 blue noise tile, generated with the void-and-cluster method
+#[rustfmt::skip]
+static BLUE_NOISE: [[u16; 32]; 32] = [
+    [229, 354, 101, 303, 909, 38, 513, 645, 415, 23, 858, 121, 990, 518, 724, 356, 811, 25, 534, 939, 253, 479, 41, 287, 623, 503, 4, 743, 966, 390, 279, 21],
+    [689, 851, 758, 570, 152, 772, 264, 971, 172, 786, 473, 669, 188, 593, 258, 102, 1001, 685, 184, 780, 113, 568, 751, 905, 138, 1022, 203, 434, 553, 148, 803, 492],
+    [420, 164, 497, 973, 382, 607, 870, 344, 715, 557, 305, 912, 380, 825, 936, 488, 615, 286, 463, 385, 637, 959, 208, 449, 359, 799, 596, 854, 319, 901, 612, 983],
+    [70, 906, 271, 6, 682, 218, 54, 501, 96, 997, 224, 80, 734, 8, 424, 169, 791, 60, 919, 838, 19, 314, 820, 546, 53, 709, 277, 95, 691, 36, 214, 332],
+    [644, 558, 742, 455, 832, 954, 435, 666, 816, 396, 628, 806, 533, 318, 700, 897, 375, 556, 693, 225, 514, 726, 127, 650, 975, 175, 478, 935, 393, 537, 765, 874],
+    [400, 204, 1018, 334, 118, 567, 302, 902, 244, 137, 934, 436, 196, 1017, 634, 116, 238, 962, 88, 348, 1012, 411, 888, 270, 397, 860, 575, 778, 237, 991, 467, 140],
+    [837, 672, 83, 526, 785, 178, 741, 69, 522, 763, 324, 576, 106, 844, 281, 505, 771, 432, 641, 853, 144, 598, 71, 490, 752, 15, 329, 135, 657, 59, 727, 294],
+    [27, 447, 879, 246, 949, 405, 599, 1006, 416, 652, 43, 885, 681, 454, 31, 595, 866, 176, 284, 532, 767, 242, 706, 957, 191, 640, 1011, 443, 890, 355, 597, 944],
+    [762, 578, 349, 714, 639, 16, 231, 339, 845, 193, 984, 261, 365, 789, 977, 336, 78, 717, 988, 9, 452, 928, 363, 560, 301, 819, 525, 212, 807, 115, 515, 195],
+    [262, 926, 97, 163, 461, 914, 793, 665, 77, 561, 481, 737, 150, 538, 228, 675, 921, 482, 369, 810, 664, 186, 46, 875, 123, 401, 64, 732, 290, 646, 980, 412],
+    [687, 499, 805, 1000, 268, 536, 133, 426, 950, 306, 809, 2, 638, 916, 99, 413, 569, 128, 226, 586, 298, 849, 422, 655, 754, 970, 602, 924, 475, 1, 855, 132],
+    [316, 34, 377, 616, 750, 330, 883, 711, 165, 619, 392, 963, 450, 312, 847, 755, 276, 831, 694, 938, 90, 540, 1003, 267, 480, 200, 338, 141, 695, 367, 566, 790],
+    [965, 549, 892, 202, 52, 587, 87, 487, 273, 899, 91, 235, 722, 173, 610, 24, 509, 1021, 55, 464, 357, 768, 143, 621, 17, 834, 552, 884, 250, 1007, 190, 459],
+    [241, 108, 696, 438, 967, 801, 386, 1014, 761, 541, 684, 857, 486, 996, 372, 889, 199, 402, 631, 177, 821, 234, 708, 387, 942, 728, 433, 45, 775, 624, 66, 725],
+    [881, 643, 828, 275, 517, 157, 659, 220, 20, 419, 155, 331, 48, 574, 130, 663, 787, 320, 738, 898, 494, 961, 73, 535, 291, 180, 658, 313, 520, 407, 932, 346],
+    [483, 171, 361, 7, 720, 925, 317, 852, 609, 945, 784, 524, 913, 748, 282, 472, 933, 110, 554, 5, 299, 604, 428, 759, 848, 109, 998, 894, 207, 826, 131, 588],
+    [56, 1020, 564, 896, 414, 93, 559, 466, 122, 360, 265, 674, 210, 406, 823, 35, 591, 263, 982, 409, 688, 158, 880, 219, 613, 489, 376, 590, 85, 677, 296, 764],
+    [248, 425, 777, 205, 617, 798, 254, 992, 654, 827, 37, 1005, 104, 620, 960, 187, 712, 440, 839, 227, 797, 1013, 40, 328, 951, 61, 782, 266, 465, 987, 542, 862],
+    [713, 635, 315, 125, 969, 352, 29, 735, 167, 410, 572, 477, 872, 353, 521, 310, 895, 134, 626, 75, 519, 364, 562, 730, 421, 679, 166, 868, 736, 10, 391, 146],
+    [943, 33, 836, 531, 697, 460, 886, 527, 325, 948, 757, 293, 153, 718, 50, 781, 543, 350, 955, 746, 272, 668, 114, 864, 257, 544, 972, 321, 627, 201, 900, 500],
+    [347, 457, 211, 911, 65, 233, 608, 112, 841, 216, 67, 636, 815, 439, 989, 230, 661, 26, 468, 183, 833, 437, 929, 189, 804, 30, 394, 100, 453, 788, 280, 606],
+    [119, 994, 649, 323, 779, 398, 979, 673, 441, 710, 507, 915, 236, 582, 89, 399, 813, 908, 601, 322, 995, 12, 583, 307, 485, 642, 893, 571, 703, 1008, 49, 817],
+    [245, 744, 498, 151, 563, 867, 181, 309, 14, 1015, 384, 117, 341, 941, 701, 495, 142, 252, 723, 84, 504, 776, 383, 707, 981, 160, 773, 240, 124, 362, 545, 671],
+    [887, 379, 0, 937, 692, 82, 511, 812, 585, 259, 859, 774, 551, 182, 850, 300, 1019, 548, 373, 861, 632, 239, 129, 877, 63, 445, 342, 947, 506, 876, 170, 430],
+    [92, 579, 842, 442, 274, 366, 731, 922, 470, 162, 611, 62, 686, 444, 42, 633, 756, 94, 930, 159, 427, 964, 660, 530, 283, 745, 630, 13, 808, 288, 733, 953],
+    [215, 311, 766, 168, 1002, 622, 206, 51, 343, 704, 952, 404, 278, 794, 958, 381, 223, 458, 676, 285, 818, 47, 345, 796, 185, 1016, 550, 222, 678, 474, 44, 625],
+    [523, 985, 667, 491, 98, 829, 423, 769, 978, 105, 508, 198, 907, 529, 145, 592, 878, 22, 770, 581, 516, 209, 920, 603, 388, 79, 835, 418, 139, 974, 374, 824],
+    [126, 358, 28, 249, 577, 903, 295, 539, 629, 251, 760, 856, 3, 351, 740, 260, 502, 976, 333, 120, 1004, 683, 451, 111, 882, 484, 699, 308, 869, 589, 247, 716],
+    [456, 873, 739, 931, 378, 690, 154, 18, 814, 371, 446, 647, 555, 1009, 103, 830, 662, 174, 417, 802, 269, 11, 753, 304, 653, 243, 940, 32, 749, 512, 68, 927],
+    [197, 600, 292, 528, 76, 783, 476, 1023, 194, 891, 72, 161, 297, 698, 471, 389, 57, 918, 614, 496, 904, 370, 840, 510, 986, 147, 573, 368, 192, 999, 326, 656],
+    [403, 58, 822, 179, 968, 232, 594, 327, 680, 493, 729, 946, 800, 221, 618, 956, 289, 747, 213, 81, 648, 149, 584, 217, 74, 792, 448, 670, 846, 469, 136, 795],
+    [547, 1010, 651, 462, 705, 395, 843, 107, 923, 255, 580, 340, 431, 39, 871, 156, 565, 429, 863, 337, 719, 993, 408, 865, 702, 335, 910, 256, 86, 605, 721, 917],
+];
+
+#[test]
+fn matrices() {
+    for size in [2, 4, 8, 32] {
+        let matrix = match size {
+            2 => OrderedMatrix::Bayer2,
+            4 => OrderedMatrix::Bayer4,
+            8 => OrderedMatrix::Bayer8,
+            _ => OrderedMatrix::BlueNoise,
+        };
+        let mut thresholds: std::vec::Vec<_> = (0..size * size).map(|i| matrix.threshold(i % size, i / size)).collect();
+        thresholds.sort_by(f32::total_cmp);
+        for (i, t) in thresholds.iter().enumerate() {
+            assert_eq!(*t, (i as f32 + 0.5) / (size * size) as f32);
+        }
+    }
+}
+
+#[test]
+fn ordered_average() {
+    // dithering preserves the average over the matrix
+    let src = [Rgb::<u16>::new(0x8000, 0x0101, 0xFFFF); 64];
+    let mut dst = [Rgb::<u8>::default(); 64];
+    ordered(OrderedMatrix::Bayer8, 8, &src, 8, &mut dst, 8);
+    let sum = dst.iter().fold([0u32; 3], |s, px| [s[0] + u32::from(px.r), s[1] + u32::from(px.g), s[2] + u32::from(px.b)]);
+    assert_eq!(sum, [(0x8000 * 64 + 128) / 257, 64, 255 * 64]);
+
+    let src = [Bgra::<u8>::new_bgra(255, 128, 0, 255); 16];
+    let mut dst = [Rgb565::default(); 16];
+    ordered(OrderedMatrix::BlueNoise, 4, &src, 4, &mut dst, 4);
+    assert!(dst.iter().all(|px| px.r() == 0 && px.b() == 31 && (px.g() == 31 || px.g() == 32)));
+    let mut dst = [Rgba4444::default(); 16];
+    ordered(OrderedMatrix::Bayer4, 4, &src, 4, &mut dst, 4);
+    assert!(dst.iter().all(|px| px.r() == 0 && px.b() == 15 && px.a() == 15));
+
+    let src = [Rgb::<u8>::new(255, 0, 0); 16];
+    let mut dst = [Bgr565::default(); 16];
+    ordered(OrderedMatrix::Bayer4, 4, &src, 4, &mut dst, 4);
+    assert!(dst.iter().all(|&px| px == Bgr565::from(src[0])));
+    assert_eq!((dst[0].r(), dst[0].b()), (31, 0));
+}
+
+#[test]
+fn empty_images() {
+    let mut dst: [Rgb<u8>; 0] = [];
+    ordered::<Rgb<u8>, _>(OrderedMatrix::Bayer2, 0, &[], 0, &mut dst, 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn diffusion_average() {
+    for kernel in [Diffusion::FloydSteinberg, Diffusion::Atkinson] {
+        let src = [Gray_v09::new(0.25f32); 256];
+        let mut dst = [Rgb555::default(); 256];
+        let src: std::vec::Vec<_> = src.iter().map(|&g| Rgb::new(g.v, 0.5, 1.)).collect();
+        error_diffusion(kernel, 16, &src, 16, &mut dst, 16);
+        let sum_r: u32 = dst.iter().map(|px| u32::from(px.r())).sum();
+        // 0.25 * 31 = 7.75
+        assert!((sum_r as f32 / 256. - 7.75).abs() < 0.1, "{kernel:?} {sum_r}");
+        assert!(dst.iter().all(|px| px.b() == 31 && (px.g() == 15 || px.g() == 16)));
+    }
+}
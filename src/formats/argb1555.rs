@@ -0,0 +1,92 @@
+use crate::formats::gray_a44::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A 16-bit packed ARGB pixel, with 1 bit for alpha, and 5 bits for each of red, green, and blue.
+///
+/// Alpha is in the most significant bit: `0bARRRRRGGGGGBBBBB`.
+///
+/// The components are stored in a native-endian `u16`. Use [`Argb1555::to_bits()`]`.to_le_bytes()`
+/// or `to_be_bytes()` when the byte order of the target matters.
+///
+/// Conversions from and to [`Rgb<u8>`](crate::Rgb) and [`Rgba<u8>`](crate::Rgba) round to the nearest value.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Argb1555, Rgba};
+///
+/// let pixel = Argb1555::new(1, 31, 20, 3).unwrap();
+/// assert_eq!(pixel.to_bits(), 0xFE83);
+///
+/// let pixel = Argb1555::from(Rgba::new(255, 128, 0, 255));
+/// assert_eq!(Rgba::from(pixel), Rgba::new(255, 132, 0, 255));
+/// ```
+pub struct Argb1555(pub(crate) u16);
+
+impl Argb1555 {
+    /// Tries to create a new [`Argb1555`] from its components.
+    ///
+    /// Returns an error if any component is out of its range (`a` `0..=1`; `r` `0..=31`; `g` `0..=31`; `b` `0..=31`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Argb1555;
+    /// use rgb::error::OutOfRangeError;
+    ///
+    /// assert!(Argb1555::new(1, 31, 20, 3).is_ok());
+    /// assert_eq!(Argb1555::new(1, 99, 20, 3), Err(OutOfRangeError));
+    /// ```
+    #[inline]
+    pub const fn new(a: u8, r: u8, g: u8, b: u8) -> Result<Self, OutOfRangeError> {
+        if a > 1 || r > 31 || g > 31 || b > 31 {
+            return Err(OutOfRangeError);
+        }
+        Ok(Self((a as u16) << 15 | (r as u16) << 10 | (g as u16) << 5 | (b as u16)))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the 1-bit alpha component, `0` or `1`.
+    #[inline]
+    #[must_use]
+    pub const fn a(self) -> u8 {
+        (self.0 >> 15) as u8
+    }
+
+    /// Returns the 5-bit red component.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        (self.0 >> 10) as u8 & 0x1F
+    }
+
+    /// Returns the 5-bit green component.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        (self.0 >> 5) as u8 & 0x1F
+    }
+
+    /// Returns the 5-bit blue component.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        self.0 as u8 & 0x1F
+    }
+}
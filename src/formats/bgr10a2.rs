@@ -0,0 +1,91 @@
+use crate::formats::gray_a44::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A 32-bit packed RGBA pixel, with 10 bits for each of red, green, and blue, and 2 bits for alpha.
+///
+/// Blue is in the least significant bits: `0bAARRRRRRRRRRGGGGGGGGGGBBBBBBBBBB`.
+/// This is `VK_FORMAT_A2R10G10B10_UNORM_PACK32`, and the common "x2r10g10b10" display framebuffer format.
+///
+/// The components are stored in a native-endian `u32`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Bgr10A2, Rgba};
+///
+/// let pixel = Bgr10A2::new(1023, 512, 0, 3).unwrap();
+/// assert_eq!(pixel.to_bits(), 0xFFF8_0000);
+///
+/// let pixel = Bgr10A2::from(Rgba::<f32>::new(1., 0.5, 0., 1.));
+/// assert_eq!(pixel.g(), 512);
+/// ```
+pub struct Bgr10A2(pub(crate) u32);
+
+impl Bgr10A2 {
+    /// Tries to create a new [`Bgr10A2`] from its components.
+    ///
+    /// Returns an error if any color component is out of the 10-bit range (`0..=1023`),
+    /// or alpha is out of the 2-bit range (`0..=3`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Bgr10A2;
+    /// use rgb::error::OutOfRangeError;
+    ///
+    /// assert!(Bgr10A2::new(1023, 0, 0, 3).is_ok());
+    /// assert_eq!(Bgr10A2::new(1024, 0, 0, 3), Err(OutOfRangeError));
+    /// ```
+    #[inline]
+    pub const fn new(r: u16, g: u16, b: u16, a: u8) -> Result<Self, OutOfRangeError> {
+        if r > 1023 || g > 1023 || b > 1023 || a > 3 {
+            return Err(OutOfRangeError);
+        }
+        Ok(Self((a as u32) << 30 | (r as u32) << 20 | (g as u32) << 10 | (b as u32)))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the 10-bit red component.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u16 {
+        (self.0 >> 20) as u16 & 0x3FF
+    }
+
+    /// Returns the 10-bit green component.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u16 {
+        (self.0 >> 10) as u16 & 0x3FF
+    }
+
+    /// Returns the 10-bit blue component.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u16 {
+        self.0 as u16 & 0x3FF
+    }
+
+    /// Returns the 2-bit alpha component.
+    #[inline]
+    #[must_use]
+    pub const fn a(self) -> u8 {
+        (self.0 >> 30) as u8
+    }
+}
@@ -0,0 +1,99 @@
+use crate::formats::gray_a44::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A 16-bit packed BGR pixel, with 5 bits for blue, 6 bits for green, and 5 bits for red.
+///
+/// Blue is in the most significant bits: `0bBBBBBGGGGGGRRRRR`.
+///
+/// The components are stored in a native-endian `u16`. Use [`Bgr565::to_bits()`]`.to_le_bytes()`
+/// or `to_be_bytes()` when the byte order of the target matters.
+///
+/// Conversions from and to [`Rgb<u8>`](crate::Rgb) and [`Rgba<u8>`](crate::Rgba) round to the nearest value.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Bgr565, Rgb};
+///
+/// let pixel = Bgr565::new(31, 20, 3).unwrap();
+/// assert_eq!(pixel.to_bits(), 0x1A9F);
+///
+/// let pixel = Bgr565::from(Rgb::new(255, 128, 0));
+/// assert_eq!(Rgb::from(pixel), Rgb::new(255, 130, 0));
+/// ```
+pub struct Bgr565(pub(crate) u16);
+
+impl Bgr565 {
+    /// Tries to create a new [`Bgr565`] from its components.
+    ///
+    /// The arguments are in R, G, B order, like for all other pixel types. See [`Bgr565::new_bgr()`] for the memory order.
+    ///
+    /// Returns an error if any component is out of its range (`r` `0..=31`; `g` `0..=63`; `b` `0..=31`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Bgr565;
+    /// use rgb::error::OutOfRangeError;
+    ///
+    /// assert!(Bgr565::new(31, 20, 3).is_ok());
+    /// assert_eq!(Bgr565::new(31, 20, 99), Err(OutOfRangeError));
+    /// ```
+    #[inline]
+    pub const fn new(r: u8, g: u8, b: u8) -> Result<Self, OutOfRangeError> {
+        Self::new_bgr(b, g, r)
+    }
+
+    /// Same as [`Bgr565::new()`], but with the arguments in B, G, R order.
+    ///
+    /// ```
+    /// use rgb::Bgr565;
+    ///
+    /// assert_eq!(Bgr565::new_bgr(3, 20, 31), Bgr565::new(31, 20, 3));
+    /// ```
+    #[inline]
+    pub const fn new_bgr(b: u8, g: u8, r: u8) -> Result<Self, OutOfRangeError> {
+        if b > 31 || g > 63 || r > 31 {
+            return Err(OutOfRangeError);
+        }
+        Ok(Self((b as u16) << 11 | (g as u16) << 5 | (r as u16)))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the 5-bit blue component.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        (self.0 >> 11) as u8
+    }
+
+    /// Returns the 6-bit green component.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        (self.0 >> 5) as u8 & 0x3F
+    }
+
+    /// Returns the 5-bit red component.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        self.0 as u8 & 0x1F
+    }
+}
@@ -0,0 +1,33 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default)]
+/// A `Blue + Green + Red + padding` pixel, e.g. of a 32-bit framebuffer.
+///
+/// The padding component is not alpha. It's ignored by `PartialEq`, `Ord` and `Hash`,
+/// and the pixel has three color components and no alpha in [`HetPixel`](crate::HetPixel).
+/// However, [`map_colors()`](crate::HetPixel::map_colors) also maps the padding with the color closure,
+/// so the closure is called four times, and [`try_from_colors_alpha()`](crate::HetPixel::try_from_colors_alpha)
+/// stores its `alpha` argument as the padding.
+///
+/// This is the memory layout of `XRGB8888` in Wayland and DRM, which name the bits of a little-endian `u32`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Bgrx;
+///
+/// let pixel: Bgrx<u8> = Bgrx { b: 0, g: 0, r: 0, x: 0 };
+/// ```
+#[doc(alias = "BGRX8")]
+#[doc(alias = "XRGB8888")]
+pub struct Bgrx<T> {
+    /// Blue Component
+    pub b: T,
+    /// Green Component
+    pub g: T,
+    /// Red Component
+    pub r: T,
+    /// Padding, which is not alpha, and is ignored by comparisons
+    pub x: T,
+}
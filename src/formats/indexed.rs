@@ -0,0 +1,129 @@
+use crate::error::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// An indexed-color pixel, which is an index into a palette.
+///
+/// The index is usually `u8` or `u16`. Indices packed into 1, 2, or 4 bits per pixel can be converted
+/// with [`Indexed::unpack()`] and [`Indexed::pack()`].
+///
+/// See [`Palette`](crate::palette::Palette) for looking up the colors.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Indexed;
+///
+/// let pixel = Indexed::new(3u8);
+/// assert_eq!(pixel.index(), 3);
+/// ```
+pub struct Indexed<I = u8>(
+    /// Index into a palette
+    pub I,
+);
+
+impl<I> Indexed<I> {
+    /// Creates a new [`Indexed`] pixel.
+    #[inline(always)]
+    pub const fn new(index: I) -> Self {
+        Self(index)
+    }
+
+    /// Returns the index as `usize`, for indexing a slice.
+    #[inline(always)]
+    pub fn index(self) -> usize where I: Into<usize> {
+        self.0.into()
+    }
+}
+
+impl Indexed<u8> {
+    /// Unpacks a row of indices with `bit_depth` bits per pixel, most significant bits first, as in PNG.
+    ///
+    /// The row has `dst.len()` pixels. Bits past the last pixel are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `bit_depth` is not 1, 2, 4, or 8, or `packed` is too short for `dst.len()` pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Indexed;
+    ///
+    /// let mut row = [Indexed::new(0); 3];
+    /// Indexed::unpack(&[0b11_01_10_00], 2, &mut row);
+    /// assert_eq!(row, [Indexed::new(3), Indexed::new(1), Indexed::new(2)]);
+    /// ```
+    pub fn unpack(packed: &[u8], bit_depth: u8, dst: &mut [Self]) {
+        assert!(matches!(bit_depth, 1 | 2 | 4 | 8), "bit_depth must be 1, 2, 4, or 8");
+        let per_byte = usize::from(8 / bit_depth);
+        assert!(packed.len() >= dst.len().div_ceil(per_byte), "packed row is too short");
+        let mask = ((1u16 << bit_depth) - 1) as u8;
+        for (dst, &byte) in dst.chunks_mut(per_byte).zip(packed) {
+            for (i, px) in dst.iter_mut().enumerate() {
+                let shift = 8 - bit_depth * (i as u8 + 1);
+                *px = Self((byte >> shift) & mask);
+            }
+        }
+    }
+
+    /// Packs a row of indices with `bit_depth` bits per pixel, most significant bits first, as in PNG.
+    ///
+    /// Unused bits of the last byte are set to 0. Returns an error if an index doesn't fit in `bit_depth` bits.
+    ///
+    /// # Panics
+    ///
+    /// If `bit_depth` is not 1, 2, 4, or 8, or `dst` is too short for `src.len()` pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Indexed;
+    ///
+    /// let mut packed = [0u8; 1];
+    /// Indexed::pack(&[Indexed::new(1), Indexed::new(0), Indexed::new(1)], 1, &mut packed).unwrap();
+    /// assert_eq!(packed, [0b1010_0000]);
+    /// ```
+    pub fn pack(src: &[Self], bit_depth: u8, dst: &mut [u8]) -> Result<(), OutOfRangeError> {
+        assert!(matches!(bit_depth, 1 | 2 | 4 | 8), "bit_depth must be 1, 2, 4, or 8");
+        let per_byte = usize::from(8 / bit_depth);
+        assert!(dst.len() >= src.len().div_ceil(per_byte), "packed row is too short");
+        let max = ((1u16 << bit_depth) - 1) as u8;
+        for (src, byte) in src.chunks(per_byte).zip(dst) {
+            *byte = 0;
+            for (i, px) in src.iter().enumerate() {
+                if px.0 > max {
+                    return Err(OutOfRangeError);
+                }
+                *byte |= px.0 << (8 - bit_depth * (i as u8 + 1));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<I> From<I> for Indexed<I> {
+    #[inline(always)]
+    fn from(index: I) -> Self {
+        Self(index)
+    }
+}
+
+#[test]
+fn packed_indices() {
+    for bit_depth in [1, 2, 4, 8] {
+        let max = (1u16 << bit_depth) as usize;
+        let src: [Indexed; 13] = core::array::from_fn(|i| Indexed::new(((i * 7) % max) as u8));
+        let mut packed = [0xFF; 13];
+        Indexed::pack(&src, bit_depth, &mut packed).unwrap();
+        let mut unpacked = [Indexed::new(0); 13];
+        Indexed::unpack(&packed, bit_depth, &mut unpacked);
+        assert_eq!(src, unpacked);
+    }
+    let mut packed = [0; 2];
+    Indexed::pack(&[Indexed::new(15), Indexed::new(0), Indexed::new(9)], 4, &mut packed).unwrap();
+    assert_eq!(packed, [0xF0, 0x90]);
+    assert_eq!(Indexed::pack(&[Indexed::new(4)], 2, &mut packed), Err(OutOfRangeError));
+}
@@ -0,0 +1,30 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A `Alpha + Blue + Green + Red` pixel with the color components premultiplied by alpha.
+///
+/// It has the same memory layout as [`Abgr`](crate::Abgr), but it's a distinct type,
+/// so that straight and premultiplied pixels can't be mixed up by accident.
+/// Convert between them using `From`/`Into`, which rounds integer components correctly.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Abgr, PremulAbgr};
+///
+/// let premultiplied = PremulAbgr { a: 128, b: 128, g: 0, r: 64 };
+/// let straight: Abgr<u8> = premultiplied.into();
+/// assert_eq!(straight, Abgr { a: 128, b: 255, g: 0, r: 128 });
+/// ```
+#[doc(alias = "PremultipliedAbgr")]
+pub struct PremulAbgr<T, A = T> {
+    /// Alpha Component
+    pub a: A,
+    /// Blue Component, premultiplied by alpha
+    pub b: T,
+    /// Green Component, premultiplied by alpha
+    pub g: T,
+    /// Red Component, premultiplied by alpha
+    pub r: T,
+}
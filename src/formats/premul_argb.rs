@@ -0,0 +1,30 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A `Alpha + Red + Green + Blue` pixel with the color components premultiplied by alpha.
+///
+/// It has the same memory layout as [`Argb`](crate::Argb), but it's a distinct type,
+/// so that straight and premultiplied pixels can't be mixed up by accident.
+/// Convert between them using `From`/`Into`, which rounds integer components correctly.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Argb, PremulArgb};
+///
+/// let premultiplied = PremulArgb { a: 128, r: 64, g: 0, b: 128 };
+/// let straight: Argb<u8> = premultiplied.into();
+/// assert_eq!(straight, Argb { a: 128, r: 128, g: 0, b: 255 });
+/// ```
+#[doc(alias = "PremultipliedArgb")]
+pub struct PremulArgb<T, A = T> {
+    /// Alpha Component
+    pub a: A,
+    /// Red Component, premultiplied by alpha
+    pub r: T,
+    /// Green Component, premultiplied by alpha
+    pub g: T,
+    /// Blue Component, premultiplied by alpha
+    pub b: T,
+}
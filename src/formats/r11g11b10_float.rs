@@ -0,0 +1,157 @@
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+/// A 32-bit packed HDR RGB pixel, with unsigned floats of 11 bits for red and green, and 10 bits for blue.
+///
+/// Red is in the least significant bits. The floats have no sign bit, a 5-bit exponent, and a 6-bit (red, green)
+/// or 5-bit (blue) mantissa. This is `DXGI_FORMAT_R11G11B10_FLOAT` and `VK_FORMAT_B10G11R11_UFLOAT_PACK32`.
+///
+/// The components are stored in a native-endian `u32`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{R11G11B10Float, Rgb};
+///
+/// let pixel = R11G11B10Float::new(1., 0.5, 1000.);
+/// // blue has only 6 significant bits
+/// assert_eq!(Rgb::from(pixel), Rgb::new(1., 0.5, 992.));
+/// ```
+pub struct R11G11B10Float(pub(crate) u32);
+
+impl R11G11B10Float {
+    /// Creates a new [`R11G11B10Float`], rounding the components to the nearest representable value.
+    ///
+    /// Negative values become `0`, and finite values that are too large become the largest finite value, `65024`.
+    /// Infinity and NaN are preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::R11G11B10Float;
+    ///
+    /// let pixel = R11G11B10Float::new(-1., 0.1, 1e6);
+    /// assert_eq!(pixel.r(), 0.);
+    /// assert_eq!(pixel.g(), 0.099609375);
+    /// assert_eq!(pixel.b(), 64512.);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(r: f32, g: f32, b: f32) -> Self {
+        Self(encode(b, 5) << 22 | encode(g, 6) << 11 | encode(r, 6))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the red component.
+    #[inline]
+    #[must_use]
+    pub fn r(self) -> f32 {
+        decode(self.0 & 0x7FF, 6)
+    }
+
+    /// Returns the green component.
+    #[inline]
+    #[must_use]
+    pub fn g(self) -> f32 {
+        decode((self.0 >> 11) & 0x7FF, 6)
+    }
+
+    /// Returns the blue component.
+    #[inline]
+    #[must_use]
+    pub fn b(self) -> f32 {
+        decode(self.0 >> 22, 5)
+    }
+}
+
+/// Converts to an unsigned float with a 5-bit exponent and `mantissa_bits` mantissa
+fn encode(v: f32, mantissa_bits: u32) -> u32 {
+    let inf = 0x1F << mantissa_bits;
+    if v.is_nan() {
+        return inf | 1 << (mantissa_bits - 1);
+    }
+    if v <= 0. {
+        return 0;
+    }
+    if v == f32::INFINITY {
+        return inf;
+    }
+    let bits = v.to_bits();
+    // rebias the exponent from 127 to 15
+    let exp = (bits >> 23) as i32 - 127 + 15;
+    let mantissa = bits & 0x7F_FFFF;
+    let drop_bits = 23 - mantissa_bits;
+    let packed = if exp > 0 {
+        // rounding may carry into the exponent, which is still correct
+        round_shift((exp as u32) << 23 | mantissa, drop_bits)
+    } else {
+        // subnormal, without the implicit leading 1
+        let shift = drop_bits + (1 - exp) as u32;
+        if shift > 24 {
+            return 0;
+        }
+        round_shift(mantissa | 1 << 23, shift)
+    };
+    packed.min(inf - 1)
+}
+
+/// Shifts right, rounding to nearest even
+fn round_shift(v: u32, shift: u32) -> u32 {
+    let half = 1 << (shift - 1);
+    let rest = v & ((1 << shift) - 1);
+    let shifted = v >> shift;
+    if rest > half || (rest == half && shifted & 1 != 0) {
+        shifted + 1
+    } else {
+        shifted
+    }
+}
+
+fn decode(v: u32, mantissa_bits: u32) -> f32 {
+    let exp = v >> mantissa_bits;
+    let mantissa = v & ((1 << mantissa_bits) - 1);
+    match exp {
+        // mantissa * 2^(-14 - mantissa_bits)
+        0 => mantissa as f32 * f32::from_bits((127 - 14 - mantissa_bits) << 23),
+        0x1F if mantissa == 0 => f32::INFINITY,
+        0x1F => f32::NAN,
+        _ => f32::from_bits((exp + 127 - 15) << 23 | mantissa << (23 - mantissa_bits)),
+    }
+}
+
+#[test]
+fn r11g11b10_float() {
+    // every value of every component roundtrips
+    for v in 0..0x7FF {
+        if v >> 6 == 0x1F && v & 0x3F != 0 {
+            continue;
+        }
+        assert_eq!(encode(decode(v, 6), 6), v);
+        if v < 0x3FF && !(v >> 5 == 0x1F && v & 0x1F != 0) {
+            assert_eq!(encode(decode(v, 5), 5), v);
+        }
+    }
+    assert!(R11G11B10Float::new(f32::NAN, 0., 0.).r().is_nan());
+    assert!(R11G11B10Float::new(0., 0., f32::NAN).b().is_nan());
+    assert_eq!(R11G11B10Float::new(f32::INFINITY, 65024., 65000.).to_bits(), 0xF7FD_FFC0);
+    assert_eq!(R11G11B10Float::new(f32::INFINITY, 65024., 64511.).b(), 64512.);
+    assert_eq!(R11G11B10Float::new(1e-30, 6.1035156e-5 / 64., 0.).g(), 6.1035156e-5 / 64.);
+    // halfway between 1 and the next value rounds to even
+    assert_eq!(R11G11B10Float::new(1. + 1. / 128., 1. + 3. / 128., 0.).r(), 1.);
+    assert_eq!(R11G11B10Float::new(1. + 1. / 128., 1. + 3. / 128., 0.).g(), 1. + 4. / 128.);
+    assert_eq!(R11G11B10Float::new(-0., f32::NEG_INFINITY, 0.).to_bits(), 0);
+}
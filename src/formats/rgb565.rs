@@ -0,0 +1,85 @@
+use crate::formats::gray_a44::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A 16-bit packed RGB pixel, with 5 bits for red, 6 bits for green, and 5 bits for blue.
+///
+/// Red is in the most significant bits: `0bRRRRRGGGGGGBBBBB`.
+///
+/// The components are stored in a native-endian `u16`. Use [`Rgb565::to_bits()`]`.to_le_bytes()`
+/// or `to_be_bytes()` when the byte order of the target matters.
+///
+/// Conversions from and to [`Rgb<u8>`](crate::Rgb) and [`Rgba<u8>`](crate::Rgba) round to the nearest value.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Rgb565, Rgb};
+///
+/// let pixel = Rgb565::new(31, 20, 3).unwrap();
+/// assert_eq!(pixel.to_bits(), 0xFA83);
+///
+/// let pixel = Rgb565::from(Rgb::new(255, 128, 0));
+/// assert_eq!(Rgb::from(pixel), Rgb::new(255, 130, 0));
+/// ```
+pub struct Rgb565(pub(crate) u16);
+
+impl Rgb565 {
+    /// Tries to create a new [`Rgb565`] from its components.
+    ///
+    /// Returns an error if any component is out of its range (`r` `0..=31`; `g` `0..=63`; `b` `0..=31`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Rgb565;
+    /// use rgb::error::OutOfRangeError;
+    ///
+    /// assert!(Rgb565::new(31, 20, 3).is_ok());
+    /// assert_eq!(Rgb565::new(99, 20, 3), Err(OutOfRangeError));
+    /// ```
+    #[inline]
+    pub const fn new(r: u8, g: u8, b: u8) -> Result<Self, OutOfRangeError> {
+        if r > 31 || g > 63 || b > 31 {
+            return Err(OutOfRangeError);
+        }
+        Ok(Self((r as u16) << 11 | (g as u16) << 5 | (b as u16)))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the 5-bit red component.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        (self.0 >> 11) as u8
+    }
+
+    /// Returns the 6-bit green component.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        (self.0 >> 5) as u8 & 0x3F
+    }
+
+    /// Returns the 5-bit blue component.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        self.0 as u8 & 0x1F
+    }
+}
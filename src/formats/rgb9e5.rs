@@ -0,0 +1,128 @@
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+/// A 32-bit packed HDR RGB pixel, with 9-bit mantissas for red, green, and blue, and a shared 5-bit exponent.
+///
+/// Red is in the least significant bits, and the exponent is in the 5 most significant bits.
+/// This is `DXGI_FORMAT_R9G9B9E5_SHAREDEXP` and `VK_FORMAT_E5B9G9R9_UFLOAT_PACK32`.
+///
+/// The components can't be negative, infinite, or NaN. The brightest component has the most precision.
+///
+/// The components are stored in a native-endian `u32`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Rgb9E5, Rgb};
+///
+/// let pixel = Rgb9E5::new(1000., 1., 0.5);
+/// // the dimmer components share the large exponent
+/// assert_eq!(Rgb::from(pixel), Rgb::new(1000., 2., 0.));
+/// ```
+pub struct Rgb9E5(pub(crate) u32);
+
+/// Exponent bias
+const B: i32 = 15;
+/// Mantissa bits
+const N: i32 = 9;
+
+impl Rgb9E5 {
+    /// The largest value of a component, `65408`
+    pub const MAX: f32 = 65408.;
+
+    /// Creates a new [`Rgb9E5`], rounding the components to the nearest representable value.
+    ///
+    /// Components are clamped to `0..=65408`. NaN becomes `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Rgb9E5;
+    ///
+    /// let pixel = Rgb9E5::new(-1., 0.25, f32::INFINITY);
+    /// assert_eq!(pixel.r(), 0.);
+    /// assert_eq!(pixel.g(), 0.);
+    /// assert_eq!(pixel.b(), Rgb9E5::MAX);
+    /// ```
+    #[must_use]
+    pub fn new(r: f32, g: f32, b: f32) -> Self {
+        // algorithm from the EXT_texture_shared_exponent spec
+        let [r, g, b] = [r, g, b].map(|c| if c.is_nan() { 0. } else { c.clamp(0., Self::MAX) });
+        let max = r.max(g).max(b);
+        // floor(log2(max)), exact for normal floats, and smaller ones are clamped anyway
+        let log2 = ((max.to_bits() >> 23) as i32 - 127).max(-B - 1);
+        let mut exp = log2 + 1 + B;
+        if quantize(max, exp) == 1 << N {
+            exp += 1;
+        }
+        Self((exp as u32) << 27 | quantize(b, exp) << 18 | quantize(g, exp) << 9 | quantize(r, exp))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the red component.
+    #[inline]
+    #[must_use]
+    pub fn r(self) -> f32 {
+        self.component(0)
+    }
+
+    /// Returns the green component.
+    #[inline]
+    #[must_use]
+    pub fn g(self) -> f32 {
+        self.component(9)
+    }
+
+    /// Returns the blue component.
+    #[inline]
+    #[must_use]
+    pub fn b(self) -> f32 {
+        self.component(18)
+    }
+
+    #[inline]
+    fn component(self, shift: u32) -> f32 {
+        let mantissa = (self.0 >> shift) & 0x1FF;
+        (mantissa as f32) * pow2(((self.0 >> 27) as i32) - B - N) as f32
+    }
+}
+
+/// `round(c / 2^(exp - B - N))`
+fn quantize(c: f32, exp: i32) -> u32 {
+    (f64::from(c) * pow2(B + N - exp) + 0.5) as u32
+}
+
+/// `2^exp`, for a small `exp`
+fn pow2(exp: i32) -> f64 {
+    f64::from_bits(((exp + 1023) as u64) << 52)
+}
+
+#[test]
+fn rgb9e5() {
+    for bits in [0, 1, 0x1FF, 0x7FFF_FFFF, 0xFFFF_FFFF, 0x8000_01FF, 0xF800_0100] {
+        let px = Rgb9E5::from_bits(bits);
+        assert_eq!(Rgb9E5::new(px.r(), px.g(), px.b()), px, "{bits:x}");
+    }
+    assert_eq!(Rgb9E5::new(1., 1., 1.).to_bits(), 0x8402_0100);
+    assert_eq!(Rgb9E5::new(Rgb9E5::MAX, 0., 0.).to_bits(), 0xF800_01FF);
+    assert_eq!(Rgb9E5::new(65535., 0., 0.).r(), Rgb9E5::MAX);
+    // rounding up to the next power of two increases the exponent
+    assert_eq!(Rgb9E5::new(0.9995, 0.5, 0.).r(), 1.);
+    assert_eq!(Rgb9E5::new(0.9995, 0.5, 0.).g(), 0.5);
+    assert_eq!(Rgb9E5::new(1e-30, 0., 0.).to_bits(), 0);
+    assert_eq!(Rgb9E5::new(f32::NAN, 3., 0.).to_bits(), Rgb9E5::new(0., 3., 0.).to_bits());
+}
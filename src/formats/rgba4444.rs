@@ -0,0 +1,92 @@
+use crate::formats::gray_a44::OutOfRangeError;
+
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A 16-bit packed RGBA pixel, with 4 bits for each of red, green, blue, and alpha.
+///
+/// Red is in the most significant bits: `0bRRRRGGGGBBBBAAAA`.
+///
+/// The components are stored in a native-endian `u16`. Use [`Rgba4444::to_bits()`]`.to_le_bytes()`
+/// or `to_be_bytes()` when the byte order of the target matters.
+///
+/// Conversions from and to [`Rgb<u8>`](crate::Rgb) and [`Rgba<u8>`](crate::Rgba) round to the nearest value.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Rgba4444, Rgba};
+///
+/// let pixel = Rgba4444::new(15, 15, 3, 1).unwrap();
+/// assert_eq!(pixel.to_bits(), 0xFF31);
+///
+/// let pixel = Rgba4444::from(Rgba::new(255, 128, 0, 255));
+/// assert_eq!(Rgba::from(pixel), Rgba::new(255, 136, 0, 255));
+/// ```
+pub struct Rgba4444(pub(crate) u16);
+
+impl Rgba4444 {
+    /// Tries to create a new [`Rgba4444`] from its components.
+    ///
+    /// Returns an error if any component is out of its range (`r` `0..=15`; `g` `0..=15`; `b` `0..=15`; `a` `0..=15`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Rgba4444;
+    /// use rgb::error::OutOfRangeError;
+    ///
+    /// assert!(Rgba4444::new(15, 15, 3, 1).is_ok());
+    /// assert_eq!(Rgba4444::new(99, 15, 3, 1), Err(OutOfRangeError));
+    /// ```
+    #[inline]
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Result<Self, OutOfRangeError> {
+        if r > 15 || g > 15 || b > 15 || a > 15 {
+            return Err(OutOfRangeError);
+        }
+        Ok(Self((r as u16) << 12 | (g as u16) << 8 | (b as u16) << 4 | (a as u16)))
+    }
+
+    /// Creates the pixel from its packed representation.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the packed representation of the pixel.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the 4-bit red component.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        (self.0 >> 12) as u8
+    }
+
+    /// Returns the 4-bit green component.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        (self.0 >> 8) as u8 & 0xF
+    }
+
+    /// Returns the 4-bit blue component.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        (self.0 >> 4) as u8 & 0xF
+    }
+
+    /// Returns the 4-bit alpha component.
+    #[inline]
+    #[must_use]
+    pub const fn a(self) -> u8 {
+        self.0 as u8 & 0xF
+    }
+}
@@ -0,0 +1,31 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default)]
+/// A `padding + Blue + Green + Red` pixel, e.g. of a 32-bit framebuffer.
+///
+/// The padding component is not alpha. It's ignored by `PartialEq`, `Ord` and `Hash`,
+/// and the pixel has three color components and no alpha in [`HetPixel`](crate::HetPixel).
+/// However, [`map_colors()`](crate::HetPixel::map_colors) also maps the padding with the color closure,
+/// so the closure is called four times, and [`try_from_colors_alpha()`](crate::HetPixel::try_from_colors_alpha)
+/// stores its `alpha` argument as the padding.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Xbgr;
+///
+/// let pixel: Xbgr<u8> = Xbgr { x: 0, b: 0, g: 0, r: 0 };
+/// ```
+#[doc(alias = "XBGR8")]
+#[doc(alias = "RGBX8888")]
+pub struct Xbgr<T> {
+    /// Padding, which is not alpha, and is ignored by comparisons
+    pub x: T,
+    /// Blue Component
+    pub b: T,
+    /// Green Component
+    pub g: T,
+    /// Red Component
+    pub r: T,
+}
@@ -0,0 +1,25 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A `Luma + Blue-difference chroma + Red-difference chroma` pixel, also known as YUV.
+///
+/// See [the `ycbcr` module](crate::ycbcr) for conversions to and from RGB.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Ycbcr;
+///
+/// let pixel: Ycbcr<u8> = Ycbcr { y: 16, cb: 128, cr: 128 };
+/// ```
+#[doc(alias = "YUV")]
+#[doc(alias = "YCbCr")]
+pub struct Ycbcr<T> {
+    /// Luma Component
+    pub y: T,
+    /// Blue-difference Chroma Component
+    pub cb: T,
+    /// Red-difference Chroma Component
+    pub cr: T,
+}
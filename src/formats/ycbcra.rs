@@ -0,0 +1,26 @@
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A `Luma + Blue-difference chroma + Red-difference chroma + Alpha` pixel, also known as YUVA.
+///
+/// See [the `ycbcr` module](crate::ycbcr) for conversions to and from RGBA.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::Ycbcra;
+///
+/// let pixel: Ycbcra<u8> = Ycbcra { y: 16, cb: 128, cr: 128, a: 255 };
+/// ```
+#[doc(alias = "YUVA")]
+pub struct Ycbcra<T, A = T> {
+    /// Luma Component
+    pub y: T,
+    /// Blue-difference Chroma Component
+    pub cb: T,
+    /// Red-difference Chroma Component
+    pub cr: T,
+    /// Alpha Component
+    pub a: A,
+}
@@ -1,4 +1,6 @@
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
 
 macro_rules! without_alpha {
     ($from_type:ident, $self_type:ident, {$($bit:tt),*}) => {
@@ -69,3 +71,354 @@ with_alpha!(Argb, Abgr, {r, g, b, a});
 with_alpha!(Bgra, Abgr, {r, g, b, a});
 
 alpha_to_no_alpha!(GrayA, Gray_v09, { v });
+alpha_to_no_alpha!(Ycbcra, Ycbcr, {y, cb, cr});
+
+with_alpha!(PremulArgb, PremulRgba, {r, g, b, a});
+with_alpha!(PremulBgra, PremulRgba, {r, g, b, a});
+with_alpha!(PremulAbgr, PremulRgba, {r, g, b, a});
+
+with_alpha!(PremulRgba, PremulArgb, {r, g, b, a});
+with_alpha!(PremulBgra, PremulArgb, {r, g, b, a});
+with_alpha!(PremulAbgr, PremulArgb, {r, g, b, a});
+
+with_alpha!(PremulRgba, PremulBgra, {r, g, b, a});
+with_alpha!(PremulArgb, PremulBgra, {r, g, b, a});
+with_alpha!(PremulAbgr, PremulBgra, {r, g, b, a});
+
+with_alpha!(PremulRgba, PremulAbgr, {r, g, b, a});
+with_alpha!(PremulArgb, PremulAbgr, {r, g, b, a});
+with_alpha!(PremulBgra, PremulAbgr, {r, g, b, a});
+
+macro_rules! no_alpha_to_padded {
+    ($from_type:ident, $self_type:ident, {$($bit:tt),*}) => {
+        impl<T: Default> From<$from_type<T>> for $self_type<T> {
+            /// The padding is set to `T::default()`
+            #[inline]
+            fn from(value: $from_type<T>) -> Self {
+                Self { $($bit: value.$bit),*, x: T::default() }
+            }
+        }
+    };
+}
+macro_rules! alpha_to_padded {
+    ($from_type:ident, $self_type:ident, {$($bit:tt),*}) => {
+        impl<T> From<$from_type<T>> for $self_type<T> {
+            /// The alpha is kept as the padding, so that the bytes of `Bgra` and `Bgrx` stay the same
+            #[inline]
+            fn from(value: $from_type<T>) -> Self {
+                Self { $($bit: value.$bit),*, x: value.a }
+            }
+        }
+    };
+}
+
+macro_rules! padded {
+    ($self_type:ident) => {
+        no_alpha_to_padded!(Rgb, $self_type, {r, g, b});
+        no_alpha_to_padded!(Bgr, $self_type, {r, g, b});
+        no_alpha_to_padded!(Grb, $self_type, {r, g, b});
+        without_alpha!($self_type, Rgb, {r, g, b});
+        without_alpha!($self_type, Bgr, {r, g, b});
+        without_alpha!($self_type, Grb, {r, g, b});
+        alpha_to_padded!(Rgba, $self_type, {r, g, b});
+        alpha_to_padded!(Argb, $self_type, {r, g, b});
+        alpha_to_padded!(Bgra, $self_type, {r, g, b});
+        alpha_to_padded!(Abgr, $self_type, {r, g, b});
+    };
+}
+
+padded!(Rgbx);
+padded!(Bgrx);
+padded!(Xrgb);
+padded!(Xbgr);
+
+without_alpha!(Bgrx, Rgbx, {r, g, b, x});
+without_alpha!(Xrgb, Rgbx, {r, g, b, x});
+without_alpha!(Xbgr, Rgbx, {r, g, b, x});
+
+without_alpha!(Rgbx, Bgrx, {r, g, b, x});
+without_alpha!(Xrgb, Bgrx, {r, g, b, x});
+without_alpha!(Xbgr, Bgrx, {r, g, b, x});
+
+without_alpha!(Rgbx, Xrgb, {r, g, b, x});
+without_alpha!(Bgrx, Xrgb, {r, g, b, x});
+without_alpha!(Xbgr, Xrgb, {r, g, b, x});
+
+without_alpha!(Rgbx, Xbgr, {r, g, b, x});
+without_alpha!(Bgrx, Xbgr, {r, g, b, x});
+without_alpha!(Xrgb, Xbgr, {r, g, b, x});
+
+use crate::{Argb1555, Bgr565, Rgb555, Rgb565, Rgba4444};
+
+/// Rounds an 8-bit value to `max`
+#[inline(always)]
+fn quantize(v: u8, max: u16) -> u16 {
+    (u16::from(v) * max + 127) / 255
+}
+
+/// Rounds a value in `0..=max` to 8 bits
+#[inline(always)]
+fn expand(v: u8, max: u16) -> u8 {
+    ((u16::from(v) * 255 + max / 2) / max) as u8
+}
+
+macro_rules! packed_without_alpha {
+    ($packed:ident, [$($bit:ident $bits:literal << $shift:literal),*]) => {
+        impl From<Rgb<u8>> for $packed {
+            #[inline]
+            fn from(px: Rgb<u8>) -> Self {
+                Self($(quantize(px.$bit, (1 << $bits) - 1) << $shift)|*)
+            }
+        }
+
+        impl From<Rgba<u8>> for $packed {
+            /// Discards alpha
+            #[inline]
+            fn from(px: Rgba<u8>) -> Self {
+                Self($(quantize(px.$bit, (1 << $bits) - 1) << $shift)|*)
+            }
+        }
+
+        impl From<$packed> for Rgb<u8> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Self { $($bit: expand(px.$bit(), (1 << $bits) - 1)),* }
+            }
+        }
+
+        impl From<$packed> for Rgba<u8> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Self { $($bit: expand(px.$bit(), (1 << $bits) - 1),)* a: 255 }
+            }
+        }
+    };
+}
+
+macro_rules! packed_with_alpha {
+    ($packed:ident, [$($bit:ident $bits:literal << $shift:literal),*]) => {
+        impl From<Rgb<u8>> for $packed {
+            /// Makes it opaque
+            #[inline]
+            fn from(px: Rgb<u8>) -> Self {
+                Rgba::new(px.r, px.g, px.b, 255).into()
+            }
+        }
+
+        impl From<Rgba<u8>> for $packed {
+            #[inline]
+            fn from(px: Rgba<u8>) -> Self {
+                Self($(quantize(px.$bit, (1 << $bits) - 1) << $shift)|*)
+            }
+        }
+
+        impl From<$packed> for Rgb<u8> {
+            /// Discards alpha
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Rgba::from(px).into()
+            }
+        }
+
+        impl From<$packed> for Rgba<u8> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Self { $($bit: expand(px.$bit(), (1 << $bits) - 1)),* }
+            }
+        }
+    };
+}
+
+packed_without_alpha!(Rgb565, [r 5 << 11, g 6 << 5, b 5 << 0]);
+packed_without_alpha!(Bgr565, [b 5 << 11, g 6 << 5, r 5 << 0]);
+packed_without_alpha!(Rgb555, [r 5 << 10, g 5 << 5, b 5 << 0]);
+packed_with_alpha!(Argb1555, [a 1 << 15, r 5 << 10, g 5 << 5, b 5 << 0]);
+packed_with_alpha!(Rgba4444, [r 4 << 12, g 4 << 8, b 4 << 4, a 4 << 0]);
+
+#[test]
+fn packed_rounding() {
+    for v in 0..=255u8 {
+        for bits in [1, 4, 5, 6] {
+            let max = (1 << bits) - 1;
+            let q = quantize(v, max);
+            assert_eq!(q, (f64::from(v) * f64::from(max) / 255.).round() as u16);
+            assert_eq!(expand(q as u8, max), (f64::from(q) * 255. / f64::from(max)).round() as u8);
+            // expanded values survive the roundtrip
+            assert_eq!(quantize(expand(q as u8, max), max), q);
+        }
+    }
+    let px = Rgba::new(10, 20, 30, 40);
+    assert_eq!(Rgba4444::from(px).to_bits(), 0x1122);
+    assert_eq!(Rgb::from(Argb1555::from(px)), Rgb::new(8, 16, 33));
+    assert_eq!(Rgba::from(Argb1555::from(px)).a, 0);
+    assert_eq!(Rgba::from(Argb1555::from(Rgb::new(1, 2, 3))).a, 255);
+    assert_eq!(Bgr565::from(Rgb::new(255, 0, 0)).to_bits(), 0x001F);
+    assert_eq!(Rgb565::from(Rgb::new(255, 0, 0)).to_bits(), 0xF800);
+    assert_eq!(Rgb555::from_bits(0xFFFF).to_bits(), 0x7FFF);
+    assert_eq!(Rgba::from(Rgb565::from_bits(0xFFFF)), Rgba::new(255, 255, 255, 255));
+}
+
+use crate::{Bgr10A2, R11G11B10Float, Rgb10A2, Rgb9E5};
+
+/// Rounds a 16-bit value to `max`
+#[inline(always)]
+fn quantize_u16(v: u16, max: u32) -> u16 {
+    ((u32::from(v) * max + 32767) / 65535) as u16
+}
+
+/// Rounds a value in `0..=max` to 16 bits
+#[inline(always)]
+fn expand_u16(v: u16, max: u32) -> u16 {
+    ((u32::from(v) * 65535 + max / 2) / max) as u16
+}
+
+/// Rounds `0.0..=1.0` to `0..=max`, clamping out-of-range values
+#[inline(always)]
+fn unorm_from_f32(v: f32, max: u16) -> u16 {
+    if v.is_nan() {
+        return 0;
+    }
+    (v.clamp(0., 1.) * f32::from(max) + 0.5) as u16
+}
+
+macro_rules! packed_10a2 {
+    ($packed:ident) => {
+        impl From<Rgba<u16>> for $packed {
+            #[inline]
+            fn from(px: Rgba<u16>) -> Self {
+                let [r, g, b] = [px.r, px.g, px.b].map(|c| quantize_u16(c, 1023));
+                Self::new(r, g, b, quantize_u16(px.a, 3) as u8).unwrap()
+            }
+        }
+
+        impl From<$packed> for Rgba<u16> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                let [r, g, b] = [px.r(), px.g(), px.b()].map(|c| expand_u16(c, 1023));
+                Self { r, g, b, a: expand_u16(px.a().into(), 3) }
+            }
+        }
+
+        impl From<Rgba<f32>> for $packed {
+            /// Clamps the components to `0.0..=1.0`
+            #[inline]
+            fn from(px: Rgba<f32>) -> Self {
+                let [r, g, b] = [px.r, px.g, px.b].map(|c| unorm_from_f32(c, 1023));
+                Self::new(r, g, b, unorm_from_f32(px.a, 3) as u8).unwrap()
+            }
+        }
+
+        impl From<$packed> for Rgba<f32> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                let [r, g, b] = [px.r(), px.g(), px.b()].map(|c| f32::from(c) / 1023.);
+                Self { r, g, b, a: f32::from(px.a()) / 3. }
+            }
+        }
+
+        impl From<Rgb<f32>> for $packed {
+            /// Clamps the components to `0.0..=1.0`, and makes it opaque
+            #[inline]
+            fn from(px: Rgb<f32>) -> Self {
+                Rgba::new(px.r, px.g, px.b, 1.).into()
+            }
+        }
+
+        impl From<$packed> for Rgb<f32> {
+            /// Discards alpha
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Rgba::from(px).into()
+            }
+        }
+    };
+}
+
+packed_10a2!(Rgb10A2);
+packed_10a2!(Bgr10A2);
+
+macro_rules! packed_hdr {
+    ($packed:ident) => {
+        impl From<Rgb<f32>> for $packed {
+            #[inline]
+            fn from(px: Rgb<f32>) -> Self {
+                Self::new(px.r, px.g, px.b)
+            }
+        }
+
+        impl From<$packed> for Rgb<f32> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Self { r: px.r(), g: px.g(), b: px.b() }
+            }
+        }
+
+        impl From<Rgba<f32>> for $packed {
+            /// Discards alpha
+            #[inline]
+            fn from(px: Rgba<f32>) -> Self {
+                Self::new(px.r, px.g, px.b)
+            }
+        }
+
+        impl From<$packed> for Rgba<f32> {
+            #[inline]
+            fn from(px: $packed) -> Self {
+                Self { r: px.r(), g: px.g(), b: px.b(), a: 1. }
+            }
+        }
+
+        impl From<Rgba<u16>> for $packed {
+            /// Discards alpha
+            #[inline]
+            fn from(px: Rgba<u16>) -> Self {
+                let [r, g, b] = [px.r, px.g, px.b].map(|c| f32::from(c) / 65535.);
+                Self::new(r, g, b)
+            }
+        }
+
+        impl From<$packed> for Rgba<u16> {
+            /// Clamps the components to `0.0..=1.0`
+            #[inline]
+            fn from(px: $packed) -> Self {
+                let [r, g, b] = [px.r(), px.g(), px.b()].map(|c| unorm_from_f32(c, 65535));
+                Self { r, g, b, a: 65535 }
+            }
+        }
+    };
+}
+
+packed_hdr!(R11G11B10Float);
+packed_hdr!(Rgb9E5);
+
+#[test]
+fn packed_32bit_conversions() {
+    for v in 0..=1023 {
+        assert_eq!(quantize_u16(expand_u16(v, 1023), 1023), v);
+        assert_eq!(unorm_from_f32(f32::from(v) / 1023., 1023), v);
+    }
+    let px = Rgba::<u16>::new(65535, 32768, 0, 65535);
+    assert_eq!(Rgba::from(Rgb10A2::from(px)), Rgba::new(65535, 32800, 0, 65535));
+    assert_eq!(Rgb10A2::from(px).to_bits(), 0xC008_03FF);
+    assert_eq!(Bgr10A2::from(px).to_bits(), 0xFFF8_0000);
+    assert_eq!(Rgb10A2::from(Rgb::new(2., -1., f32::NAN)).to_bits(), 0xC000_03FF);
+    assert_eq!(Rgba::<f32>::from(Bgr10A2::new(1023, 0, 0, 1).unwrap()), Rgba::new(1., 0., 0., 1. / 3.));
+    assert_eq!(Rgba::<u16>::from(Rgb9E5::from(px)), Rgba::new(65535, 32768, 0, 65535));
+    assert_eq!(Rgba::<u16>::from(R11G11B10Float::new(2., 0.5, -1.)), Rgba::new(65535, 32768, 0, 65535));
+    assert_eq!(Rgba::<f32>::from(R11G11B10Float::from(Rgba::new(0.25, 4., 8., 0.))), Rgba::new(0.25, 4., 8., 1.));
+}
+
+#[test]
+fn padded_conversions() {
+    let bgra = Bgra::new_bgra(1u8, 2, 3, 4);
+    let bgrx = Bgrx::from(bgra);
+    assert_eq!((bgrx.b, bgrx.g, bgrx.r, bgrx.x), (1, 2, 3, 4));
+    assert_eq!(Rgb::from(bgrx), Rgb::new(3, 2, 1));
+    assert_eq!(Xrgb::from(Rgb::new(3u16, 2, 1)), Xrgb::new_xrgb(0, 3, 2, 1));
+    assert_eq!(Xrgb::from(Rgb::new(3u16, 2, 1)).x, 0);
+
+    let xbgr = Xbgr::from(bgrx);
+    assert_eq!(xbgr.x, 4);
+    assert_eq!(Rgbx::from(xbgr), Rgbx::new(3, 2, 1, 4));
+    assert_eq!(Bgrx::from(Rgbx::from(xbgr)).x, 4);
+}
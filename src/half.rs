@@ -0,0 +1,187 @@
+//! Half-precision float components, using [`f16`] from the [`half` crate](https://lib.rs/crates/half).
+//!
+//! All pixel types work with `f16` components, e.g. [`RGBA16F`](crate::RGBA16F) is `Rgba<f16>`.
+//! When the `bytemuck` feature is enabled, they can be cast to and from bytes too.
+//!
+//! Converting slices with [`to_f32_slice()`] and [`from_f32_slice()`] is much faster than converting
+//! pixels one by one, because it uses the F16C or NEON instructions when available.
+//! With the `std` feature, the instructions are detected at run time.
+//!
+//! ```
+//! use rgb::{Rgba, RGBA32F};
+//! use rgb::half::f16;
+//!
+//! let half = [Rgba::new(f16::ONE, f16::ZERO, f16::from_f32(0.5), f16::ONE); 100];
+//! let mut float = [RGBA32F::new(0., 0., 0., 0.); 100];
+//! rgb::half::to_f32_slice(&half, &mut float);
+//! assert_eq!(float[99], Rgba::new(1., 0., 0.5, 1.));
+//! ```
+
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, HetPixel, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use ::half::slice::HalfFloatSliceExt;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+pub use ::half::f16;
+
+/// A pixel type with only `f16` components, which can be converted in bulk.
+///
+/// This trait is sealed, and implemented for all generic pixel types in this crate.
+pub trait HalfPixel: HetPixel<ColorComponent = f16, AlphaComponent = f16> + Default + sealed::Sealed {
+    /// The same pixel type with `f32` components
+    type F32: HetPixel<ColorComponent = f32, AlphaComponent = f32> + Default;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! half_pixel {
+    ($name:ident) => {
+        impl sealed::Sealed for $name<f16> {}
+        impl HalfPixel for $name<f16> {
+            type F32 = $name<f32>;
+        }
+    };
+}
+
+half_pixel!(Rgb);
+half_pixel!(Bgr);
+half_pixel!(Grb);
+half_pixel!(Gray_v09);
+half_pixel!(Rgbw);
+half_pixel!(Rgba);
+half_pixel!(Argb);
+half_pixel!(Bgra);
+half_pixel!(Abgr);
+half_pixel!(GrayA);
+half_pixel!(Ycbcr);
+half_pixel!(Ycbcra);
+half_pixel!(PremulRgba);
+half_pixel!(PremulArgb);
+half_pixel!(PremulBgra);
+half_pixel!(PremulAbgr);
+
+#[inline]
+fn components<P: HalfPixel>(px: &[P]) -> &[f16] {
+    debug_assert_eq!(core::mem::size_of::<P>(), 2 * usize::from(P::NUM_COMPONENTS));
+    // SAFETY: the sealed HalfPixel is only implemented for repr(C) structs of f16 fields
+    unsafe { core::slice::from_raw_parts(px.as_ptr().cast(), px.len() * usize::from(P::NUM_COMPONENTS)) }
+}
+
+#[inline]
+fn components_mut<P: HalfPixel>(px: &mut [P]) -> &mut [f16] {
+    // SAFETY: see components()
+    unsafe { core::slice::from_raw_parts_mut(px.as_mut_ptr().cast(), px.len() * usize::from(P::NUM_COMPONENTS)) }
+}
+
+#[inline]
+fn f32_components<P: HalfPixel>(px: &[P::F32]) -> &[f32] {
+    debug_assert_eq!(core::mem::size_of::<P::F32>(), 4 * usize::from(P::NUM_COMPONENTS));
+    // SAFETY: the F32 types are the same repr(C) structs with f32 fields
+    unsafe { core::slice::from_raw_parts(px.as_ptr().cast(), px.len() * usize::from(P::NUM_COMPONENTS)) }
+}
+
+#[inline]
+fn f32_components_mut<P: HalfPixel>(px: &mut [P::F32]) -> &mut [f32] {
+    // SAFETY: see f32_components()
+    unsafe { core::slice::from_raw_parts_mut(px.as_mut_ptr().cast(), px.len() * usize::from(P::NUM_COMPONENTS)) }
+}
+
+/// Converts all pixels of `src` to `f32` components, writing them to `dst`.
+///
+/// The conversion is exact.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn to_f32_slice<P: HalfPixel>(src: &[P], dst: &mut [P::F32]) {
+    assert_eq!(src.len(), dst.len());
+    components(src).convert_to_f32_slice(f32_components_mut::<P>(dst));
+}
+
+/// Converts all pixels of `src` to `f16` components, writing them to `dst`.
+///
+/// The values are rounded to the nearest `f16`. Values too large for `f16` become infinity.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::GrayA;
+/// use rgb::half::f16;
+///
+/// let src = [GrayA::new(0.25f32, 1e6)];
+/// let mut dst = [GrayA::new(f16::ZERO, f16::ZERO)];
+/// rgb::half::from_f32_slice(&src, &mut dst);
+/// assert_eq!(dst[0], GrayA::new(f16::from_f32(0.25), f16::INFINITY));
+/// ```
+pub fn from_f32_slice<P: HalfPixel>(src: &[P::F32], dst: &mut [P]) {
+    assert_eq!(src.len(), dst.len());
+    components_mut(dst).convert_from_f32_slice(f32_components::<P>(src));
+}
+
+/// Converts all pixels to a new `Vec` of pixels with `f32` components, e.g. `&[RGBA16F]` to `Vec<RGBA32F>`.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn to_f32_vec<P: HalfPixel>(src: &[P]) -> Vec<P::F32> {
+    let mut dst = vec![P::F32::default(); src.len()];
+    to_f32_slice(src, &mut dst);
+    dst
+}
+
+/// Converts all pixels to a new `Vec` of pixels with `f16` components, e.g. `&[RGBA32F]` to `Vec<RGBA16F>`.
+///
+/// The target pixel type usually needs to be specified explicitly.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn from_f32_vec<P: HalfPixel>(src: &[P::F32]) -> Vec<P> {
+    let mut dst = vec![P::default(); src.len()];
+    from_f32_slice(src, &mut dst);
+    dst
+}
+
+#[test]
+fn half_slices() {
+    use crate::Pixel;
+
+    let half = [Rgb::new(f16::ONE, f16::NAN, f16::MAX), Rgb::new(f16::MIN_POSITIVE_SUBNORMAL, f16::NEG_ZERO, f16::NEG_INFINITY)];
+    let mut float = [Rgb::new(0f32, 0., 0.); 2];
+    to_f32_slice(&half, &mut float);
+    assert_eq!(float[0].r, 1.);
+    assert!(float[0].g.is_nan());
+    assert_eq!(float[0].b, 65504.);
+    assert_eq!(float[1], Rgb::new(f32::from(f16::MIN_POSITIVE_SUBNORMAL), -0., f32::NEG_INFINITY));
+
+    let mut back = [Rgb::new(f16::ZERO, f16::ZERO, f16::ZERO); 2];
+    from_f32_slice(&float, &mut back);
+    assert_eq!(back[1], half[1]);
+    assert_eq!(back[0].map(f16::to_bits), half[0].map(f16::to_bits));
+
+    // the slice conversions may use a SIMD path for long inputs
+    let src: [_; 33] = core::array::from_fn(|i| PremulBgra::new_bgra(i as f32, 0.5, -2., 1. / 3.));
+    let mut dst = [PremulBgra::new_bgra(f16::ZERO, f16::ZERO, f16::ZERO, f16::ZERO); 33];
+    from_f32_slice(&src, &mut dst);
+    assert_eq!(dst[32], PremulBgra::new_bgra(f16::from_f32(32.), f16::from_f32(0.5), f16::from_f32(-2.), f16::from_f32(1. / 3.)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn half_vecs() {
+    let src = [Rgbw::new(0.5f32, 1., 2., 3.); 5];
+    let half: Vec<Rgbw<f16>> = from_f32_vec(&src);
+    assert_eq!(half[4].w, f16::from_f32(3.));
+    assert_eq!(to_f32_vec(&half), src);
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn half_cast() {
+    let px = [crate::RGBA16F::new(f16::ONE, f16::ZERO, f16::ZERO, f16::ONE)];
+    let bits: &[u16] = bytemuck::cast_slice(&px);
+    assert_eq!(bits, &[0x3C00, 0, 0, 0x3C00]);
+}
@@ -0,0 +1,139 @@
+/// Parses `#rgb`, `#rrggbb`, and with `alpha` also `#rgba` and `#rrggbbaa`.
+///
+/// It's a `const fn` for the macros. Errors are panics, which become compile errors in `const` blocks.
+#[doc(hidden)]
+#[must_use]
+#[track_caller]
+pub const fn parse_hex_literal(hex: &str, alpha: bool) -> [u8; 4] {
+    let bytes = hex.as_bytes();
+    let digits = match bytes {
+        [b'#', rest @ ..] => rest,
+        rest => rest,
+    };
+    let short = match digits.len() {
+        3 => true,
+        6 => false,
+        4 | 8 if alpha => digits.len() == 4,
+        4 | 8 => panic!("this color has alpha; use the macro for a type with alpha"),
+        _ => panic!("hex color must be #rgb, #rgba, #rrggbb, or #rrggbbaa"),
+    };
+    let mut out = [255; 4];
+    let mut i = 0;
+    while i * if short { 1 } else { 2 } < digits.len() {
+        out[i] = if short {
+            hex_digit(digits[i]) * 17
+        } else {
+            hex_digit(digits[i * 2]) << 4 | hex_digit(digits[i * 2 + 1])
+        };
+        i += 1;
+    }
+    out
+}
+
+#[track_caller]
+const fn hex_digit(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("invalid hex digit in color"),
+    }
+}
+
+/// Creates an [`Rgb<u8>`](crate::Rgb) from a hex color literal, at compile time.
+///
+/// Accepts `"#rgb"` and `"#rrggbb"`, with an optional `#`. A malformed literal is a compile error.
+///
+/// ```
+/// use rgb::{rgb8, RGB8};
+///
+/// const ORANGE: RGB8 = rgb8!("#ff8800");
+/// assert_eq!(ORANGE, RGB8::new(0xff, 0x88, 0x00));
+/// assert_eq!(rgb8!("F80"), ORANGE);
+/// ```
+///
+/// ```compile_fail
+/// let _ = rgb::rgb8!("#ff880");
+/// ```
+#[macro_export]
+macro_rules! rgb8 {
+    ($hex:literal) => {
+        const {
+            let c = $crate::__parse_hex_literal($hex, false);
+            $crate::Rgb::<u8>::new(c[0], c[1], c[2])
+        }
+    };
+}
+
+/// Creates an [`Rgba<u8>`](crate::Rgba) from a hex color literal, at compile time.
+///
+/// Accepts `"#rgb"`, `"#rgba"`, `"#rrggbb"`, and `"#rrggbbaa"`, with an optional `#`.
+/// Alpha is 255 if omitted. A malformed literal is a compile error.
+///
+/// ```
+/// use rgb::{rgba8, RGBA8};
+///
+/// const THEME: [RGBA8; 2] = [rgba8!("#ff880080"), rgba8!("#000")];
+/// assert_eq!(THEME[0], RGBA8::new(0xff, 0x88, 0x00, 0x80));
+/// assert_eq!(THEME[1], RGBA8::new(0, 0, 0, 255));
+/// ```
+///
+/// ```compile_fail
+/// let _ = rgb::rgba8!("#ff88zz");
+/// ```
+#[macro_export]
+macro_rules! rgba8 {
+    ($hex:literal) => {
+        const {
+            let c = $crate::__parse_hex_literal($hex, true);
+            $crate::Rgba::<u8>::new(c[0], c[1], c[2], c[3])
+        }
+    };
+}
+
+/// Creates a [`Bgr<u8>`](crate::Bgr) from a hex color literal, at compile time.
+///
+/// The literal is in the usual `"#rrggbb"` order, same as for [`rgb8!`](crate::rgb8).
+///
+/// ```
+/// use rgb::{bgr8, BGR8};
+///
+/// assert_eq!(bgr8!("#ff8800"), BGR8 { b: 0x00, g: 0x88, r: 0xff });
+/// ```
+#[macro_export]
+macro_rules! bgr8 {
+    ($hex:literal) => {
+        const {
+            let c = $crate::__parse_hex_literal($hex, false);
+            $crate::Bgr::<u8>::new_bgr(c[2], c[1], c[0])
+        }
+    };
+}
+
+/// Creates a [`Bgra<u8>`](crate::Bgra) from a hex color literal, at compile time.
+///
+/// The literal is in the usual `"#rrggbbaa"` order, same as for [`rgba8!`](crate::rgba8).
+///
+/// ```
+/// use rgb::{bgra8, BGRA8};
+///
+/// assert_eq!(bgra8!("#ff880080"), BGRA8 { b: 0x00, g: 0x88, r: 0xff, a: 0x80 });
+/// ```
+#[macro_export]
+macro_rules! bgra8 {
+    ($hex:literal) => {
+        const {
+            let c = $crate::__parse_hex_literal($hex, true);
+            $crate::Bgra::<u8>::new_bgra(c[2], c[1], c[0], c[3])
+        }
+    };
+}
+
+#[test]
+fn hex_literals() {
+    assert_eq!(crate::Rgb::new(0x12, 0x34, 0x56), rgb8!("#123456"));
+    assert_eq!(crate::Rgba::new(0x11, 0x22, 0x33, 0x44), rgba8!("1234"));
+    assert_eq!(crate::Rgba::new(0xAB, 0xCD, 0xEF, 0xFF), rgba8!("#abCDef"));
+    assert_eq!(crate::Bgra::new_bgra(3, 2, 1, 0), bgra8!("#01020300"));
+    assert_eq!(crate::Bgr::new_bgr(0xff, 0, 0), bgr8!("#00f"));
+}
@@ -0,0 +1,794 @@
+//! 2D images of pixels with width, height and stride, borrowing a slice like `&[Rgba<u8>]`.
+//!
+//! The stride is the distance between the starts of consecutive rows, in pixels. It can be larger
+//! than the width, e.g. when the rows are padded, or the image is a sub-rectangle of a larger one.
+//!
+//! ```
+//! use rgb::Rgb;
+//! use rgb::image::ImageView;
+//!
+//! let pixels = [Rgb::new(0u8, 0, 0); 4 * 3];
+//! let image = ImageView::new(&pixels, 4, 3).unwrap();
+//! let sub = image.sub_view(1, 1, 2, 2).unwrap();
+//! assert_eq!(sub.stride(), 4);
+//! for row in sub.rows() {
+//!     assert_eq!(row.len(), 2);
+//! }
+//! assert_eq!(image.get(3, 2), Some(&Rgb::new(0, 0, 0)));
+//! assert_eq!(image.get(4, 0), None);
+//! ```
+//!
+//! With the `bytemuck` feature, images can borrow bytes, e.g. of a framebuffer with rows padded to some alignment:
+//!
+//! ```
+//! # #[cfg(feature = "bytemuck")] {
+//! use rgb::Bgra;
+//! use rgb::image::ImageView;
+//!
+//! let bytes = [0u8; 64 * 2];
+//! let image = ImageView::<Bgra<u8>>::from_bytes(&bytes, 15, 2, 64).unwrap();
+//! assert_eq!(image.stride(), 16);
+//! # }
+//! ```
+
+use core::fmt;
+use core::ops::{Index, IndexMut};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Error returned when the width, height and stride don't fit the buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LayoutError {
+    /// The stride is smaller than the width
+    StrideTooSmall,
+    /// The buffer is too short for the rows
+    BufferTooSmall,
+    /// The bytes aren't aligned for the pixel type, or the stride in bytes isn't a multiple of the pixel size
+    Misaligned,
+    /// The size of the image doesn't fit in `usize`
+    Overflow,
+}
+
+impl fmt::Display for LayoutError {
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::StrideTooSmall => "the stride is smaller than the width",
+            Self::BufferTooSmall => "the buffer is too small for the image",
+            Self::Misaligned => "the bytes are misaligned for the pixel type",
+            Self::Overflow => "the image is too large",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutError {}
+
+/// Number of elements needed for `height` rows of `width`, starting `stride` apart. The last row needs no padding.
+#[inline]
+fn required_len(width: usize, height: usize, stride: usize) -> Result<usize, LayoutError> {
+    if stride < width {
+        return Err(LayoutError::StrideTooSmall);
+    }
+    if height == 0 {
+        return Ok(0);
+    }
+    stride.checked_mul(height - 1).and_then(|n| n.checked_add(width)).ok_or(LayoutError::Overflow)
+}
+
+/// Start and end of a sub-rectangle in the buffer, if it's inside the image
+#[inline]
+fn sub_range(img: (usize, usize, usize), x: usize, y: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+    let (img_width, img_height, stride) = img;
+    if x.checked_add(width)? > img_width || y.checked_add(height)? > img_height {
+        return None;
+    }
+    if height == 0 {
+        return Some((0, 0));
+    }
+    let start = y * stride + x;
+    Some((start, start + (height - 1) * stride + width))
+}
+
+/// Converts a stride in bytes to pixels, and casts enough bytes for the image
+#[cfg(feature = "bytemuck")]
+#[inline]
+fn check_bytes<P>(len: usize, ptr: *const u8, width: usize, height: usize, stride_bytes: usize) -> Result<(usize, usize), LayoutError> {
+    let size = core::mem::size_of::<P>();
+    if size == 0 || stride_bytes % size != 0 || ptr.align_offset(core::mem::align_of::<P>()) != 0 {
+        return Err(LayoutError::Misaligned);
+    }
+    let stride = stride_bytes / size;
+    let pixels = required_len(width, height, stride)?;
+    if pixels.checked_mul(size).ok_or(LayoutError::Overflow)? > len {
+        return Err(LayoutError::BufferTooSmall);
+    }
+    Ok((stride, pixels))
+}
+
+/// An immutable 2D view of pixels. See [the module docs](self).
+///
+/// It's `Copy`, and the pixels and rows it returns borrow the underlying slice, not the view.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageView<'a, P> {
+    buf: &'a [P],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+/// A mutable 2D view of pixels. See [the module docs](self).
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImageViewMut<'a, P> {
+    buf: &'a mut [P],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a, P> ImageView<'a, P> {
+    /// Views `buf` as rows of `width` pixels, without padding.
+    ///
+    /// The buffer may be longer than `width * height`, and the rest is ignored.
+    #[inline]
+    pub fn new(buf: &'a [P], width: usize, height: usize) -> Result<Self, LayoutError> {
+        Self::with_stride(buf, width, height, width)
+    }
+
+    /// Views `buf` as rows of `width` pixels, starting every `stride` pixels.
+    ///
+    /// The last row doesn't need the padding, so the buffer must have at least `(height - 1) * stride + width` pixels.
+    #[inline]
+    pub fn with_stride(buf: &'a [P], width: usize, height: usize, stride: usize) -> Result<Self, LayoutError> {
+        let len = required_len(width, height, stride)?;
+        let buf = buf.get(..len).ok_or(LayoutError::BufferTooSmall)?;
+        Ok(Self { buf, width, height, stride })
+    }
+
+    /// Views bytes as rows of `width` pixels, starting every `stride_bytes` bytes.
+    ///
+    /// The bytes must be aligned for `P`, and the stride must be a multiple of `size_of::<P>()`.
+    #[cfg(feature = "bytemuck")]
+    pub fn from_bytes(bytes: &'a [u8], width: usize, height: usize, stride_bytes: usize) -> Result<Self, LayoutError> where P: bytemuck::Pod {
+        let (stride, pixels) = check_bytes::<P>(bytes.len(), bytes.as_ptr(), width, height, stride_bytes)?;
+        let buf = bytemuck::try_cast_slice(&bytes[..pixels * core::mem::size_of::<P>()]).map_err(|_| LayoutError::Misaligned)?;
+        Ok(Self { buf, width, height, stride })
+    }
+
+    /// Width in pixels
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Distance between the starts of rows, in pixels
+    #[inline]
+    #[must_use]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The underlying pixels, from the first pixel of the first row to the last pixel of the last row, including the padding between rows
+    #[inline]
+    #[must_use]
+    pub fn buf(&self) -> &'a [P] {
+        self.buf
+    }
+
+    /// The pixel at column `x` and row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a P> {
+        if x < self.width && y < self.height {
+            self.buf.get(y * self.stride + x)
+        } else {
+            None
+        }
+    }
+
+    /// The pixels of row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn row(&self, y: usize) -> Option<&'a [P]> {
+        if y < self.height {
+            self.buf.get(y * self.stride..y * self.stride + self.width)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates the rows, top to bottom. Every row has `width` pixels.
+    #[inline]
+    pub fn rows(&self) -> Rows<'a, P> {
+        Rows { buf: self.buf, width: self.width, stride: self.stride, remaining: self.height }
+    }
+
+    /// Iterates all the pixels, row by row, skipping the padding
+    #[inline]
+    pub fn pixels(&self) -> impl DoubleEndedIterator<Item = &'a P> + 'a {
+        self.rows().flatten()
+    }
+
+    /// A view of the `width` × `height` rectangle at `x`, `y`, or `None` if it doesn't fit in the image
+    #[inline]
+    #[must_use]
+    pub fn sub_view(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Self> {
+        let (start, end) = sub_range((self.width, self.height, self.stride), x, y, width, height)?;
+        Some(Self { buf: &self.buf[start..end], width, height, stride: self.stride })
+    }
+
+    /// Copies the pixels to an image without padding
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn to_image_buf(&self) -> ImageBuf<P> where P: Clone {
+        let mut buf = Vec::with_capacity(self.width * self.height);
+        for row in self.rows() {
+            buf.extend_from_slice(row);
+        }
+        ImageBuf { buf, width: self.width, height: self.height }
+    }
+}
+
+impl<'a, P> ImageViewMut<'a, P> {
+    /// Views `buf` as rows of `width` pixels, without padding.
+    ///
+    /// The buffer may be longer than `width * height`, and the rest is ignored.
+    #[inline]
+    pub fn new(buf: &'a mut [P], width: usize, height: usize) -> Result<Self, LayoutError> {
+        Self::with_stride(buf, width, height, width)
+    }
+
+    /// Views `buf` as rows of `width` pixels, starting every `stride` pixels.
+    ///
+    /// The last row doesn't need the padding, so the buffer must have at least `(height - 1) * stride + width` pixels.
+    #[inline]
+    pub fn with_stride(buf: &'a mut [P], width: usize, height: usize, stride: usize) -> Result<Self, LayoutError> {
+        let len = required_len(width, height, stride)?;
+        let buf = buf.get_mut(..len).ok_or(LayoutError::BufferTooSmall)?;
+        Ok(Self { buf, width, height, stride })
+    }
+
+    /// Views bytes as rows of `width` pixels, starting every `stride_bytes` bytes.
+    ///
+    /// The bytes must be aligned for `P`, and the stride must be a multiple of `size_of::<P>()`.
+    #[cfg(feature = "bytemuck")]
+    pub fn from_bytes_mut(bytes: &'a mut [u8], width: usize, height: usize, stride_bytes: usize) -> Result<Self, LayoutError> where P: bytemuck::Pod {
+        let (stride, pixels) = check_bytes::<P>(bytes.len(), bytes.as_ptr(), width, height, stride_bytes)?;
+        let buf = bytemuck::try_cast_slice_mut(&mut bytes[..pixels * core::mem::size_of::<P>()]).map_err(|_| LayoutError::Misaligned)?;
+        Ok(Self { buf, width, height, stride })
+    }
+
+    /// Width in pixels
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Distance between the starts of rows, in pixels
+    #[inline]
+    #[must_use]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Borrows as an immutable view
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView { buf: self.buf, width: self.width, height: self.height, stride: self.stride }
+    }
+
+    /// Reborrows, so that the view can be passed by value without giving it up
+    #[inline]
+    #[must_use]
+    pub fn reborrow(&mut self) -> ImageViewMut<'_, P> {
+        ImageViewMut { buf: self.buf, width: self.width, height: self.height, stride: self.stride }
+    }
+
+    /// The underlying pixels, including the padding between rows. See [`ImageView::buf()`].
+    #[inline]
+    #[must_use]
+    pub fn buf_mut(&mut self) -> &mut [P] {
+        self.buf
+    }
+
+    /// The pixel at column `x` and row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&P> {
+        self.as_view().get(x, y)
+    }
+
+    /// The pixel at column `x` and row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut P> {
+        if x < self.width && y < self.height {
+            self.buf.get_mut(y * self.stride + x)
+        } else {
+            None
+        }
+    }
+
+    /// The pixels of row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn row(&self, y: usize) -> Option<&[P]> {
+        self.as_view().row(y)
+    }
+
+    /// The pixels of row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [P]> {
+        if y < self.height {
+            self.buf.get_mut(y * self.stride..y * self.stride + self.width)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates the rows, top to bottom
+    #[inline]
+    pub fn rows(&self) -> Rows<'_, P> {
+        self.as_view().rows()
+    }
+
+    /// Iterates the rows mutably, top to bottom. Every row has `width` pixels.
+    #[inline]
+    pub fn rows_mut(&mut self) -> RowsMut<'_, P> {
+        RowsMut { buf: self.buf, width: self.width, stride: self.stride, remaining: self.height }
+    }
+
+    /// Iterates all the pixels mutably, row by row, skipping the padding
+    #[inline]
+    pub fn pixels_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut P> + '_ {
+        self.rows_mut().flatten()
+    }
+
+    /// Consumes the view, and iterates the rows mutably for its whole lifetime
+    #[inline]
+    pub fn into_rows_mut(self) -> RowsMut<'a, P> {
+        RowsMut { buf: self.buf, width: self.width, stride: self.stride, remaining: self.height }
+    }
+
+    /// A view of the `width` × `height` rectangle at `x`, `y`, or `None` if it doesn't fit in the image
+    #[inline]
+    #[must_use]
+    pub fn sub_view(&self, x: usize, y: usize, width: usize, height: usize) -> Option<ImageView<'_, P>> {
+        self.as_view().sub_view(x, y, width, height)
+    }
+
+    /// A mutable view of the `width` × `height` rectangle at `x`, `y`, or `None` if it doesn't fit in the image
+    #[inline]
+    #[must_use]
+    pub fn sub_view_mut(&mut self, x: usize, y: usize, width: usize, height: usize) -> Option<ImageViewMut<'_, P>> {
+        let (start, end) = sub_range((self.width, self.height, self.stride), x, y, width, height)?;
+        Some(ImageViewMut { buf: &mut self.buf[start..end], width, height, stride: self.stride })
+    }
+
+    /// Sets every pixel to `value`, leaving the padding alone
+    #[inline]
+    pub fn fill(&mut self, value: P) where P: Clone {
+        for row in self.rows_mut() {
+            row.fill(value.clone());
+        }
+    }
+
+    /// Copies the pixels of `src`, which must have the same width and height.
+    ///
+    /// # Panics
+    ///
+    /// If the sizes are different.
+    pub fn copy_from(&mut self, src: ImageView<'_, P>) where P: Copy {
+        assert_eq!((self.width, self.height), (src.width, src.height));
+        for (dst, src) in self.rows_mut().zip(src.rows()) {
+            dst.copy_from_slice(src);
+        }
+    }
+}
+
+impl<'a, P> From<ImageViewMut<'a, P>> for ImageView<'a, P> {
+    #[inline]
+    fn from(view: ImageViewMut<'a, P>) -> Self {
+        Self { buf: view.buf, width: view.width, height: view.height, stride: view.stride }
+    }
+}
+
+impl<P> Index<(usize, usize)> for ImageView<'_, P> {
+    type Output = P;
+
+    /// The pixel at `(x, y)`. Panics if it's outside the image, even if it's in the padding.
+    #[inline]
+    #[track_caller]
+    fn index(&self, (x, y): (usize, usize)) -> &P {
+        self.get(x, y).expect("pixel outside of the image")
+    }
+}
+
+impl<P> Index<(usize, usize)> for ImageViewMut<'_, P> {
+    type Output = P;
+
+    /// The pixel at `(x, y)`. Panics if it's outside the image, even if it's in the padding.
+    #[inline]
+    #[track_caller]
+    fn index(&self, (x, y): (usize, usize)) -> &P {
+        self.get(x, y).expect("pixel outside of the image")
+    }
+}
+
+impl<P> IndexMut<(usize, usize)> for ImageViewMut<'_, P> {
+    /// The pixel at `(x, y)`. Panics if it's outside the image, even if it's in the padding.
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut P {
+        self.get_mut(x, y).expect("pixel outside of the image")
+    }
+}
+
+/// Iterator of the rows of an [`ImageView`]
+#[derive(Debug, Clone)]
+pub struct Rows<'a, P> {
+    buf: &'a [P],
+    width: usize,
+    stride: usize,
+    remaining: usize,
+}
+
+impl<'a, P> Iterator for Rows<'a, P> {
+    type Item = &'a [P];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [P]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let row = &self.buf[..self.width];
+        self.buf = if self.remaining == 0 { &[] } else { &self.buf[self.stride..] };
+        Some(row)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, P> DoubleEndedIterator for Rows<'a, P> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [P]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let start = self.remaining * self.stride;
+        Some(&self.buf[start..start + self.width])
+    }
+}
+
+impl<P> ExactSizeIterator for Rows<'_, P> {}
+impl<P> core::iter::FusedIterator for Rows<'_, P> {}
+
+/// Iterator of the rows of an [`ImageViewMut`]
+#[derive(Debug)]
+pub struct RowsMut<'a, P> {
+    buf: &'a mut [P],
+    width: usize,
+    stride: usize,
+    remaining: usize,
+}
+
+impl<'a, P> Iterator for RowsMut<'a, P> {
+    type Item = &'a mut [P];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut [P]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let buf = core::mem::take(&mut self.buf);
+        if self.remaining == 0 {
+            return Some(&mut buf[..self.width]);
+        }
+        let (row, rest) = buf.split_at_mut(self.stride);
+        self.buf = rest;
+        Some(&mut row[..self.width])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, P> DoubleEndedIterator for RowsMut<'a, P> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut [P]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let start = self.remaining * self.stride;
+        let (rest, row) = core::mem::take(&mut self.buf).split_at_mut(start);
+        self.buf = rest;
+        Some(&mut row[..self.width])
+    }
+}
+
+impl<P> ExactSizeIterator for RowsMut<'_, P> {}
+impl<P> core::iter::FusedIterator for RowsMut<'_, P> {}
+
+/// An image that owns its pixels, without padding between rows.
+///
+/// ```
+/// use rgb::Rgba;
+/// use rgb::image::ImageBuf;
+///
+/// let mut image = ImageBuf::new(3, 2, Rgba::new(0u8, 0, 0, 255));
+/// image[(2, 1)] = Rgba::new(255, 0, 0, 255);
+/// let row = image.as_view().row(1).unwrap();
+/// assert_eq!(row[2], Rgba::new(255, 0, 0, 255));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageBuf<P> {
+    buf: Vec<P>,
+    width: usize,
+    height: usize,
+}
+
+#[cfg(feature = "std")]
+impl<P> ImageBuf<P> {
+    /// Creates an image with every pixel set to `value`
+    ///
+    /// # Panics
+    ///
+    /// If the size doesn't fit in `usize`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: P) -> Self where P: Clone {
+        let len = width.checked_mul(height).expect("image too large");
+        Self { buf: std::vec![value; len], width, height }
+    }
+
+    /// Creates an image by calling `f(x, y)` for every pixel, row by row
+    #[must_use]
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> P) -> Self {
+        let buf = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect();
+        Self { buf, width, height }
+    }
+
+    /// Takes the pixels of rows of `width` without padding. The `Vec` must have exactly `width * height` pixels.
+    pub fn from_vec(buf: Vec<P>, width: usize, height: usize) -> Result<Self, LayoutError> {
+        if width.checked_mul(height).ok_or(LayoutError::Overflow)? != buf.len() {
+            return Err(LayoutError::BufferTooSmall);
+        }
+        Ok(Self { buf, width, height })
+    }
+
+    /// The pixels, row by row
+    #[inline]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<P> {
+        self.buf
+    }
+
+    /// Width in pixels
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels, row by row
+    #[inline]
+    #[must_use]
+    pub fn buf(&self) -> &[P] {
+        &self.buf
+    }
+
+    /// The pixels, row by row
+    #[inline]
+    #[must_use]
+    pub fn buf_mut(&mut self) -> &mut [P] {
+        &mut self.buf
+    }
+
+    /// Borrows as an immutable view
+    #[inline]
+    #[must_use]
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView { buf: &self.buf, width: self.width, height: self.height, stride: self.width }
+    }
+
+    /// Borrows as a mutable view
+    #[inline]
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> ImageViewMut<'_, P> {
+        ImageViewMut { buf: &mut self.buf, width: self.width, height: self.height, stride: self.width }
+    }
+
+    /// The pixel at column `x` and row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&P> {
+        if x < self.width && y < self.height {
+            self.buf.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// The pixel at column `x` and row `y`, or `None` if it's outside the image
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut P> {
+        if x < self.width && y < self.height {
+            self.buf.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates the rows, top to bottom
+    #[inline]
+    pub fn rows(&self) -> Rows<'_, P> {
+        self.as_view().rows()
+    }
+
+    /// Iterates the rows mutably, top to bottom
+    #[inline]
+    pub fn rows_mut(&mut self) -> RowsMut<'_, P> {
+        self.as_view_mut().into_rows_mut()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P> Index<(usize, usize)> for ImageBuf<P> {
+    type Output = P;
+
+    /// The pixel at `(x, y)`. Panics if it's outside the image.
+    #[inline]
+    #[track_caller]
+    fn index(&self, (x, y): (usize, usize)) -> &P {
+        self.get(x, y).expect("pixel outside of the image")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P> IndexMut<(usize, usize)> for ImageBuf<P> {
+    /// The pixel at `(x, y)`. Panics if it's outside the image.
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut P {
+        self.get_mut(x, y).expect("pixel outside of the image")
+    }
+}
+
+#[test]
+fn views() {
+    use crate::Rgb;
+
+    let pixels: [Rgb<u8>; 20] = core::array::from_fn(|i| Rgb::new(i as u8, 0, 0));
+    assert_eq!(ImageView::with_stride(&pixels, 3, 4, 2), Err(LayoutError::StrideTooSmall));
+    assert_eq!(ImageView::with_stride(&pixels, 3, 5, 5), Err(LayoutError::BufferTooSmall));
+    assert_eq!(ImageView::with_stride(&pixels, 3, 1, usize::MAX).unwrap().height(), 1);
+    assert_eq!(ImageView::with_stride(&pixels, 3, 3, usize::MAX), Err(LayoutError::Overflow));
+    assert_eq!(ImageView::<Rgb<u8>>::new(&[], 100, 0).unwrap().rows().count(), 0);
+
+    // the last row doesn't need padding
+    let image = ImageView::with_stride(&pixels, 3, 4, 5).unwrap();
+    assert_eq!(image[(2, 3)].r, 17);
+    assert_eq!(image.get(3, 0), None);
+    assert_eq!(image.get(0, 4), None);
+    assert_eq!(image.row(1).unwrap()[0].r, 5);
+    let firsts: std::vec::Vec<u8> = image.rows().map(|row| row[0].r).collect();
+    assert_eq!(firsts, [0, 5, 10, 15]);
+    let lasts: std::vec::Vec<u8> = image.rows().rev().map(|row| row[2].r).collect();
+    assert_eq!(lasts, [17, 12, 7, 2]);
+    assert_eq!(image.pixels().count(), 12);
+
+    let sub = image.sub_view(1, 2, 2, 2).unwrap();
+    assert_eq!((sub.width(), sub.height(), sub.stride()), (2, 2, 5));
+    assert_eq!(sub[(0, 0)].r, 11);
+    assert_eq!(sub.pixels().map(|p| p.r).last(), Some(17));
+    assert!(image.sub_view(2, 0, 2, 1).is_none());
+    assert!(image.sub_view(0, 4, 3, 0).unwrap().rows().next().is_none());
+    assert!(image.sub_view(usize::MAX, 0, 2, 1).is_none());
+}
+
+#[test]
+fn views_mut() {
+    use crate::Rgb;
+
+    let mut pixels = [Rgb::new(0u8, 0, 0); 15];
+    assert!(ImageViewMut::with_stride(&mut pixels[..14], 3, 3, 6).is_err());
+    let mut image = ImageViewMut::with_stride(&mut pixels, 3, 3, 6).unwrap();
+    image.sub_view_mut(1, 1, 2, 2).unwrap().fill(Rgb::new(1, 1, 1));
+    image[(0, 2)] = Rgb::new(2, 2, 2);
+    for (y, row) in image.rows_mut().rev().enumerate() {
+        row[0].g = y as u8;
+    }
+    assert_eq!(image.rows().len(), 3);
+    let expected = [(0, 2, 0), (0, 1, 0), (1, 1, 1), (1, 1, 1), (2, 0, 2), (1, 1, 1), (1, 1, 1)];
+    for (i, (r, g, b)) in [0, 6, 7, 8, 12, 13, 14].into_iter().zip(expected) {
+        assert_eq!(pixels[i], Rgb::new(r, g, b));
+    }
+    assert_eq!(pixels[3], Rgb::new(0, 0, 0));
+
+    let mut image = ImageViewMut::with_stride(&mut pixels, 2, 2, 6).unwrap();
+    let src = [Rgb::new(9u8, 9, 9); 4];
+    image.copy_from(ImageView::new(&src, 2, 2).unwrap());
+    assert_eq!(image.pixels_mut().count(), 4);
+    assert_eq!(pixels[7], Rgb::new(9, 9, 9));
+    assert_eq!(pixels[8], Rgb::new(1, 1, 1));
+}
+
+#[test]
+#[cfg(feature = "bytemuck")]
+fn views_from_bytes() {
+    use crate::Bgra;
+
+    #[repr(align(4))]
+    struct Aligned([u8; 40]);
+    let mut bytes = Aligned([0; 40]);
+    bytes.0[36] = 7;
+    assert_eq!(ImageView::<Bgra<u8>>::from_bytes(&bytes.0, 2, 3, 14).unwrap_err(), LayoutError::Misaligned);
+    assert_eq!(ImageView::<Bgra<u8>>::from_bytes(&bytes.0, 2, 3, 4).unwrap_err(), LayoutError::StrideTooSmall);
+    assert_eq!(ImageView::<Bgra<u8>>::from_bytes(&bytes.0, 2, 4, 12).unwrap_err(), LayoutError::BufferTooSmall);
+    let image = ImageView::<Bgra<u8>>::from_bytes(&bytes.0, 2, 3, 16).unwrap();
+    assert_eq!(image.stride(), 4);
+    assert_eq!(image[(1, 2)].b, 7);
+    assert_eq!(ImageView::<u16>::from_bytes(&bytes.0[1..], 2, 2, 4).unwrap_err(), LayoutError::Misaligned);
+
+    let mut image = ImageViewMut::<Bgra<u8>>::from_bytes_mut(&mut bytes.0, 2, 2, 20).unwrap();
+    image[(0, 1)].a = 9;
+    assert_eq!(bytes.0[23], 9);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn image_buf() {
+    let image = ImageBuf::from_fn(3, 2, |x, y| (x + 10 * y) as u8);
+    assert_eq!(image.buf(), [0, 1, 2, 10, 11, 12]);
+    assert_eq!(image.get(3, 0), None);
+    assert_eq!(image.get(0, 2), None);
+    let sub = image.as_view().sub_view(1, 0, 2, 2).unwrap();
+    let copy = sub.to_image_buf();
+    assert_eq!(copy.into_vec(), [1, 2, 11, 12]);
+    assert_eq!(ImageBuf::from_vec(std::vec![0u8; 5], 3, 2), Err(LayoutError::BufferTooSmall));
+
+    let mut image = ImageBuf::new(2, 2, 0u8);
+    for row in image.rows_mut() {
+        row[1] = 1;
+    }
+    image[(0, 1)] = 5;
+    assert_eq!(image.get(0, 1 << 63), None);
+    assert_eq!(image.get_mut(1, usize::MAX), None);
+    assert_eq!(image.into_vec(), [0, 1, 5, 1]);
+}
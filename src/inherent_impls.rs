@@ -1,4 +1,6 @@
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
 
 macro_rules! inherent_impls {
     ($name:ident, $new_fn:ident, [$($field:tt $var:ident),*]) => {
@@ -24,6 +26,11 @@ inherent_impls!(Bgr, new_bgr, [b blue, g green, r red]);
 inherent_impls!(Grb, new_grb, [g green, r red, b blue]);
 inherent_impls!(Gray_v09, new, [v value]);
 inherent_impls!(Rgbw, new, [r red, g green, b blue, w white]);
+inherent_impls!(Ycbcr, new, [y luma, cb blue_chroma, cr red_chroma]);
+inherent_impls!(Rgbx, new, [r red, g green, b blue, x padding]);
+inherent_impls!(Bgrx, new_bgrx, [b blue, g green, r red, x padding]);
+inherent_impls!(Xrgb, new_xrgb, [x padding, r red, g green, b blue]);
+inherent_impls!(Xbgr, new_xbgr, [x padding, b blue, g green, r red]);
 
 use crate::formats::gray::Gray_v08;
 inherent_impls!(Gray_v08, new, [0 value]);
@@ -33,6 +40,11 @@ inherent_impls!(Argb, new_argb, [a alpha, r red, g green, b blue]);
 inherent_impls!(Bgra, new_bgra, [b blue, g green, r red, a alpha]);
 inherent_impls!(Abgr, new_abgr, [a alpha, b blue, g green, r red]);
 inherent_impls!(GrayA, new, [v value, a alpha]);
+inherent_impls!(Ycbcra, new, [y luma, cb blue_chroma, cr red_chroma, a alpha]);
+inherent_impls!(PremulRgba, new, [r red, g green, b blue, a alpha]);
+inherent_impls!(PremulArgb, new_argb, [a alpha, r red, g green, b blue]);
+inherent_impls!(PremulBgra, new_bgra, [b blue, g green, r red, a alpha]);
+inherent_impls!(PremulAbgr, new_abgr, [a alpha, b blue, g green, r red]);
 
 use crate::formats::gray_alpha::GrayAlpha_v08;
 inherent_impls!(GrayAlpha_v08, new, [0 value, 1 alpha]);
@@ -4,27 +4,49 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![no_std]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
 mod formats {
     pub mod abgr;
     pub mod argb;
+    pub mod argb1555;
     pub mod bgr;
+    pub mod bgr10a2;
+    pub mod bgr565;
     pub mod bgra;
+    pub mod bgrx;
     pub mod gray;
     pub mod gray_a;
     pub mod gray_alpha;
     pub mod gray_a44;
     pub mod grb;
+    pub mod indexed;
+    pub mod premul_abgr;
+    pub mod premul_argb;
+    pub mod premul_bgra;
+    pub mod premul_rgba;
+    pub mod r11g11b10_float;
     pub mod rgb;
+    pub mod rgb10a2;
+    pub mod rgb555;
+    pub mod rgb565;
+    pub mod rgb9e5;
     pub mod rgba;
+    pub mod rgba4444;
     pub mod rgbw;
+    pub mod rgbx;
+    pub mod xbgr;
+    pub mod xrgb;
+    pub mod ycbcr;
+    pub mod ycbcra;
 }
 mod core_traits;
 mod from;
+mod hex_macros;
 mod inherent_impls;
+mod math;
 mod tuples;
 mod pixel_traits {
     pub mod arraylike;
@@ -57,24 +79,70 @@ pub use legacy::internal::pixel::ComponentBytes;
 pub use legacy::internal::pixel::{ColorComponentMap, ComponentSlice};
 pub use legacy::*;
 pub use pixel_traits::pixel::Pixel as ComponentMap;
+#[doc(hidden)]
+pub use hex_macros::parse_hex_literal as __parse_hex_literal;
 
 /// If the `num-traits` feature is enabled, the implemented traits are in this module
 #[cfg(feature = "num-traits")]
 pub mod num_traits;
 
+pub mod alpha;
+pub mod blend;
+pub mod composite;
+pub mod css;
+pub mod depth;
+pub mod dither;
+#[cfg(feature = "half")]
+pub mod half;
+pub mod image;
+pub mod named;
+#[cfg(feature = "std")]
+pub mod palette;
+pub mod planar;
+pub mod premul;
+#[cfg(feature = "std")]
+pub mod quantize;
+pub mod resize;
+pub mod simd;
+pub mod srgb;
+pub mod swizzle;
+pub mod transform;
+pub mod ycbcr;
+pub mod yuv;
+
 pub use formats::abgr::Abgr;
 pub use formats::argb::Argb;
+pub use formats::argb1555::Argb1555;
 pub use formats::bgr::Bgr;
+pub use formats::bgr10a2::Bgr10A2;
+pub use formats::bgr565::Bgr565;
 pub use formats::bgra::Bgra;
+pub use formats::bgrx::Bgrx;
 pub use formats::gray::Gray_v08 as Gray;
 pub use formats::gray::Gray_v09;
 pub use formats::gray_a::GrayA;
 pub use formats::gray_alpha::GrayAlpha_v08 as GrayAlpha;
 pub use formats::gray_a44::GrayA44;
 pub use formats::grb::Grb;
+pub use formats::indexed::Indexed;
+pub use formats::premul_abgr::PremulAbgr;
+pub use formats::premul_argb::PremulArgb;
+pub use formats::premul_bgra::PremulBgra;
+pub use formats::premul_rgba::PremulRgba;
+pub use formats::r11g11b10_float::R11G11B10Float;
 pub use formats::rgb::Rgb;
+pub use formats::rgb10a2::Rgb10A2;
+pub use formats::rgb555::Rgb555;
+pub use formats::rgb565::Rgb565;
+pub use formats::rgb9e5::Rgb9E5;
 pub use formats::rgba::Rgba;
+pub use formats::rgba4444::Rgba4444;
 pub use formats::rgbw::Rgbw;
+pub use formats::rgbx::Rgbx;
+pub use formats::xbgr::Xbgr;
+pub use formats::xrgb::Xrgb;
+pub use formats::ycbcr::Ycbcr;
+pub use formats::ycbcra::Ycbcra;
 
 pub use pixel_traits::{
     arraylike::ArrayLike, gain_alpha::GainAlpha, has_alpha::HasAlpha, het_pixel::HetPixel,
@@ -92,17 +160,20 @@ pub use pixel_traits::{
 /// ```
 pub mod prelude {
     pub use crate::ArrayLike;
+    pub use crate::depth::ToDepth;
     pub use crate::GainAlpha;
     pub use crate::HasAlpha;
     pub use crate::HetPixel;
     pub use crate::Pixel;
 }
 
-/// `TryFrom` errors
+/// `TryFrom` and parsing errors
 pub mod error {
+    pub use crate::css::ParseColorError;
     pub use crate::pixel_traits::het_pixel::TryFromColorsAlphaError;
     pub use crate::pixel_traits::pixel::TryFromComponentsError;
     pub use crate::formats::gray_a44::OutOfRangeError;
+    pub use crate::planar::PlaneLengthError;
 }
 
 /// [`Abgr<u8>`]
@@ -157,3 +228,13 @@ pub type RGBW16 = formats::rgbw::Rgbw<u16>;
 
 /// [`Rgba<f32>`]
 pub type RGBA32F = formats::rgba::Rgba<f32>;
+
+/// [`Rgb<f16>`](Rgb), with [`f16`](::half::f16) from the `half` crate
+#[cfg(feature = "half")]
+pub type RGB16F = formats::rgb::Rgb<::half::f16>;
+/// [`Rgba<f16>`](Rgba), with [`f16`](::half::f16) from the `half` crate
+#[cfg(feature = "half")]
+pub type RGBA16F = formats::rgba::Rgba<::half::f16>;
+/// [`GrayA<f16>`](GrayA), with [`f16`](::half::f16) from the `half` crate
+#[cfg(feature = "half")]
+pub type GRAYA16F = formats::gray_a::GrayA<::half::f16>;
@@ -0,0 +1,126 @@
+//! Float functions that are missing from `core`.
+//!
+//! These are computed in `f64`, so they're accurate to the last bit of `f32` in practice,
+//! and they give the same results with and without `std`.
+
+const LN_2_HI: f64 = 0.693_147_180_369_123_8;
+const LN_2_LO: f64 = 1.908_214_929_270_587_7e-10;
+
+/// Natural logarithm of a positive finite number
+pub(crate) fn ln(x: f64) -> f64 {
+    debug_assert!(x > 0.);
+    let bits = x.to_bits();
+    let biased_exp = (bits >> 52) & 0x7ff;
+    if biased_exp == 0 {
+        // subnormal
+        return ln(x * (1u64 << 54) as f64) - 54. * core::f64::consts::LN_2;
+    }
+    let mut exp = biased_exp as i64 - 1023;
+    // mantissa in 1..2
+    let mut m = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if m > core::f64::consts::SQRT_2 {
+        m *= 0.5;
+        exp += 1;
+    }
+    // ln(m) = 2 atanh(s), and |s| < 0.172 converges quickly
+    let s = (m - 1.) / (m + 1.);
+    let s2 = s * s;
+    let mut sum = 0.;
+    let mut term = s;
+    let mut k = 1.;
+    while k < 30. {
+        sum += term / k;
+        term *= s2;
+        k += 2.;
+    }
+    2. * sum + exp as f64 * LN_2_HI + exp as f64 * LN_2_LO
+}
+
+/// `e^x`
+pub(crate) fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.7 {
+        return f64::INFINITY;
+    }
+    if x < -745.2 {
+        return 0.;
+    }
+    let k = round_f64(x * core::f64::consts::LOG2_E) as i64;
+    // |r| <= ln(2)/2
+    let r = (x - k as f64 * LN_2_HI) - k as f64 * LN_2_LO;
+    let mut sum = 1.;
+    let mut i = 17.;
+    while i >= 1. {
+        sum = 1. + sum * r / i;
+        i -= 1.;
+    }
+    scale_by_pow2(sum, k)
+}
+
+/// `x * 2^k`
+fn scale_by_pow2(mut x: f64, mut k: i64) -> f64 {
+    while k > 1023 {
+        x *= f64::from_bits(2046 << 52);
+        k -= 1023;
+    }
+    while k < -1022 {
+        x *= f64::from_bits(1 << 52);
+        k += 1022;
+    }
+    x * f64::from_bits(((k + 1023) as u64) << 52)
+}
+
+/// `x^y` for non-negative `x`. Negative `x` is treated as 0.
+pub(crate) fn powf(x: f32, y: f32) -> f32 {
+    if x > 0. {
+        exp(f64::from(y) * ln(f64::from(x))) as f32
+    } else if y == 0. {
+        1.
+    } else {
+        0.
+    }
+}
+
+/// Square root of a non-negative number. Negative `x` is treated as 0.
+pub(crate) fn sqrt(x: f64) -> f64 {
+    if x.is_nan() || x <= 0. {
+        return 0.;
+    }
+    if x == f64::INFINITY {
+        return x;
+    }
+    // halving the exponent gives a guess within 50%
+    let mut y = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..6 {
+        y = 0.5 * (y + x / y);
+    }
+    y
+}
+
+/// Rounds half away from zero
+pub(crate) fn round_f64(x: f64) -> f64 {
+    if x.abs() >= 4_503_599_627_370_496. {
+        return x;
+    }
+    (x + 0.5f64.copysign(x)) as i64 as f64
+}
+
+#[test]
+fn math_accuracy() {
+    for &x in &[1e-30_f64, 1e-5, 0.1, 0.5, 1., 2., core::f64::consts::E, 10., 12345.678, 1e300] {
+        let rel = (exp(ln(x)) - x).abs() / x;
+        assert!(rel < 1e-13, "{x} {rel}");
+    }
+    assert_eq!(ln(1.), 0.);
+    assert!((exp(1.) - core::f64::consts::E).abs() < 1e-15);
+    assert_eq!(powf(0.25, 0.5), 0.5);
+    assert_eq!(powf(2., 10.), 1024.);
+    assert_eq!(powf(0., 2.), 0.);
+    assert!((sqrt(2.) - core::f64::consts::SQRT_2).abs() < 1e-15);
+    assert!((sqrt(1e-300) / 1e-150 - 1.).abs() < 1e-15);
+    assert_eq!(sqrt(0.25), 0.5);
+    assert_eq!(round_f64(-2.5), -3.);
+    assert_eq!(round_f64(2.49), 2.);
+}
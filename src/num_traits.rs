@@ -1,4 +1,5 @@
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
 
 /// Re-exports from [the `num-traits` crate](https://lib.rs/crates/num-traits).
 pub use num_traits::ops::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
@@ -160,12 +161,18 @@ num_traits_without_alpha!(Bgr, [b, g, r]);
 num_traits_without_alpha!(Grb, [g, r, b]);
 num_traits_without_alpha!(Gray_v09, [v]);
 num_traits_without_alpha!(Rgbw, [r, g, b, w]);
+num_traits_without_alpha!(Ycbcr, [y, cb, cr]);
 
 num_traits_with_alpha!(Rgba, [r, g, b, a]);
 num_traits_with_alpha!(Argb, [a, r, g, b]);
 num_traits_with_alpha!(Bgra, [b, g, r, a]);
 num_traits_with_alpha!(Abgr, [a, b, g, r]);
 num_traits_with_alpha!(GrayA, [v, a]);
+num_traits_with_alpha!(Ycbcra, [y, cb, cr, a]);
+num_traits_with_alpha!(PremulRgba, [r, g, b, a]);
+num_traits_with_alpha!(PremulArgb, [a, r, g, b]);
+num_traits_with_alpha!(PremulBgra, [b, g, r, a]);
+num_traits_with_alpha!(PremulAbgr, [a, b, g, r]);
 
 #[test]
 #[cfg(not(feature = "checked_fns"))]
@@ -0,0 +1,319 @@
+//! Palettes for [`Indexed`] pixels.
+//!
+//! [`Palette`] looks up the colors of indexed pixels, in bulk or one by one. Indices packed into 1, 2, or 4 bits
+//! per pixel can be expanded directly with [`Palette::expand_packed()`].
+//!
+//! PNG stores the alpha of palette entries separately, in a `tRNS` chunk. It can be combined with
+//! [`Palette::with_trns()`] and split with [`Palette::split_trns()`].
+//!
+//! Mapping colors back to indices is done with [`PaletteLookup`], which finds the nearest color using a k-d tree.
+//!
+//! ```
+//! use rgb::{Bgra, Indexed, Rgb};
+//! use rgb::palette::Palette;
+//!
+//! let plte = Palette::new(vec![Rgb::new(0u8, 0, 0), Rgb::new(255, 0, 0)]);
+//! let palette = plte.with_trns(&[0]);
+//!
+//! let mut pixels = [Bgra::new_bgra(0, 0, 0, 0); 8];
+//! palette.expand_packed(&[0b0110_0000], 1, &mut pixels).unwrap();
+//! assert_eq!(pixels[1], Bgra::new_bgra(0, 0, 255, 255));
+//! assert_eq!(pixels[0].a, 0);
+//!
+//! let lookup = palette.lookup();
+//! assert_eq!(lookup.nearest(&palette[1]), 1);
+//! ```
+
+use crate::error::OutOfRangeError;
+use crate::{Indexed, Pixel, Rgb, Rgba};
+use core::ops::Deref;
+use std::vec::Vec;
+
+/// A list of colors that [`Indexed`] pixels refer to.
+///
+/// It dereferences to a slice of the colors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Palette<P> {
+    colors: Vec<P>,
+}
+
+impl<P: Copy> Palette<P> {
+    /// Creates a palette from its colors. The first color has index 0.
+    #[inline]
+    #[must_use]
+    pub fn new(colors: Vec<P>) -> Self {
+        Self { colors }
+    }
+
+    /// Returns the colors.
+    #[inline]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<P> {
+        self.colors
+    }
+
+    /// Returns the color of the pixel, or `None` if its index is out of range.
+    #[inline]
+    #[must_use]
+    pub fn get<I: Into<usize>>(&self, pixel: Indexed<I>) -> Option<P> {
+        self.colors.get(pixel.index()).copied()
+    }
+
+    /// Writes the colors of the `indices` to `dst`, converting them to `Q`, e.g. from `Rgba` to `Bgra`.
+    ///
+    /// Returns an error if an index is out of range of the palette. The pixels before it are still written.
+    ///
+    /// # Panics
+    ///
+    /// If the slices have different lengths.
+    pub fn expand<I, Q>(&self, indices: &[Indexed<I>], dst: &mut [Q]) -> Result<(), OutOfRangeError>
+    where
+        I: Copy + Into<usize>,
+        Q: From<P>,
+    {
+        assert_eq!(indices.len(), dst.len());
+        for (&i, dst) in indices.iter().zip(dst) {
+            *dst = self.get(i).ok_or(OutOfRangeError)?.into();
+        }
+        Ok(())
+    }
+
+    /// Same as [`Palette::expand()`], but for a row of indices packed with `bit_depth` bits per pixel.
+    ///
+    /// See [`Indexed::unpack()`] for the packing. The row has `dst.len()` pixels.
+    ///
+    /// # Panics
+    ///
+    /// If `bit_depth` is not 1, 2, 4, or 8, or `packed` is too short for `dst.len()` pixels.
+    pub fn expand_packed<Q>(&self, packed: &[u8], bit_depth: u8, dst: &mut [Q]) -> Result<(), OutOfRangeError>
+    where
+        Q: From<P>,
+    {
+        assert!(matches!(bit_depth, 1 | 2 | 4 | 8), "bit_depth must be 1, 2, 4, or 8");
+        assert!(packed.len() >= (dst.len() * usize::from(bit_depth)).div_ceil(8), "packed row is too short");
+        let mut indices = [Indexed::new(0u8); 64];
+        for (packed, dst) in packed.chunks(64 * usize::from(bit_depth) / 8).zip(dst.chunks_mut(64)) {
+            let indices = &mut indices[..dst.len()];
+            Indexed::unpack(packed, bit_depth, indices);
+            self.expand(indices, dst)?;
+        }
+        Ok(())
+    }
+}
+
+impl Palette<Rgb<u8>> {
+    /// Adds alpha from a PNG `tRNS` chunk, which has alpha of the first `trns.len()` colors.
+    ///
+    /// The other colors are opaque. Extra alpha values are ignored.
+    #[must_use]
+    pub fn with_trns(&self, trns: &[u8]) -> Palette<Rgba<u8>> {
+        let alpha = trns.iter().copied().chain(core::iter::repeat(255));
+        Palette::new(self.colors.iter().zip(alpha).map(|(c, a)| Rgba::new(c.r, c.g, c.b, a)).collect())
+    }
+}
+
+impl Palette<Rgba<u8>> {
+    /// Splits the palette into the colors and alpha for a PNG `PLTE` and `tRNS` chunk.
+    ///
+    /// Trailing opaque colors are omitted from the alpha, so it's empty when all colors are opaque.
+    #[must_use]
+    pub fn split_trns(&self) -> (Palette<Rgb<u8>>, Vec<u8>) {
+        let colors = self.colors.iter().map(|c| Rgb::new(c.r, c.g, c.b)).collect();
+        let opaque = self.colors.iter().rev().take_while(|c| c.a == 255).count();
+        let trns = self.colors[..self.colors.len() - opaque].iter().map(|c| c.a).collect();
+        (Palette::new(colors), trns)
+    }
+}
+
+impl<P> Palette<P>
+where
+    P: Pixel,
+    P::Component: Into<f32>,
+{
+    /// Prepares for finding the nearest colors in the palette.
+    ///
+    /// The distance is Euclidean, in the pixels' components, including alpha.
+    #[must_use]
+    pub fn lookup(&self) -> PaletteLookup<'_, P> {
+        PaletteLookup::new(self)
+    }
+}
+
+impl<P> Deref for Palette<P> {
+    type Target = [P];
+
+    #[inline(always)]
+    fn deref(&self) -> &[P] {
+        &self.colors
+    }
+}
+
+impl<P: Copy> From<Vec<P>> for Palette<P> {
+    #[inline]
+    fn from(colors: Vec<P>) -> Self {
+        Self::new(colors)
+    }
+}
+
+/// Finds the palette colors nearest to other colors. Made with [`Palette::lookup()`].
+#[derive(Debug, Clone)]
+pub struct PaletteLookup<'a, P> {
+    palette: &'a Palette<P>,
+    dims: usize,
+    /// components of the colors
+    points: Vec<f32>,
+    /// a balanced k-d tree of color indices: the middle of each range splits it
+    tree: Vec<usize>,
+}
+
+impl<'a, P> PaletteLookup<'a, P>
+where
+    P: Pixel,
+    P::Component: Into<f32>,
+{
+    fn new(palette: &'a Palette<P>) -> Self {
+        let dims = usize::from(P::NUM_COMPONENTS);
+        let points = palette.iter().flat_map(|c| c.as_array().as_ref().iter().map(|&c| c.into())).collect();
+        let mut lookup = Self { palette, dims, points, tree: (0..palette.len()).collect() };
+        let mut tree = core::mem::take(&mut lookup.tree);
+        lookup.build(&mut tree, 0);
+        lookup.tree = tree;
+        lookup
+    }
+
+    fn build(&self, tree: &mut [usize], depth: usize) {
+        if tree.len() <= 1 {
+            return;
+        }
+        let axis = depth % self.dims;
+        let mid = tree.len() / 2;
+        tree.select_nth_unstable_by(mid, |&a, &b| self.point(a)[axis].total_cmp(&self.point(b)[axis]));
+        let (left, right) = tree.split_at_mut(mid);
+        self.build(left, depth + 1);
+        self.build(&mut right[1..], depth + 1);
+    }
+
+    #[inline]
+    fn point(&self, i: usize) -> &[f32] {
+        &self.points[i * self.dims..(i + 1) * self.dims]
+    }
+
+    /// Returns the index of the palette color nearest to `color`.
+    ///
+    /// If several colors are equally near, the one with the lowest index is chosen.
+    ///
+    /// # Panics
+    ///
+    /// If the palette is empty.
+    #[must_use]
+    pub fn nearest(&self, color: &P) -> usize {
+        assert!(!self.tree.is_empty(), "the palette is empty");
+        let target: Vec<f32> = color.as_array().as_ref().iter().map(|&c| c.into()).collect();
+        let mut best = (f32::INFINITY, usize::MAX);
+        self.search(&self.tree, 0, &target, &mut best);
+        best.1
+    }
+
+    fn search(&self, tree: &[usize], depth: usize, target: &[f32], best: &mut (f32, usize)) {
+        if tree.is_empty() {
+            return;
+        }
+        let mid = tree.len() / 2;
+        let i = tree[mid];
+        let point = self.point(i);
+        let dist: f32 = point.iter().zip(target).map(|(a, b)| (a - b) * (a - b)).sum();
+        if dist < best.0 || (dist == best.0 && i < best.1) {
+            *best = (dist, i);
+        }
+        let axis = depth % self.dims;
+        let diff = target[axis] - point[axis];
+        let (near, far) = if diff < 0. { (&tree[..mid], &tree[mid + 1..]) } else { (&tree[mid + 1..], &tree[..mid]) };
+        self.search(near, depth + 1, target, best);
+        // equal distance is searched too, for the lowest index
+        if diff * diff <= best.0 {
+            self.search(far, depth + 1, target, best);
+        }
+    }
+
+    /// Maps all pixels to the indices of the nearest palette colors.
+    ///
+    /// Returns an error if the palette has more colors than `I` can index.
+    ///
+    /// # Panics
+    ///
+    /// If the slices have different lengths, or the palette is empty while there are pixels.
+    pub fn remap<I>(&self, pixels: &[P], dst: &mut [Indexed<I>]) -> Result<(), OutOfRangeError>
+    where
+        I: TryFrom<usize>,
+    {
+        assert_eq!(pixels.len(), dst.len());
+        if I::try_from(self.palette.len().saturating_sub(1)).is_err() {
+            return Err(OutOfRangeError);
+        }
+        for (px, dst) in pixels.iter().zip(dst) {
+            *dst = Indexed(I::try_from(self.nearest(px)).map_err(|_| OutOfRangeError)?);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn expand() {
+    use crate::Bgra;
+
+    let palette = Palette::new(vec![Rgba::new(1u8, 2, 3, 4), Rgba::new(5, 6, 7, 8)]);
+    let mut dst = [Bgra::new_bgra(0, 0, 0, 0); 3];
+    palette.expand(&[Indexed::new(1u16), Indexed::new(0), Indexed::new(1)], &mut dst).unwrap();
+    assert_eq!(dst[0], Bgra::new_bgra(7, 6, 5, 8));
+    assert_eq!(dst[1], Bgra::new_bgra(3, 2, 1, 4));
+    assert_eq!(palette.expand(&[Indexed::new(2u8)], &mut dst[..1]), Err(OutOfRangeError));
+    assert_eq!(palette.get(Indexed::new(1u8)), Some(Rgba::new(5, 6, 7, 8)));
+
+    let mut rgb = [Rgb::new(0, 0, 0); 100];
+    let packed: Vec<u8> = (0..25).map(|_| 0b0100_0101).collect();
+    palette.expand_packed(&packed, 2, &mut rgb).unwrap();
+    assert!(rgb.chunks(4).all(|c| c == [Rgb::new(5, 6, 7), Rgb::new(1, 2, 3), Rgb::new(5, 6, 7), Rgb::new(5, 6, 7)]));
+    assert_eq!(palette.expand_packed(&[0b1100_0000], 2, &mut rgb[..1]), Err(OutOfRangeError));
+}
+
+#[test]
+#[should_panic(expected = "packed row is too short")]
+fn expand_packed_short() {
+    let palette = Palette::new(vec![Rgb::new(0u8, 0, 0), Rgb::new(255, 0, 0)]);
+    let mut dst = [Rgb::new(0u8, 0, 0); 72];
+    let _ = palette.expand_packed(&[0; 8], 1, &mut dst);
+}
+
+#[test]
+fn trns() {
+    let plte = Palette::new(vec![Rgb::new(1u8, 2, 3), Rgb::new(4, 5, 6), Rgb::new(7, 8, 9)]);
+    let palette = plte.with_trns(&[0, 128]);
+    assert_eq!(&*palette, &[Rgba::new(1, 2, 3, 0), Rgba::new(4, 5, 6, 128), Rgba::new(7, 8, 9, 255)]);
+    assert_eq!(palette.split_trns(), (plte.clone(), vec![0, 128]));
+    assert_eq!(plte.with_trns(&[]).split_trns(), (plte, vec![]));
+}
+
+#[test]
+fn lookup() {
+    let colors: Vec<Rgba<u8>> = (0..200u32).map(|i| Rgba::new((i * 37 % 256) as u8, (i * 91 % 256) as u8, (i * 13 % 256) as u8, (i * 59 % 256) as u8)).collect();
+    let palette = Palette::new(colors);
+    let lookup = palette.lookup();
+    for (i, c) in palette.iter().enumerate() {
+        assert_eq!(lookup.nearest(c), i);
+    }
+    let dist = |a: &Rgba<u8>, b: &Rgba<u8>| a.to_array().into_iter().zip(b.to_array()).map(|(a, b)| (i32::from(a) - i32::from(b)).pow(2)).sum::<i32>();
+    let pixels: Vec<Rgba<u8>> = (0..1000u32).map(|i| Rgba::new((i * 7 % 256) as u8, (i * 3 % 256) as u8, (i % 256) as u8, (i * 11 % 256) as u8)).collect();
+    let mut indices = vec![Indexed::new(0u8); pixels.len()];
+    lookup.remap(&pixels, &mut indices).unwrap();
+    for (px, i) in pixels.iter().zip(&indices) {
+        let expected = (0..palette.len()).min_by_key(|&j| dist(px, &palette[j])).unwrap();
+        assert_eq!(i.index(), expected);
+    }
+
+    let gray = Palette::new((0..300u16).map(crate::Gray_v09::new).collect());
+    let mut small = [Indexed::new(0u8)];
+    assert_eq!(gray.lookup().remap(&[crate::Gray_v09::new(7)], &mut small), Err(OutOfRangeError));
+    let mut wide = [Indexed::new(0u16)];
+    gray.lookup().remap(&[crate::Gray_v09::new(1000)], &mut wide).unwrap();
+    assert_eq!(wide[0].index(), 299);
+}
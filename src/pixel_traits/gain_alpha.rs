@@ -1,13 +1,16 @@
 use crate::HasAlpha;
 use crate::HetPixel;
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Rgb, Rgba};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Rgb, Rgba, Ycbcr, Ycbcra};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
 use crate::formats::gray::{Gray_v08, Gray_v09};
 use crate::formats::gray_alpha::GrayAlpha_v08;
 
 /// A pixel which can gain an alpha component.
 ///
 /// It's implemented for every pixel type in the crate, including those which
-/// already have an alpha component.
+/// already have an alpha component. The exception are the premultiplied pixel types
+/// like [`PremulRgba`](crate::PremulRgba), because their color components would need
+/// to be rescaled whenever the alpha changes.
 pub trait GainAlpha: HetPixel {
     /// The pixel type after gaining an alpha component.
     ///
@@ -102,9 +105,15 @@ gain_already_alpha!(Argb, a);
 gain_already_alpha!(Bgra, a);
 gain_already_alpha!(Abgr, a);
 gain_already_alpha!(GrayA, a);
+gain_already_alpha!(Ycbcra, a);
 gain_already_alpha!(GrayAlpha_v08, 1);
 
 lower_upper!(Rgb, Rgba, {r, g, b}, a);
 lower_upper!(Bgr, Bgra, {r, g, b}, a);
 lower_upper!(Gray_v08, GrayAlpha_v08, { 0 }, 1);
 lower_upper!(Gray_v09, GrayA, { v }, a);
+lower_upper!(Ycbcr, Ycbcra, {y, cb, cr}, a);
+lower_upper!(Rgbx, Rgba, {r, g, b}, a);
+lower_upper!(Bgrx, Bgra, {r, g, b}, a);
+lower_upper!(Xrgb, Argb, {r, g, b}, a);
+lower_upper!(Xbgr, Abgr, {r, g, b}, a);
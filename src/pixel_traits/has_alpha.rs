@@ -1,5 +1,6 @@
 use crate::HetPixel;
-use crate::{Abgr, Argb, Bgra, GrayA, Rgba};
+use crate::{Abgr, Argb, Bgra, GrayA, Rgba, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
 
 /// A pixel which has an alpha component.
 ///
@@ -76,6 +77,11 @@ has_alpha!(Argb, a);
 has_alpha!(Bgra, a);
 has_alpha!(Abgr, a);
 has_alpha!(GrayA, a);
+has_alpha!(Ycbcra, a);
+has_alpha!(PremulRgba, a);
+has_alpha!(PremulArgb, a);
+has_alpha!(PremulBgra, a);
+has_alpha!(PremulAbgr, a);
 
 use crate::formats::gray_alpha::GrayAlpha_v08;
 has_alpha!(GrayAlpha_v08, 1);
@@ -1,4 +1,6 @@
-use crate::{Abgr, Argb, ArrayLike, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, ArrayLike, Bgr, Bgra, GrayA, Gray_v09, Grb, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
 use core::fmt::Display;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -338,6 +340,70 @@ macro_rules! without_alpha {
         }
     }
 }
+/// The padding isn't a component. It's set to the `alpha` argument by `try_from_colors_alpha`, and mapped like
+/// a color by `map_colors` (so its closure sees the padding as a fourth color), because the new component type
+/// may have no other value to put there.
+macro_rules! padded {
+    ($name:ident, [$($color_bit:tt),*], $padding_bit:tt) => {
+        impl<T> HetPixel for $name<T> where T: Copy + 'static {
+            type ColorComponent = T;
+            type AlphaComponent = T;
+
+            const NUM_COMPONENTS: u8 = 3;
+            const NUM_COLOR_COMPONENTS: u8 = 3;
+
+            type SelfType<U: Copy + 'static, V: Copy + 'static> = $name<U>;
+            type ColorArray<U> = [U; 3];
+
+            #[inline]
+            fn to_color_array(&self) -> Self::ColorArray<Self::ColorComponent> where Self::ColorArray<Self::ColorComponent>: Copy {
+                [$(self.$color_bit),*]
+            }
+
+            #[inline]
+            fn each_color_mut(&mut self) -> Self::ColorArray<&mut Self::ColorComponent> {
+                [$(&mut self.$color_bit),*]
+            }
+
+            #[inline]
+            fn alpha_opt(&self) -> Option<Self::AlphaComponent> {
+                None
+            }
+
+            #[inline]
+            fn alpha_opt_mut(&mut self) -> Option<&mut Self::AlphaComponent> {
+                None
+            }
+
+            #[inline]
+            fn try_from_colors_alpha(colors: impl IntoIterator<Item = Self::ColorComponent>, padding: Self::AlphaComponent) -> Result<Self, TryFromColorsAlphaError> {
+                let mut iter = colors.into_iter();
+                Ok(Self {$($color_bit: iter.next().ok_or(TryFromColorsAlphaError)?),*, $padding_bit: padding})
+            }
+
+            #[inline]
+            fn map_colors<U>(&self, mut f: impl FnMut(Self::ColorComponent) -> U) -> Self::SelfType<U, Self::AlphaComponent> where U: Copy + 'static {
+                $name {$($color_bit: f(self.$color_bit),)* $padding_bit: f(self.$padding_bit)}
+            }
+
+            #[inline]
+            fn map_colors_same(&self, mut f: impl FnMut(Self::ColorComponent) -> Self::ColorComponent) -> Self {
+                Self {$($color_bit: f(self.$color_bit),)* $padding_bit: self.$padding_bit}
+            }
+
+            #[inline]
+            fn map_alpha<U>(&self, _: impl FnMut(Self::AlphaComponent) -> U) -> Self::SelfType<Self::ColorComponent, U> where U: Copy + 'static {
+                *self
+            }
+
+            #[inline]
+            fn map_alpha_same(&self, _: impl FnMut(Self::AlphaComponent) -> Self::AlphaComponent) -> Self {
+                *self
+            }
+        }
+    }
+}
+
 macro_rules! with_alpha {
     ($name:tt, $length:literal, [$($color_bit:tt),*], $alpha_bit:tt) => {
         impl<T, A> HetPixel for $name<T, A> where T: Copy + 'static, A: Copy + 'static {
@@ -405,6 +471,11 @@ with_alpha!(Abgr, 4, [b, g, r], a);
 with_alpha!(Argb, 4, [r, g, b], a);
 with_alpha!(Bgra, 4, [b, g, r], a);
 with_alpha!(GrayA, 2, [v], a);
+with_alpha!(Ycbcra, 4, [y, cb, cr], a);
+with_alpha!(PremulRgba, 4, [r, g, b], a);
+with_alpha!(PremulAbgr, 4, [b, g, r], a);
+with_alpha!(PremulArgb, 4, [r, g, b], a);
+with_alpha!(PremulBgra, 4, [b, g, r], a);
 
 use crate::formats::gray_alpha::GrayAlpha_v08;
 with_alpha!(GrayAlpha_v08, 2, [0], 1);
@@ -413,7 +484,29 @@ without_alpha!(Bgr, 3, [b, g, r]);
 without_alpha!(Rgb, 3, [r, g, b]);
 without_alpha!(Grb, 3, [g, r, b]);
 without_alpha!(Gray_v09, 1, [v]);
+without_alpha!(Ycbcr, 3, [y, cb, cr]);
 without_alpha!(Rgbw, 4, [r, g, b, w]);
 
 use crate::formats::gray::Gray_v08;
 without_alpha!(Gray_v08, 1, [0]);
+
+padded!(Rgbx, [r, g, b], x);
+padded!(Bgrx, [b, g, r], x);
+padded!(Xrgb, [r, g, b], x);
+padded!(Xbgr, [b, g, r], x);
+
+#[test]
+fn padded_padding() {
+    use crate::{Rgbx, Xbgr};
+
+    let mut calls = 0;
+    let px = Rgbx { r: 1u8, g: 2, b: 3, x: 4 }.map_colors(|c| {
+        calls += 1;
+        u16::from(c) * 10
+    });
+    assert_eq!(calls, 4);
+    assert_eq!([px.r, px.g, px.b, px.x], [10, 20, 30, 40]);
+
+    let px = Xbgr::try_from_colors_alpha([1u8, 2, 3], 9).unwrap();
+    assert_eq!([px.x, px.b, px.g, px.r], [9, 1, 2, 3]);
+}
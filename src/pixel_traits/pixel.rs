@@ -1,6 +1,7 @@
 use core::fmt::Display;
 use crate::HetPixel;
-use crate::{Abgr, Argb, ArrayLike, Bgr, Bgra, Gray_v09, GrayA, Grb,Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, ArrayLike, Bgr, Bgra, Gray_v09, GrayA, Grb,Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 /// Error returned from the [`Pixel::try_from_components()`] function.
@@ -280,11 +281,17 @@ with_alpha!(Abgr, 4, [a, b, g, r]);
 with_alpha!(Argb, 4, [a, r, g, b]);
 with_alpha!(Bgra, 4, [b, g, r, a]);
 with_alpha!(GrayA, 2, [v, a]);
+with_alpha!(Ycbcra, 4, [y, cb, cr, a]);
+with_alpha!(PremulRgba, 4, [r, g, b, a]);
+with_alpha!(PremulAbgr, 4, [a, b, g, r]);
+with_alpha!(PremulArgb, 4, [a, r, g, b]);
+with_alpha!(PremulBgra, 4, [b, g, r, a]);
 
 without_alpha!(Bgr, 3, [b, g, r]);
 without_alpha!(Rgb, 3, [r, g, b]);
 without_alpha!(Grb, 3, [g, r, b]);
 without_alpha!(Gray_v09, 1, [v]);
+without_alpha!(Ycbcr, 3, [y, cb, cr]);
 without_alpha!(Rgbw, 4, [r, g, b, w]);
 
 use crate::formats::gray::Gray_v08;
@@ -0,0 +1,149 @@
+//! Conversions between interleaved pixels, like `&[Rgba<T>]`, and separate planes of components, like `[&[T]; 4]`.
+//!
+//! The planes are in the order of the pixel type's fields, so [`Bgra`](crate::Bgra) splits into
+//! blue, green, red, and alpha planes, and [`Argb`](crate::Argb) into alpha, red, green, and blue.
+//!
+//! ```
+//! use rgb::Bgra;
+//! use rgb::planar::{merge_planes, split_planes};
+//!
+//! let pixels = [Bgra::new_bgra(1u8, 2, 3, 4), Bgra::new_bgra(5, 6, 7, 8)];
+//! let [mut b, mut g, mut r, mut a] = [[0u8; 2]; 4];
+//! split_planes(&pixels, &mut [&mut b, &mut g, &mut r, &mut a]).unwrap();
+//! assert_eq!((b, g, r, a), ([1, 5], [2, 6], [3, 7], [4, 8]));
+//!
+//! let mut merged = [Bgra::default(); 2];
+//! merge_planes(&[&b, &g, &r, &a], &mut merged).unwrap();
+//! assert_eq!(merged, pixels);
+//! ```
+//!
+//! The `_color_` variants skip the alpha plane, which is useful for images without alpha:
+//!
+//! ```
+//! use rgb::Rgba;
+//! use rgb::planar::merge_color_planes;
+//!
+//! let (r, g, b) = ([255u8, 0], [0u8, 255], [0u8, 0]);
+//! let mut pixels = [Rgba::default(); 2];
+//! merge_color_planes(&[&r, &g, &b], 255, &mut pixels).unwrap();
+//! assert_eq!(pixels[1], Rgba::new(0, 255, 0, 255));
+//! ```
+
+use crate::{HetPixel, Pixel};
+use core::fmt;
+
+/// Error returned when the number of planes is wrong for the pixel type, or a plane's length
+/// is different from the number of pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PlaneLengthError;
+
+impl fmt::Display for PlaneLengthError {
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the number or lengths of the planes don't match the pixels")
+    }
+}
+
+#[inline]
+fn check_lengths(num_planes: usize, expected_planes: u8, lengths: impl Iterator<Item = usize>, num_pixels: usize) -> Result<(), PlaneLengthError> {
+    if num_planes != usize::from(expected_planes) {
+        return Err(PlaneLengthError);
+    }
+    for len in lengths {
+        if len != num_pixels {
+            return Err(PlaneLengthError);
+        }
+    }
+    Ok(())
+}
+
+/// Copies every component of the pixels to its own plane.
+///
+/// There must be `P::NUM_COMPONENTS` planes, each as long as `src`.
+pub fn split_planes<P: Pixel>(src: &[P], planes: &mut [&mut [P::Component]]) -> Result<(), PlaneLengthError> {
+    check_lengths(planes.len(), P::NUM_COMPONENTS, planes.iter().map(|p| p.len()), src.len())?;
+    for (c, plane) in planes.iter_mut().enumerate() {
+        for (dst, px) in plane.iter_mut().zip(src) {
+            *dst = px.as_array()[c];
+        }
+    }
+    Ok(())
+}
+
+/// Copies the components from the planes to the pixels.
+///
+/// There must be `P::NUM_COMPONENTS` planes, each as long as `dst`.
+pub fn merge_planes<P: Pixel>(planes: &[&[P::Component]], dst: &mut [P]) -> Result<(), PlaneLengthError> {
+    check_lengths(planes.len(), P::NUM_COMPONENTS, planes.iter().map(|p| p.len()), dst.len())?;
+    for (c, plane) in planes.iter().enumerate() {
+        for (px, &v) in dst.iter_mut().zip(*plane) {
+            px.as_array_mut()[c] = v;
+        }
+    }
+    Ok(())
+}
+
+/// Copies every color component of the pixels to its own plane, skipping alpha.
+///
+/// There must be `P::NUM_COLOR_COMPONENTS` planes, each as long as `src`.
+pub fn split_color_planes<P: HetPixel>(src: &[P], planes: &mut [&mut [P::ColorComponent]]) -> Result<(), PlaneLengthError> {
+    check_lengths(planes.len(), P::NUM_COLOR_COMPONENTS, planes.iter().map(|p| p.len()), src.len())?;
+    for (c, plane) in planes.iter_mut().enumerate() {
+        for (dst, &px) in plane.iter_mut().zip(src) {
+            let mut px = px;
+            *dst = *px.each_color_mut()[c];
+        }
+    }
+    Ok(())
+}
+
+/// Copies the color components from the planes to the pixels, and sets alpha (if any) to `alpha`.
+///
+/// There must be `P::NUM_COLOR_COMPONENTS` planes, each as long as `dst`.
+pub fn merge_color_planes<P: HetPixel>(planes: &[&[P::ColorComponent]], alpha: P::AlphaComponent, dst: &mut [P]) -> Result<(), PlaneLengthError> {
+    check_lengths(planes.len(), P::NUM_COLOR_COMPONENTS, planes.iter().map(|p| p.len()), dst.len())?;
+    for (c, plane) in planes.iter().enumerate() {
+        for (px, &v) in dst.iter_mut().zip(*plane) {
+            *px.each_color_mut()[c] = v;
+        }
+    }
+    for px in dst {
+        if let Some(a) = px.alpha_opt_mut() {
+            *a = alpha;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn planes() {
+    use crate::{Argb, GrayA, Rgb, Rgba};
+
+    let pixels = [Argb::new_argb(1u16, 2, 3, 4), Argb::new_argb(5, 6, 7, 8), Argb::new_argb(9, 10, 11, 12)];
+    let mut planes = [[0u16; 3]; 4];
+    let [a, r, g, b] = &mut planes;
+    split_planes(&pixels, &mut [a, r, g, b]).unwrap();
+    assert_eq!(planes, [[1, 5, 9], [2, 6, 10], [3, 7, 11], [4, 8, 12]]);
+
+    let [a, r, g, b] = &mut planes;
+    assert_eq!(split_planes(&pixels, &mut [a, r, g]), Err(PlaneLengthError));
+    assert_eq!(split_planes(&pixels, &mut [a, r, g, &mut b[..2]]), Err(PlaneLengthError));
+    assert_eq!(merge_planes(&[&[1u8][..], &[2, 3]], &mut [GrayA::default()]), Err(PlaneLengthError));
+
+    let mut colors = [[0u16; 3]; 3];
+    let [r, g, b] = &mut colors;
+    split_color_planes(&pixels, &mut [r, g, b]).unwrap();
+    assert_eq!(colors, [[2, 6, 10], [3, 7, 11], [4, 8, 12]]);
+    let mut merged = [Argb::default(); 3];
+    merge_color_planes(&[&colors[0], &colors[1], &colors[2]], 99, &mut merged).unwrap();
+    assert_eq!(merged[2], Argb::new_argb(99, 10, 11, 12));
+
+    let mut rgb = [Rgb::default(); 3];
+    merge_color_planes(&[&colors[0], &colors[1], &colors[2]], 99, &mut rgb).unwrap();
+    assert_eq!(rgb[0], Rgb::new(2, 3, 4));
+
+    // different alpha type
+    let mut het = [Rgba { r: 0u8, g: 0, b: 0, a: false }];
+    merge_color_planes(&[&[1], &[2], &[3]], true, &mut het).unwrap();
+    assert_eq!(het[0], Rgba { r: 1, g: 2, b: 3, a: true });
+}
@@ -0,0 +1,151 @@
+//! Conversions between straight and premultiplied alpha.
+//!
+//! The premultiplied pixel types, such as [`PremulRgba`], implement `From` for their
+//! straight-alpha counterparts, and vice versa:
+//!
+//! ```
+//! use rgb::{Rgba, PremulRgba};
+//!
+//! let straight = Rgba::<u8>::new(255, 128, 0, 128);
+//! let premultiplied = PremulRgba::from(straight);
+//! assert_eq!(premultiplied, PremulRgba::new(128, 64, 0, 128));
+//! assert_eq!(Rgba::from(premultiplied), straight);
+//! ```
+//!
+//! Integer components are rounded to the nearest value. Unpremultiplying integers is
+//! lossy when alpha is low, because the premultiplied values have fewer distinct levels.
+
+use crate::{Abgr, Argb, Bgra, Rgba};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+
+/// A component type that can be multiplied and divided by alpha.
+///
+/// Integers are treated as fractions of their maximum value, e.g. `255u8` is `1.0`.
+pub trait PremultiplyComponent: Copy + 'static {
+    /// Multiplies the color component by alpha, rounding to the nearest value.
+    fn premultiply(self, alpha: Self) -> Self;
+
+    /// Divides the premultiplied color component by alpha, rounding to the nearest value.
+    ///
+    /// When alpha is zero the color is unknown, and `0` is returned.
+    /// Integer results are clamped to the valid range.
+    fn unpremultiply(self, alpha: Self) -> Self;
+}
+
+macro_rules! premultiply_int {
+    ($int:ty, $wide:ty) => {
+        impl PremultiplyComponent for $int {
+            #[inline]
+            fn premultiply(self, alpha: Self) -> Self {
+                const MAX: $wide = <$int>::MAX as $wide;
+                ((<$wide>::from(self) * <$wide>::from(alpha) + MAX / 2) / MAX) as $int
+            }
+
+            #[inline]
+            fn unpremultiply(self, alpha: Self) -> Self {
+                const MAX: $wide = <$int>::MAX as $wide;
+                if alpha == 0 {
+                    return 0;
+                }
+                let alpha = <$wide>::from(alpha);
+                ((<$wide>::from(self) * MAX + alpha / 2) / alpha).min(MAX) as $int
+            }
+        }
+    };
+}
+
+macro_rules! premultiply_float {
+    ($float:ty) => {
+        impl PremultiplyComponent for $float {
+            #[inline]
+            fn premultiply(self, alpha: Self) -> Self {
+                self * alpha
+            }
+
+            #[inline]
+            fn unpremultiply(self, alpha: Self) -> Self {
+                if alpha == 0. {
+                    return 0.;
+                }
+                self / alpha
+            }
+        }
+    };
+}
+
+premultiply_int!(u8, u32);
+premultiply_int!(u16, u32);
+premultiply_float!(f32);
+premultiply_float!(f64);
+
+macro_rules! premul_from {
+    ($straight:ident, $premul:ident) => {
+        impl<T: PremultiplyComponent> From<$straight<T>> for $premul<T> {
+            #[inline]
+            fn from(px: $straight<T>) -> Self {
+                Self {
+                    r: px.r.premultiply(px.a),
+                    g: px.g.premultiply(px.a),
+                    b: px.b.premultiply(px.a),
+                    a: px.a,
+                }
+            }
+        }
+
+        impl<T: PremultiplyComponent> From<$premul<T>> for $straight<T> {
+            #[inline]
+            fn from(px: $premul<T>) -> Self {
+                Self {
+                    r: px.r.unpremultiply(px.a),
+                    g: px.g.unpremultiply(px.a),
+                    b: px.b.unpremultiply(px.a),
+                    a: px.a,
+                }
+            }
+        }
+    };
+}
+
+premul_from!(Rgba, PremulRgba);
+premul_from!(Argb, PremulArgb);
+premul_from!(Bgra, PremulBgra);
+premul_from!(Abgr, PremulAbgr);
+
+#[test]
+fn premultiply_rounding() {
+    for a in 0..=255u8 {
+        for c in 0..=255u8 {
+            let expected = (f64::from(c) * f64::from(a) / 255. + 0.5) as u8;
+            assert_eq!(expected, c.premultiply(a));
+            let p = c.premultiply(a);
+            if a > 0 {
+                let expected = ((f64::from(p) * 255. / f64::from(a) + 0.5) as u8).min(255);
+                assert_eq!(expected, p.unpremultiply(a));
+                // the premultiplied value survives the roundtrip
+                assert_eq!(p, p.unpremultiply(a).premultiply(a));
+            }
+        }
+    }
+    assert_eq!(65535u16.premultiply(65535), 65535);
+    assert_eq!(65535u16.premultiply(32768), 32768);
+    assert_eq!(1000u16.unpremultiply(0), 0);
+    assert_eq!(1000u16.unpremultiply(500), 65535);
+}
+
+#[test]
+fn premultiply_float_zero_alpha() {
+    let px = PremulBgra::<f32>::new_bgra(0., 0., 0., 0.);
+    assert_eq!(Bgra::from(px), Bgra::new_bgra(0., 0., 0., 0.));
+    let px = Argb::<f32>::new_argb(0.5, 1., 0.5, 0.);
+    assert_eq!(PremulArgb::from(px), PremulArgb::new_argb(0.5, 0.5, 0.25, 0.));
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn premultiply_cast() {
+    let premul = [PremulRgba::<u8>::new(1, 2, 3, 4)];
+    let bytes: &[u8] = bytemuck::cast_slice(&premul);
+    assert_eq!(bytes, &[1, 2, 3, 4]);
+    let straight: &[Rgba<u8>] = bytemuck::cast_slice(&premul);
+    assert_eq!(straight[0].a, 4);
+}
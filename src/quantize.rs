@@ -0,0 +1,334 @@
+//! Reducing an image to a palette of at most 256 colors, e.g. for GIF or 8-bit PNG.
+//!
+//! The palette is built with the median cut algorithm, and refined with a few iterations of k-means.
+//! It's alpha-aware: colors are compared premultiplied by alpha, so colors of nearly-transparent
+//! pixels don't waste palette entries, and all fully transparent pixels share one palette entry.
+//!
+//! The results are deterministic.
+//!
+//! ```
+//! use rgb::RGBA8;
+//!
+//! let pixels = vec![RGBA8::new(255, 0, 0, 255), RGBA8::new(250, 0, 0, 255), RGBA8::new(0, 0, 255, 128)];
+//! let q = rgb::quantize::quantize(&pixels, 2);
+//! assert_eq!(q.palette.len(), 2);
+//! assert_eq!(q.indices[0], q.indices[1]);
+//! assert_eq!(q.palette[usize::from(q.indices[2])], RGBA8::new(0, 0, 255, 128));
+//! ```
+
+use crate::dither::{diffuse, Diffusion};
+use crate::Rgba;
+use std::vec::Vec;
+
+/// A palette, and the image's pixels as indices into the palette.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Quantized {
+    /// At most 256 colors
+    pub palette: Vec<Rgba<u8>>,
+    /// An index into `palette` for every pixel
+    pub indices: Vec<u8>,
+}
+
+/// Builds a palette of at most `max_colors` colors, and maps the pixels to it.
+///
+/// If the image has `max_colors` or fewer distinct colors, they're used as-is.
+///
+/// # Panics
+///
+/// If `max_colors` is not in `1..=256`.
+#[must_use]
+pub fn quantize(pixels: &[Rgba<u8>], max_colors: usize) -> Quantized {
+    let palette = build_palette(pixels, max_colors);
+    let indices = remap(pixels, &palette);
+    Quantized { palette, indices }
+}
+
+/// Same as [`quantize()`], but with Floyd–Steinberg dithering applied when mapping the pixels.
+///
+/// The image is `width` pixels wide, and the rows are contiguous.
+///
+/// # Panics
+///
+/// If `max_colors` is not in `1..=256`, or `pixels.len()` is not a multiple of `width`.
+#[must_use]
+pub fn quantize_dithered(pixels: &[Rgba<u8>], width: usize, max_colors: usize) -> Quantized {
+    let palette = build_palette(pixels, max_colors);
+    let indices = remap_dithered(pixels, width, &palette);
+    Quantized { palette, indices }
+}
+
+/// A color premultiplied by alpha, in `0.0..=255.0`
+type Point = [f32; 4];
+
+fn to_point(px: Rgba<u8>) -> Point {
+    let a = f32::from(px.a) / 255.;
+    [f32::from(px.r) * a, f32::from(px.g) * a, f32::from(px.b) * a, f32::from(px.a)]
+}
+
+fn from_point(p: Point) -> Rgba<u8> {
+    let a = (p[3].clamp(0., 255.) + 0.5) as u8;
+    if a == 0 {
+        return Rgba::new(0, 0, 0, 0);
+    }
+    let [r, g, b] = [p[0], p[1], p[2]].map(|c| (c * 255. / f32::from(a) + 0.5).clamp(0., 255.) as u8);
+    Rgba::new(r, g, b, a)
+}
+
+fn distance(a: &Point, b: &Point) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+fn nearest(palette: &[Point], p: &Point) -> usize {
+    let mut best = 0;
+    let mut best_dist = f32::INFINITY;
+    for (i, entry) in palette.iter().enumerate() {
+        let dist = distance(entry, p);
+        if dist < best_dist {
+            best = i;
+            best_dist = dist;
+        }
+    }
+    best
+}
+
+/// All fully transparent colors are the same
+fn key(px: Rgba<u8>) -> u32 {
+    if px.a == 0 {
+        return 0;
+    }
+    u32::from_be_bytes([px.r, px.g, px.b, px.a])
+}
+
+fn from_key(key: u32) -> Rgba<u8> {
+    let [r, g, b, a] = key.to_be_bytes();
+    Rgba::new(r, g, b, a)
+}
+
+/// Distinct colors and their counts, sorted by key
+fn histogram(pixels: &[Rgba<u8>]) -> Vec<(u32, u32)> {
+    let mut keys: Vec<u32> = pixels.iter().copied().map(key).collect();
+    keys.sort_unstable();
+    let mut hist: Vec<(u32, u32)> = Vec::new();
+    for k in keys {
+        match hist.last_mut() {
+            Some((last, count)) if *last == k => *count += 1,
+            _ => hist.push((k, 1)),
+        }
+    }
+    hist
+}
+
+/// A range of colors in median cut
+struct ColorBox {
+    start: usize,
+    end: usize,
+    mean: Point,
+    /// sum of squared distances from the mean, per axis
+    variance: [f64; 4],
+}
+
+impl ColorBox {
+    fn new(colors: &[(Point, u32)], start: usize, end: usize) -> Self {
+        let colors = &colors[start..end];
+        let weight: f64 = colors.iter().map(|&(_, n)| f64::from(n)).sum();
+        let mut mean = [0f64; 4];
+        for (p, n) in colors {
+            for (m, &c) in mean.iter_mut().zip(p) {
+                *m += f64::from(c) * f64::from(*n);
+            }
+        }
+        let mean = mean.map(|m| m / weight);
+        let mut variance = [0f64; 4];
+        for (p, n) in colors {
+            for ((v, &c), m) in variance.iter_mut().zip(p).zip(mean) {
+                *v += (f64::from(c) - m) * (f64::from(c) - m) * f64::from(*n);
+            }
+        }
+        Self { start, end, mean: mean.map(|m| m as f32), variance }
+    }
+
+    fn error(&self) -> f64 {
+        self.variance.iter().sum()
+    }
+}
+
+/// Builds a palette of at most `max_colors` colors for the pixels.
+///
+/// If any pixel is fully transparent, the palette has an `Rgba(0, 0, 0, 0)` entry.
+///
+/// # Panics
+///
+/// If `max_colors` is not in `1..=256`.
+#[must_use]
+pub fn build_palette(pixels: &[Rgba<u8>], max_colors: usize) -> Vec<Rgba<u8>> {
+    assert!((1..=256).contains(&max_colors), "max_colors must be 1..=256");
+    let mut hist = histogram(pixels);
+    if hist.len() <= max_colors {
+        return hist.into_iter().map(|(k, _)| from_key(k)).collect();
+    }
+
+    let mut palette = Vec::with_capacity(max_colors);
+    // keep an exact transparent entry, e.g. for GIF
+    if hist[0].0 == 0 {
+        hist.remove(0);
+        palette.push(Rgba::new(0, 0, 0, 0));
+    }
+    let mut colors: Vec<(Point, u32)> = hist.into_iter().map(|(k, n)| (to_point(from_key(k)), n)).collect();
+    let num_boxes = max_colors - palette.len();
+    if num_boxes == 0 {
+        return palette;
+    }
+
+    let mut boxes = vec![ColorBox::new(&colors, 0, colors.len())];
+    while boxes.len() < num_boxes {
+        let Some((i, _)) = boxes.iter().enumerate()
+            .filter(|(_, b)| b.end - b.start > 1)
+            .max_by(|(_, a), (_, b)| a.error().total_cmp(&b.error())) else {
+            break;
+        };
+        let b = boxes.swap_remove(i);
+        let axis = (0..4).max_by(|&x, &y| b.variance[x].total_cmp(&b.variance[y])).unwrap_or(0);
+        let slice = &mut colors[b.start..b.end];
+        slice.sort_unstable_by(|(p1, _), (p2, _)| p1[axis].total_cmp(&p2[axis]));
+        // split at the weighted median, leaving at least one color on each side
+        let total: u64 = slice.iter().map(|&(_, n)| u64::from(n)).sum();
+        let mut sum = 0;
+        let mut split = slice.len() - 1;
+        for (i, &(_, n)) in slice.iter().enumerate().take(slice.len() - 1) {
+            sum += u64::from(n);
+            if sum * 2 >= total {
+                split = i + 1;
+                break;
+            }
+        }
+        boxes.push(ColorBox::new(&colors, b.start, b.start + split));
+        boxes.push(ColorBox::new(&colors, b.start + split, b.end));
+    }
+
+    let mut centroids: Vec<Point> = boxes.iter().map(|b| b.mean).collect();
+    refine(&colors, &mut centroids);
+    palette.extend(centroids.into_iter().map(from_point));
+    palette
+}
+
+/// A few iterations of weighted k-means
+fn refine(colors: &[(Point, u32)], centroids: &mut [Point]) {
+    let mut sums = vec![([0f64; 4], 0f64); centroids.len()];
+    for _ in 0..4 {
+        sums.fill(([0.; 4], 0.));
+        for (p, n) in colors {
+            let (sum, weight) = &mut sums[nearest(centroids, p)];
+            for (s, &c) in sum.iter_mut().zip(p) {
+                *s += f64::from(c) * f64::from(*n);
+            }
+            *weight += f64::from(*n);
+        }
+        for (c, (sum, weight)) in centroids.iter_mut().zip(&sums) {
+            if *weight > 0. {
+                *c = sum.map(|s| (s / weight) as f32);
+            }
+        }
+    }
+}
+
+/// Maps every pixel to the index of the closest palette color.
+///
+/// # Panics
+///
+/// If the palette is empty while there are pixels, or the palette has more than 256 colors.
+#[must_use]
+pub fn remap(pixels: &[Rgba<u8>], palette: &[Rgba<u8>]) -> Vec<u8> {
+    assert!(palette.len() <= 256);
+    assert!(pixels.is_empty() || !palette.is_empty());
+    let points: Vec<Point> = palette.iter().copied().map(to_point).collect();
+    // each distinct color is searched only once
+    let hist = histogram(pixels);
+    let mapping: Vec<u8> = hist.iter().map(|&(k, _)| nearest(&points, &to_point(from_key(k))) as u8).collect();
+    pixels.iter().map(|&px| {
+        let i = hist.binary_search_by_key(&key(px), |&(k, _)| k).unwrap_or_default();
+        mapping[i]
+    }).collect()
+}
+
+/// Maps every pixel to the index of a palette color, with Floyd–Steinberg dithering.
+///
+/// The image is `width` pixels wide, and the rows are contiguous. Rows are processed in alternating directions.
+///
+/// # Panics
+///
+/// If the palette is empty while there are pixels, the palette has more than 256 colors,
+/// or `pixels.len()` is not a multiple of `width`.
+#[must_use]
+pub fn remap_dithered(pixels: &[Rgba<u8>], width: usize, palette: &[Rgba<u8>]) -> Vec<u8> {
+    assert!(palette.len() <= 256);
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+    assert!(width > 0 && pixels.len() % width == 0, "the pixels must be a whole number of rows");
+    let points: Vec<Point> = palette.iter().copied().map(to_point).collect();
+    assert!(!points.is_empty());
+    let mut indices = vec![0; pixels.len()];
+    let rows = pixels.chunks_exact(width).zip(indices.chunks_exact_mut(width));
+    diffuse(Diffusion::FloydSteinberg, width, rows, |(row, out), x, carried| {
+        let mut p = to_point(row[x]);
+        for (c, e) in p.iter_mut().zip(carried) {
+            *c = (*c + e).clamp(0., 255.);
+        }
+        // don't let the error make colors more opaque than alpha allows
+        for c in 0..3 {
+            p[c] = p[c].min(p[3]);
+        }
+        let best = nearest(&points, &p);
+        out[x] = best as u8;
+        core::array::from_fn(|c| p[c] - points[best][c])
+    });
+    indices
+}
+
+#[test]
+fn exact_palette() {
+    let pixels = [Rgba::new(1, 2, 3, 4), Rgba::new(9, 9, 9, 0), Rgba::new(1, 2, 3, 4), Rgba::new(0, 0, 0, 0)];
+    let q = quantize(&pixels, 2);
+    assert_eq!(q.palette, [Rgba::new(0, 0, 0, 0), Rgba::new(1, 2, 3, 4)]);
+    assert_eq!(q.indices, [1, 0, 1, 0]);
+    assert_eq!(quantize(&[], 1), Quantized::default());
+}
+
+#[test]
+fn reduces_colors() {
+    let pixels: Vec<Rgba<u8>> = (0..64 * 64u32)
+        .map(|i| Rgba::new((i % 64 * 4) as u8, (i / 64 * 4) as u8, 128, if i % 7 == 0 { 0 } else { 255 }))
+        .collect();
+    for max_colors in [1, 2, 16, 256] {
+        let q = quantize(&pixels, max_colors);
+        assert!(q.palette.len() <= max_colors);
+        assert_eq!(q.indices.len(), pixels.len());
+        assert!(q.indices.iter().all(|&i| usize::from(i) < q.palette.len()));
+        if max_colors > 1 {
+            assert_eq!(q.palette[0], Rgba::new(0, 0, 0, 0));
+            assert!(pixels.iter().zip(&q.indices).all(|(px, &i)| (px.a == 0) == (i == 0)));
+        }
+        let d = quantize_dithered(&pixels, 64, max_colors);
+        assert_eq!(d.palette, q.palette);
+        assert!(d.indices.iter().all(|&i| usize::from(i) < q.palette.len()));
+    }
+    let q = quantize(&pixels, 256);
+    let error: f32 = pixels.iter().zip(&q.indices).map(|(&px, &i)| distance(&to_point(px), &to_point(q.palette[usize::from(i)]))).sum();
+    assert!(error / (pixels.len() as f32) < 40., "{error}");
+}
+
+#[test]
+#[should_panic]
+fn remap_empty_palette() {
+    let _ = remap(&[Rgba::new(1, 2, 3, 4)], &[]);
+}
+
+#[test]
+fn dithering_preserves_average() {
+    let pixels = vec![Rgba::new(100, 100, 100, 255); 32 * 32];
+    let palette = [Rgba::new(0, 0, 0, 255), Rgba::new(255, 255, 255, 255)];
+    let indices = remap_dithered(&pixels, 32, &palette);
+    let white = indices.iter().filter(|&&i| i == 1).count();
+    assert!((white as f32 / 1024. - 100. / 255.).abs() < 0.01, "{white}");
+    assert!(remap(&pixels, &palette).iter().all(|&i| i == 0));
+}
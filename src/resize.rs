@@ -0,0 +1,516 @@
+//! Downsampling [images](crate::image) in linear light, weighted by alpha, e.g. for mipmaps and thumbnails.
+//!
+//! Averaging gamma-encoded values directly, e.g. with the `Sum` impls of pixels, makes the results too dark,
+//! and averaging colors of transparent pixels makes their garbage colors bleed into the visible ones.
+//! These functions decode `u8` and `u16` components from sRGB to linear light (see [`srgb`](crate::srgb)),
+//! weight the colors by alpha, and accumulate the sums in floating point.
+//! `f32` components are assumed to be already linear, and aren't decoded.
+//!
+//! * [`downsample_2x2()`] halves the width and height, e.g. for the next level of a mipmap.
+//! * [`resize_box()`] averages the area of every destination pixel, for any ratio.
+//! * [`resize()`] uses a separable [`Filter`], which is sharper, and needs the `std` feature.
+//!
+//! ```
+//! use rgb::Rgb;
+//! use rgb::image::{ImageView, ImageViewMut};
+//!
+//! let checkerboard = [Rgb::new(0u8, 0, 0), Rgb::new(255, 255, 255), Rgb::new(255, 255, 255), Rgb::new(0, 0, 0)];
+//! let mut average = [Rgb::new(0u8, 0, 0)];
+//! rgb::resize::downsample_2x2(
+//!     ImageView::new(&checkerboard, 2, 2).unwrap(),
+//!     &mut ImageViewMut::new(&mut average, 1, 1).unwrap(),
+//! );
+//! // half of the light of white, not 128
+//! assert_eq!(average[0], Rgb::new(188, 188, 188));
+//! ```
+
+use crate::formats::gray::Gray_v09;
+use crate::image::{ImageView, ImageViewMut};
+use crate::srgb::SrgbComponent;
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Rgb, Rgba};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Linear-light color components multiplied by alpha, and alpha. Gray uses only the first color.
+type Premul = [f32; 4];
+
+/// A pixel that can be resized by this module.
+///
+/// This trait is sealed, and implemented for [`Rgb`], [`Bgr`], [`Rgba`], [`Argb`], [`Bgra`], [`Abgr`], [`Gray`](Gray_v09),
+/// and [`GrayA`] with `u8`, `u16`, and `f32` components.
+pub trait ResizePixel: Copy + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {
+        /// Decodes to linear light premultiplied by alpha
+        fn to_premul(self) -> super::Premul;
+        /// Encodes from linear light premultiplied by alpha. Negative values from ringing are clamped.
+        fn from_premul(px: super::Premul) -> Self;
+    }
+}
+
+/// A component type of [`ResizePixel`]s: `u8` and `u16` are gamma-encoded, and `f32` is linear
+trait Component: Copy + 'static {
+    fn decode(self) -> f32;
+    fn encode(linear: f32) -> Self;
+    fn decode_alpha(self) -> f32;
+    fn encode_alpha(alpha: f32) -> Self;
+}
+
+macro_rules! srgb_component {
+    ($t:ty) => {
+        impl Component for $t {
+            #[inline(always)]
+            fn decode(self) -> f32 {
+                SrgbComponent::to_linear(self)
+            }
+
+            #[inline(always)]
+            fn encode(linear: f32) -> Self {
+                SrgbComponent::from_linear(linear)
+            }
+
+            #[inline(always)]
+            fn decode_alpha(self) -> f32 {
+                SrgbComponent::alpha_to_f32(self)
+            }
+
+            #[inline(always)]
+            fn encode_alpha(alpha: f32) -> Self {
+                SrgbComponent::alpha_from_f32(alpha)
+            }
+        }
+    };
+}
+
+srgb_component!(u8);
+srgb_component!(u16);
+
+impl Component for f32 {
+    #[inline(always)]
+    fn decode(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn encode(linear: f32) -> Self {
+        linear.max(0.)
+    }
+
+    #[inline(always)]
+    fn decode_alpha(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn encode_alpha(alpha: f32) -> Self {
+        alpha.clamp(0., 1.)
+    }
+}
+
+macro_rules! resize_without_alpha {
+    ($name:ident, [$($color:ident $i:literal),+]) => {
+        impl<T: Component> sealed::Sealed for $name<T> {
+            #[inline(always)]
+            fn to_premul(self) -> Premul {
+                let mut px = [0., 0., 0., 1.];
+                $(px[$i] = self.$color.decode();)+
+                px
+            }
+
+            #[inline(always)]
+            fn from_premul(px: Premul) -> Self {
+                Self { $($color: T::encode(px[$i])),+ }
+            }
+        }
+        impl<T: Component> ResizePixel for $name<T> {}
+    };
+}
+
+macro_rules! resize_with_alpha {
+    ($name:ident, [$($color:ident $i:literal),+]) => {
+        impl<T: Component> sealed::Sealed for $name<T> {
+            #[inline(always)]
+            fn to_premul(self) -> Premul {
+                let a = self.a.decode_alpha();
+                let mut px = [0., 0., 0., a];
+                $(px[$i] = self.$color.decode() * a;)+
+                px
+            }
+
+            #[inline(always)]
+            fn from_premul(px: Premul) -> Self {
+                let a = px[3].min(1.);
+                let scale = if a > 0. { 1. / a } else { 0. };
+                Self { $($color: T::encode(px[$i] * scale),)+ a: T::encode_alpha(a) }
+            }
+        }
+        impl<T: Component> ResizePixel for $name<T> {}
+    };
+}
+
+resize_without_alpha!(Rgb, [r 0, g 1, b 2]);
+resize_without_alpha!(Bgr, [r 0, g 1, b 2]);
+resize_without_alpha!(Gray_v09, [v 0]);
+resize_with_alpha!(Rgba, [r 0, g 1, b 2]);
+resize_with_alpha!(Argb, [r 0, g 1, b 2]);
+resize_with_alpha!(Bgra, [r 0, g 1, b 2]);
+resize_with_alpha!(Abgr, [r 0, g 1, b 2]);
+resize_with_alpha!(GrayA, [v 0]);
+
+#[inline(always)]
+fn add_weighted(sum: &mut [f64; 4], px: Premul, weight: f64) {
+    for (s, c) in sum.iter_mut().zip(px) {
+        *s += f64::from(c) * weight;
+    }
+}
+
+#[inline(always)]
+fn to_f32(sum: [f64; 4], scale: f64) -> Premul {
+    sum.map(|s| (s * scale) as f32)
+}
+
+/// Halves the width and height, averaging every 2×2 block of pixels.
+///
+/// `dst` must be `src.width().div_ceil(2)` × `src.height().div_ceil(2)`. If the width or height of `src` is odd,
+/// the last column or row is averaged from fewer pixels.
+///
+/// # Panics
+///
+/// If `dst` has a different size.
+pub fn downsample_2x2<P: ResizePixel>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    let (width, height) = (src.width(), src.height());
+    assert_eq!((width.div_ceil(2), height.div_ceil(2)), (dst.width(), dst.height()));
+    for (y, dst_row) in dst.rows_mut().enumerate() {
+        let top = src.row(2 * y).unwrap();
+        let bottom = src.row(2 * y + 1);
+        for (x, d) in dst_row.iter_mut().enumerate() {
+            let mut sum = [0.; 4];
+            let mut count = 0u32;
+            for row in core::iter::once(top).chain(bottom) {
+                for &px in &row[2 * x..(2 * x + 2).min(width)] {
+                    add_weighted(&mut sum, px.to_premul(), 1.);
+                    count += 1;
+                }
+            }
+            *d = P::from_premul(to_f32(sum, 1. / f64::from(count)));
+        }
+    }
+}
+
+/// Source pixels `start..end` covering destination pixel `i`, and the coverage of the first and last one
+#[inline]
+fn box_span(i: usize, scale: f64, src_len: usize) -> (usize, usize, f64, f64) {
+    let (x0, x1) = (i as f64 * scale, (i + 1) as f64 * scale);
+    let start = (x0 as usize).min(src_len - 1);
+    // `ceil()` isn't in `core`
+    let end = x1 as usize + usize::from((x1 as usize as f64) < x1);
+    let end = end.clamp(start + 1, src_len);
+    let first = ((start + 1) as f64).min(x1) - x0;
+    let last = x1 - ((end - 1) as f64).max(x0);
+    (start, end, first, last)
+}
+
+#[inline]
+fn box_weight(j: usize, (start, end, first, last): (usize, usize, f64, f64)) -> f64 {
+    if j == start {
+        first
+    } else if j + 1 == end {
+        last
+    } else {
+        1.
+    }
+}
+
+/// Resizes to the size of `dst` by averaging the area of `src` that every destination pixel covers.
+///
+/// Source pixels that are partially covered are weighted by the covered part, so the ratio doesn't have to be an integer.
+/// This doesn't allocate, and every source pixel is decoded once or a few times.
+/// When enlarging, it repeats pixels like the nearest-neighbor filter.
+///
+/// # Panics
+///
+/// If `src` is empty, and `dst` isn't.
+///
+/// ```
+/// use rgb::Rgba;
+/// use rgb::image::{ImageView, ImageViewMut};
+///
+/// let src = [Rgba::new(1.0f32, 0., 0., 1.), Rgba::new(0., 1., 0., 0.), Rgba::new(0., 0., 1., 1.)];
+/// let mut dst = [Rgba::default(); 2];
+/// rgb::resize::resize_box(ImageView::new(&src, 3, 1).unwrap(), &mut ImageViewMut::new(&mut dst, 2, 1).unwrap());
+/// // the transparent green pixel doesn't change the color
+/// assert_eq!(dst[0], Rgba::new(1., 0., 0., 2. / 3.));
+/// ```
+pub fn resize_box<P: ResizePixel>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    let (dst_width, dst_height) = (dst.width(), dst.height());
+    if dst_width == 0 || dst_height == 0 {
+        return;
+    }
+    let (width, height) = (src.width(), src.height());
+    assert!(width > 0 && height > 0, "the source image is empty");
+    let x_scale = width as f64 / dst_width as f64;
+    let y_scale = height as f64 / dst_height as f64;
+    let area = 1. / (x_scale * y_scale);
+    for (dy, dst_row) in dst.rows_mut().enumerate() {
+        let y_span = box_span(dy, y_scale, height);
+        for (dx, d) in dst_row.iter_mut().enumerate() {
+            let x_span = box_span(dx, x_scale, width);
+            let mut sum = [0.; 4];
+            for y in y_span.0..y_span.1 {
+                let wy = box_weight(y, y_span);
+                let row = &src.row(y).unwrap()[x_span.0..x_span.1];
+                for (x, &px) in (x_span.0..).zip(row) {
+                    add_weighted(&mut sum, px.to_premul(), wy * box_weight(x, x_span));
+                }
+            }
+            *d = P::from_premul(to_f32(sum, area));
+        }
+    }
+}
+
+/// A filter for [`resize()`].
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Filter {
+    /// Mitchell–Netravali cubic with B = C = ⅓. Smooth, with little ringing.
+    #[default]
+    Mitchell,
+    /// Lanczos with 3 lobes. Sharp, but may ring around hard edges.
+    Lanczos3,
+}
+
+#[cfg(feature = "std")]
+impl Filter {
+    #[inline]
+    fn support(self) -> f64 {
+        match self {
+            Self::Mitchell => 2.,
+            Self::Lanczos3 => 3.,
+        }
+    }
+
+    fn kernel(self, x: f64) -> f64 {
+        let x = x.abs();
+        match self {
+            Self::Mitchell => {
+                const B: f64 = 1. / 3.;
+                const C: f64 = 1. / 3.;
+                if x < 1. {
+                    ((12. - 9. * B - 6. * C) * x * x * x + (-18. + 12. * B + 6. * C) * x * x + (6. - 2. * B)) / 6.
+                } else if x < 2. {
+                    ((-B - 6. * C) * x * x * x + (6. * B + 30. * C) * x * x + (-12. * B - 48. * C) * x + (8. * B + 24. * C)) / 6.
+                } else {
+                    0.
+                }
+            },
+            Self::Lanczos3 => {
+                if x < 1e-8 {
+                    1.
+                } else if x < 3. {
+                    let px = core::f64::consts::PI * x;
+                    3. * px.sin() * (px / 3.).sin() / (px * px)
+                } else {
+                    0.
+                }
+            },
+        }
+    }
+}
+
+/// Weights of the source pixels for every destination pixel along one axis
+#[cfg(feature = "std")]
+struct Weights {
+    /// Number of weights per destination pixel
+    taps: usize,
+    /// The first source pixel of every destination pixel
+    starts: Vec<usize>,
+    /// `taps` weights for every destination pixel, zero-padded at the edges
+    weights: Vec<f64>,
+}
+
+#[cfg(feature = "std")]
+impl Weights {
+    fn new(filter: Filter, src_len: usize, dst_len: usize) -> Self {
+        let scale = src_len as f64 / dst_len as f64;
+        // when shrinking, the kernel is stretched to cover all the source pixels
+        let stretch = scale.max(1.);
+        let radius = filter.support() * stretch;
+        let taps = ((2. * radius).ceil() as usize + 1).min(src_len);
+        let mut starts = Vec::with_capacity(dst_len);
+        let mut weights = std::vec![0.; taps * dst_len];
+        for (i, w) in weights.chunks_exact_mut(taps).enumerate() {
+            let center = (i as f64 + 0.5) * scale - 0.5;
+            let start = ((center - radius).ceil().max(0.) as usize).min(src_len - taps);
+            starts.push(start);
+            let mut sum = 0.;
+            for (j, w) in (start..).zip(w.iter_mut()) {
+                *w = filter.kernel((j as f64 - center) / stretch);
+                sum += *w;
+            }
+            if sum != 0. {
+                w.iter_mut().for_each(|w| *w /= sum);
+            } else {
+                // the nearest pixel, for kernels that are zero at every pixel center in range
+                let nearest = (center.round().max(0.) as usize).clamp(start, start + taps - 1);
+                w[nearest - start] = 1.;
+            }
+        }
+        Self { taps, starts, weights }
+    }
+
+    #[inline]
+    fn get(&self, i: usize) -> (usize, &[f64]) {
+        (self.starts[i], &self.weights[i * self.taps..(i + 1) * self.taps])
+    }
+}
+
+/// Resizes to the size of `dst` with a separable filter, first horizontally and then vertically.
+///
+/// The filter is stretched when shrinking, so that every source pixel contributes to the result.
+/// Pixels at the edges are weighted only by the part of the filter that's inside the image.
+///
+/// # Panics
+///
+/// If `src` is empty, and `dst` isn't.
+///
+/// ```
+/// use rgb::Gray_v09 as Gray;
+/// use rgb::image::{ImageView, ImageViewMut};
+/// use rgb::resize::{resize, Filter};
+///
+/// let src: Vec<_> = (0..64u8).map(|x| Gray::new(x * 4)).collect();
+/// let mut dst = [Gray::new(0u8); 16];
+/// resize(ImageView::new(&src, 64, 1).unwrap(), &mut ImageViewMut::new(&mut dst, 16, 1).unwrap(), Filter::Lanczos3);
+/// assert!(dst.windows(2).all(|w| w[0].v < w[1].v));
+/// ```
+#[cfg(feature = "std")]
+pub fn resize<P: ResizePixel>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>, filter: Filter) {
+    let (dst_width, dst_height) = (dst.width(), dst.height());
+    if dst_width == 0 || dst_height == 0 {
+        return;
+    }
+    let (width, height) = (src.width(), src.height());
+    assert!(width > 0 && height > 0, "the source image is empty");
+
+    let horizontal = Weights::new(filter, width, dst_width);
+    let vertical = Weights::new(filter, height, dst_height);
+
+    // only the source rows used by the destination are filtered horizontally
+    let first_row = vertical.starts[0];
+    let last_row = vertical.starts[dst_height - 1] + vertical.taps;
+    let mut decoded = Vec::with_capacity(width);
+    let mut columns = Vec::with_capacity((last_row - first_row) * dst_width);
+    for row in src.rows().skip(first_row).take(last_row - first_row) {
+        decoded.clear();
+        decoded.extend(row.iter().map(|px| px.to_premul()));
+        columns.extend((0..dst_width).map(|x| {
+            let (start, weights) = horizontal.get(x);
+            let mut sum = [0.; 4];
+            for (&px, &w) in decoded[start..].iter().zip(weights) {
+                add_weighted(&mut sum, px, w);
+            }
+            to_f32(sum, 1.)
+        }));
+    }
+
+    let mut sums = std::vec![[0.; 4]; dst_width];
+    for (y, dst_row) in dst.rows_mut().enumerate() {
+        let (start, weights) = vertical.get(y);
+        sums.fill([0.; 4]);
+        for (row, &w) in (start - first_row..).zip(weights) {
+            for (sum, &px) in sums.iter_mut().zip(&columns[row * dst_width..(row + 1) * dst_width]) {
+                add_weighted(sum, px, w);
+            }
+        }
+        for (d, &sum) in dst_row.iter_mut().zip(&sums) {
+            *d = P::from_premul(to_f32(sum, 1.));
+        }
+    }
+}
+
+#[test]
+fn linear_average() {
+    let px = [Rgba::new(255u8, 0, 0, 255), Rgba::new(0, 255, 0, 0), Rgba::new(0, 0, 0, 255), Rgba::new(255, 0, 0, 255)];
+    let mut dst = [Rgba::default()];
+    downsample_2x2(ImageView::new(&px, 2, 2).unwrap(), &mut ImageViewMut::new(&mut dst, 1, 1).unwrap());
+    // no green, and red is the linear average of 2/3 of full red
+    assert_eq!(dst[0].g, 0);
+    assert_eq!(dst[0].a, 191);
+    assert_eq!(dst[0].r, u8::encode(2. / 3.));
+
+    let mut dst = [Rgba::new(1u8, 1, 1, 1)];
+    let transparent = [Rgba::new(9u8, 9, 9, 0); 4];
+    downsample_2x2(ImageView::new(&transparent, 2, 2).unwrap(), &mut ImageViewMut::new(&mut dst, 1, 1).unwrap());
+    assert_eq!(dst[0], Rgba::new(0, 0, 0, 0));
+
+    // u16 doesn't overflow, and uniform colors stay the same
+    let px = [GrayA::new(65535u16, 65535); 9];
+    let mut dst = [GrayA::default(); 4];
+    downsample_2x2(ImageView::new(&px, 3, 3).unwrap(), &mut ImageViewMut::new(&mut dst, 2, 2).unwrap());
+    assert_eq!(dst, [GrayA::new(65535, 65535); 4]);
+}
+
+#[test]
+fn downsample_odd() {
+    let src: std::vec::Vec<_> = (0..15).map(|i| Gray_v09::new(i as f32)).collect();
+    let src = ImageView::new(&src, 5, 3).unwrap();
+    let mut buf = [Gray_v09::new(0.); 6];
+    downsample_2x2(src, &mut ImageViewMut::new(&mut buf, 3, 2).unwrap());
+    assert_eq!(buf.map(|g| g.v), [3., 5., 6.5, 10.5, 12.5, 14.]);
+
+    // box of an exact ratio is the same
+    let mut boxed = [Gray_v09::new(0.); 2];
+    resize_box(src.sub_view(0, 0, 4, 2).unwrap(), &mut ImageViewMut::new(&mut boxed, 2, 1).unwrap());
+    assert_eq!(boxed, buf[..2]);
+}
+
+#[test]
+fn box_ratios() {
+    let uniform = [Bgra::new_bgra(10u16, 2000, 30000, 40000); 7 * 5];
+    let src = ImageView::new(&uniform, 7, 5).unwrap();
+    for (w, h) in [(3, 2), (1, 1), (7, 5), (2, 4), (10, 11)] {
+        let mut buf = [Bgra::default(); 110];
+        let mut dst = ImageViewMut::new(&mut buf, w, h).unwrap();
+        resize_box(src, &mut dst);
+        assert!(dst.rows().flatten().all(|&px| px == uniform[0]), "{w}x{h}");
+    }
+
+    // 3 to 2 covers 1.5 pixels each
+    let src = [Gray_v09::new(0.0f32), Gray_v09::new(1.), Gray_v09::new(3.)];
+    let mut dst = [Gray_v09::new(0.); 2];
+    resize_box(ImageView::new(&src, 3, 1).unwrap(), &mut ImageViewMut::new(&mut dst, 2, 1).unwrap());
+    assert_eq!(dst, [Gray_v09::new(1. / 3.), Gray_v09::new(7. / 3.)]);
+
+    resize_box(ImageView::new(&src[..0], 0, 0).unwrap(), &mut ImageViewMut::new(&mut dst, 0, 1).unwrap());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn filters() {
+    let uniform = [Rgb::new(10u8, 200, 255); 37 * 23];
+    let src = ImageView::new(&uniform, 37, 23).unwrap();
+    for filter in [Filter::Mitchell, Filter::Lanczos3] {
+        for (w, h) in [(9, 5), (1, 1), (37, 23), (40, 30), (2, 23)] {
+            let mut buf = [Rgb::default(); 1200];
+            let mut dst = ImageViewMut::new(&mut buf, w, h).unwrap();
+            resize(src, &mut dst, filter);
+            assert!(dst.rows().flatten().all(|&px| px == uniform[0]), "{filter:?} {w}x{h}");
+        }
+
+        // the colors of transparent pixels don't bleed
+        let src: std::vec::Vec<_> = (0..100).map(|i| if i % 3 == 0 { Rgba::new(0., 1., 0., 1.) } else { Rgba::new(1., 0., 0., 0.) }).collect();
+        let mut dst = [Rgba::default(); 12];
+        resize(ImageView::new(&src, 10, 10).unwrap(), &mut ImageViewMut::new(&mut dst, 4, 3).unwrap(), filter);
+        for px in dst {
+            assert!(px.r == 0. && px.b == 0. && (px.g - 1.).abs() < 1e-5 && px.a > 0.1 && px.a < 0.6, "{px:?}");
+        }
+    }
+
+    // identity size is the same image
+    let src: std::vec::Vec<_> = (0..=255u8).map(|v| GrayA::new(v, 255)).collect();
+    let mut dst = [GrayA::default(); 256];
+    resize(ImageView::new(&src, 16, 16).unwrap(), &mut ImageViewMut::new(&mut dst, 16, 16).unwrap(), Filter::Lanczos3);
+    assert_eq!(dst[..], src[..]);
+}
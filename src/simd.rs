@@ -0,0 +1,985 @@
+//! Bulk operations on slices of 8-bit pixels, accelerated with SIMD instructions.
+//!
+//! On `x86_64` the functions use SSE2, SSSE3, or AVX2, and on `aarch64` they use NEON.
+//! With the `std` feature the best instruction set is chosen at run time. Without it, only
+//! the features enabled at compile time (e.g. with `-C target-cpu=native`) are used.
+//! On other architectures, and for the remainder of slices that don't fill a whole vector,
+//! a scalar implementation is used. All implementations give bit-identical results,
+//! which are the same as the per-pixel conversions in this crate.
+//!
+//! [`swizzle_in_place()`](crate::swizzle::swizzle_in_place) uses these kernels too, for pixels with 4 one-byte components.
+//!
+//! ```
+//! use rgb::{PremulRgba, Rgb, Rgba};
+//!
+//! let rgb = vec![Rgb::new(255u8, 128, 0); 100];
+//! let mut rgba = vec![Rgba::new(0, 0, 0, 0); 100];
+//! rgb::simd::rgb_to_rgba(&rgb, 128, &mut rgba);
+//!
+//! let mut premul = vec![PremulRgba::new(0, 0, 0, 0); 100];
+//! rgb::simd::premultiply(&rgba, &mut premul);
+//! assert_eq!(premul[99], PremulRgba::new(128, 64, 0, 128));
+//! ```
+
+use crate::{Abgr, Argb, Bgra, Rgb, Rgba};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use core::mem::size_of_val;
+
+/// An instruction set, in order of preference. A level is only used if the CPU supports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    #[allow(dead_code)] // x86_64 always has SSE2
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    #[cfg(target_arch = "x86_64")]
+    Ssse3,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+#[cfg(any(test, feature = "std"))]
+macro_rules! has_feature {
+    (x86, $f:tt) => { std::is_x86_feature_detected!($f) };
+    (aarch64, $f:tt) => { std::arch::is_aarch64_feature_detected!($f) };
+}
+
+#[cfg(not(any(test, feature = "std")))]
+macro_rules! has_feature {
+    ($arch:tt, $f:tt) => { cfg!(target_feature = $f) };
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+#[allow(clippy::ifs_same_cond)] // without std, the conditions are constants
+fn detect() -> Level {
+    if has_feature!(x86, "avx2") {
+        Level::Avx2
+    } else if has_feature!(x86, "ssse3") {
+        Level::Ssse3
+    } else {
+        Level::Sse2
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline]
+fn detect() -> Level {
+    if has_feature!(aarch64, "neon") {
+        Level::Neon
+    } else {
+        Level::Scalar
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[inline]
+fn detect() -> Level {
+    Level::Scalar
+}
+
+/// Views pixels as their components.
+///
+/// SAFETY: `P` must be a `repr(C)` struct of only `u8`-sized fields.
+#[inline(always)]
+pub(crate) unsafe fn as_bytes<P>(pixels: &[P]) -> &[u8] {
+    core::slice::from_raw_parts(pixels.as_ptr().cast(), size_of_val(pixels))
+}
+
+/// SAFETY: `P` must be a `repr(C)` struct of only `u8`-sized fields, valid for any bit pattern.
+#[inline(always)]
+pub(crate) unsafe fn as_bytes_mut<P>(pixels: &mut [P]) -> &mut [u8] {
+    core::slice::from_raw_parts_mut(pixels.as_mut_ptr().cast(), size_of_val(pixels))
+}
+
+/// The reference implementations. The SIMD kernels process whole vectors, and leave the rest to these.
+mod scalar {
+    use crate::depth::ConvertDepth;
+    use crate::premul::PremultiplyComponent;
+
+    pub fn expand3(src: &[u8], alpha: u8, dst: &mut [u8]) {
+        for (s, d) in src.chunks_exact(3).zip(dst.chunks_exact_mut(4)) {
+            d[..3].copy_from_slice(s);
+            d[3] = alpha;
+        }
+    }
+
+    pub fn strip4(src: &[u8], dst: &mut [u8]) {
+        for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(3)) {
+            d.copy_from_slice(&s[..3]);
+        }
+    }
+
+    pub fn permute4(pixels: &mut [u8], perm: [u8; 4]) {
+        for px in pixels.chunks_exact_mut(4) {
+            let old = [px[0], px[1], px[2], px[3]];
+            for (c, &p) in px.iter_mut().zip(&perm) {
+                *c = old[usize::from(p)];
+            }
+        }
+    }
+
+    pub fn premultiply4<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) {
+        for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+            let a = s[ALPHA];
+            for (i, (&s, d)) in s.iter().zip(d).enumerate() {
+                *d = if i == ALPHA { a } else { s.premultiply(a) };
+            }
+        }
+    }
+
+    pub fn unpremultiply4<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) {
+        for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+            let a = s[ALPHA];
+            for (i, (&s, d)) in s.iter().zip(d).enumerate() {
+                *d = if i == ALPHA { a } else { s.unpremultiply(a) };
+            }
+        }
+    }
+
+    pub fn u8_to_f32(src: &[u8], dst: &mut [f32]) {
+        for (&s, d) in src.iter().zip(dst) {
+            *d = s.convert_depth();
+        }
+    }
+
+    pub fn f32_to_u8(src: &[f32], dst: &mut [u8]) {
+        for (&s, d) in src.iter().zip(dst) {
+            *d = s.convert_depth();
+        }
+    }
+}
+
+/// Each kernel returns how many pixels (or components) it has converted, always a multiple of the vector width.
+///
+/// Premultiplication computes `(c * a + 127) / 255` in 16 bits, using `t / 255 == (t + 1 + (t >> 8)) >> 8`,
+/// which is exact for `t < 65535`.
+/// Unpremultiplication divides in `f32`, which is exact, because the dividend and divisor are integers
+/// below 2<sup>24</sup>, and the distance of the quotient to the next integer is larger than the rounding error.
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    #[inline]
+    fn alpha_byte_mask<const ALPHA: usize>() -> i32 {
+        (0xFFu32 << (8 * ALPHA)) as i32
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn expand3_ssse3(src: &[u8], alpha: u8, dst: &mut [u8]) -> usize {
+        let shuffle = _mm_setr_epi8(0, 1, 2, -1, 3, 4, 5, -1, 6, 7, 8, -1, 9, 10, 11, -1);
+        let alpha = _mm_set1_epi32(i32::from_le_bytes([0, 0, 0, alpha]));
+        let n = dst.len() / 4;
+        let mut i = 0;
+        // loads 16 bytes to use 12
+        while i + 4 <= n && 3 * i + 16 <= src.len() {
+            let v = _mm_loadu_si128(src.as_ptr().add(3 * i).cast());
+            _mm_storeu_si128(dst.as_mut_ptr().add(4 * i).cast(), _mm_or_si128(_mm_shuffle_epi8(v, shuffle), alpha));
+            i += 4;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn expand3_avx2(src: &[u8], alpha: u8, dst: &mut [u8]) -> usize {
+        let shuffle = _mm256_setr_epi8(
+            0, 1, 2, -1, 3, 4, 5, -1, 6, 7, 8, -1, 9, 10, 11, -1,
+            0, 1, 2, -1, 3, 4, 5, -1, 6, 7, 8, -1, 9, 10, 11, -1,
+        );
+        let alpha = _mm256_set1_epi32(i32::from_le_bytes([0, 0, 0, alpha]));
+        let n = dst.len() / 4;
+        let mut i = 0;
+        while i + 8 <= n && 3 * i + 28 <= src.len() {
+            let lo = _mm_loadu_si128(src.as_ptr().add(3 * i).cast());
+            let hi = _mm_loadu_si128(src.as_ptr().add(3 * i + 12).cast());
+            let v = _mm256_inserti128_si256(_mm256_castsi128_si256(lo), hi, 1);
+            _mm256_storeu_si256(dst.as_mut_ptr().add(4 * i).cast(), _mm256_or_si256(_mm256_shuffle_epi8(v, shuffle), alpha));
+            i += 8;
+        }
+        i
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn strip4_ssse3(src: &[u8], dst: &mut [u8]) -> usize {
+        let shuffle = _mm_setr_epi8(0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14, -1, -1, -1, -1);
+        let n = src.len() / 4;
+        let mut i = 0;
+        // stores 16 bytes, of which the last 4 are overwritten by the next iteration
+        while i + 4 <= n && 3 * i + 16 <= dst.len() {
+            let v = _mm_loadu_si128(src.as_ptr().add(4 * i).cast());
+            _mm_storeu_si128(dst.as_mut_ptr().add(3 * i).cast(), _mm_shuffle_epi8(v, shuffle));
+            i += 4;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn strip4_avx2(src: &[u8], dst: &mut [u8]) -> usize {
+        let shuffle = _mm256_setr_epi8(
+            0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14, -1, -1, -1, -1,
+            0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14, -1, -1, -1, -1,
+        );
+        let n = src.len() / 4;
+        let mut i = 0;
+        while i + 8 <= n && 3 * i + 28 <= dst.len() {
+            let v = _mm256_shuffle_epi8(_mm256_loadu_si256(src.as_ptr().add(4 * i).cast()), shuffle);
+            _mm_storeu_si128(dst.as_mut_ptr().add(3 * i).cast(), _mm256_castsi256_si128(v));
+            _mm_storeu_si128(dst.as_mut_ptr().add(3 * i + 12).cast(), _mm256_extracti128_si256(v, 1));
+            i += 8;
+        }
+        i
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn permute4_ssse3(pixels: &mut [u8], mask: &[u8; 16]) -> usize {
+        let mask = _mm_loadu_si128(mask.as_ptr().cast());
+        let n = pixels.len() / 4;
+        let mut i = 0;
+        while i + 4 <= n {
+            let p = pixels.as_mut_ptr().add(4 * i).cast();
+            _mm_storeu_si128(p, _mm_shuffle_epi8(_mm_loadu_si128(p), mask));
+            i += 4;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn permute4_avx2(pixels: &mut [u8], mask: &[u8; 16]) -> usize {
+        let mask = _mm256_broadcastsi128_si256(_mm_loadu_si128(mask.as_ptr().cast()));
+        let n = pixels.len() / 4;
+        let mut i = 0;
+        while i + 8 <= n {
+            let p = pixels.as_mut_ptr().add(4 * i).cast();
+            _mm256_storeu_si256(p, _mm256_shuffle_epi8(_mm256_loadu_si256(p), mask));
+            i += 8;
+        }
+        i
+    }
+
+    /// Copies the alpha of each pixel to all of its 16-bit lanes
+    #[inline(always)]
+    unsafe fn broadcast_alpha_sse2<const ALPHA: usize>(v: __m128i) -> __m128i {
+        if ALPHA == 0 {
+            _mm_shufflehi_epi16(_mm_shufflelo_epi16(v, 0x00), 0x00)
+        } else {
+            _mm_shufflehi_epi16(_mm_shufflelo_epi16(v, 0xFF), 0xFF)
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn mul_div255_sse2(c: __m128i, a: __m128i) -> __m128i {
+        let t = _mm_add_epi16(_mm_mullo_epi16(c, a), _mm_set1_epi16(127));
+        _mm_srli_epi16(_mm_add_epi16(_mm_add_epi16(t, _mm_set1_epi16(1)), _mm_srli_epi16(t, 8)), 8)
+    }
+
+    pub unsafe fn premultiply4_sse2<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) -> usize {
+        let zero = _mm_setzero_si128();
+        let keep = _mm_set1_epi32(alpha_byte_mask::<ALPHA>());
+        let n = src.len().min(dst.len()) / 4;
+        let mut i = 0;
+        while i + 4 <= n {
+            let v = _mm_loadu_si128(src.as_ptr().add(4 * i).cast());
+            let lo = _mm_unpacklo_epi8(v, zero);
+            let hi = _mm_unpackhi_epi8(v, zero);
+            let lo = mul_div255_sse2(lo, broadcast_alpha_sse2::<ALPHA>(lo));
+            let hi = mul_div255_sse2(hi, broadcast_alpha_sse2::<ALPHA>(hi));
+            let p = _mm_packus_epi16(lo, hi);
+            let p = _mm_or_si128(_mm_andnot_si128(keep, p), _mm_and_si128(keep, v));
+            _mm_storeu_si128(dst.as_mut_ptr().add(4 * i).cast(), p);
+            i += 4;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn premultiply4_avx2<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) -> usize {
+        let zero = _mm256_setzero_si256();
+        let keep = _mm256_set1_epi32(alpha_byte_mask::<ALPHA>());
+        let bias = _mm256_set1_epi16(127);
+        let one = _mm256_set1_epi16(1);
+        let n = src.len().min(dst.len()) / 4;
+        let mut i = 0;
+        while i + 8 <= n {
+            let v = _mm256_loadu_si256(src.as_ptr().add(4 * i).cast());
+            let mut halves = [_mm256_unpacklo_epi8(v, zero), _mm256_unpackhi_epi8(v, zero)];
+            for c in &mut halves {
+                let a = if ALPHA == 0 {
+                    _mm256_shufflehi_epi16(_mm256_shufflelo_epi16(*c, 0x00), 0x00)
+                } else {
+                    _mm256_shufflehi_epi16(_mm256_shufflelo_epi16(*c, 0xFF), 0xFF)
+                };
+                let t = _mm256_add_epi16(_mm256_mullo_epi16(*c, a), bias);
+                *c = _mm256_srli_epi16(_mm256_add_epi16(_mm256_add_epi16(t, one), _mm256_srli_epi16(t, 8)), 8);
+            }
+            let p = _mm256_packus_epi16(halves[0], halves[1]);
+            let p = _mm256_or_si256(_mm256_andnot_si256(keep, p), _mm256_and_si256(keep, v));
+            _mm256_storeu_si256(dst.as_mut_ptr().add(4 * i).cast(), p);
+            i += 8;
+        }
+        i
+    }
+
+    /// Unpremultiplies one pixel widened to 32-bit lanes
+    #[inline(always)]
+    unsafe fn unpremultiply_px_sse2<const ALPHA: usize>(p: __m128i) -> __m128i {
+        let (a, lane) = if ALPHA == 0 {
+            (_mm_shuffle_epi32(p, 0x00), _mm_setr_epi32(-1, 0, 0, 0))
+        } else {
+            (_mm_shuffle_epi32(p, 0xFF), _mm_setr_epi32(0, 0, 0, -1))
+        };
+        let x = _mm_add_ps(_mm_mul_ps(_mm_cvtepi32_ps(p), _mm_set1_ps(255.)), _mm_cvtepi32_ps(_mm_srli_epi32(a, 1)));
+        let q = _mm_cvttps_epi32(_mm_min_ps(_mm_div_ps(x, _mm_cvtepi32_ps(a)), _mm_set1_ps(255.)));
+        // division by zero gives garbage, but zero alpha makes colors zero
+        let q = _mm_andnot_si128(_mm_cmpeq_epi32(a, _mm_setzero_si128()), q);
+        _mm_or_si128(_mm_andnot_si128(lane, q), _mm_and_si128(lane, p))
+    }
+
+    pub unsafe fn unpremultiply4_sse2<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) -> usize {
+        let zero = _mm_setzero_si128();
+        let n = src.len().min(dst.len()) / 4;
+        let mut i = 0;
+        while i + 4 <= n {
+            let v = _mm_loadu_si128(src.as_ptr().add(4 * i).cast());
+            let lo = _mm_unpacklo_epi8(v, zero);
+            let hi = _mm_unpackhi_epi8(v, zero);
+            let p0 = unpremultiply_px_sse2::<ALPHA>(_mm_unpacklo_epi16(lo, zero));
+            let p1 = unpremultiply_px_sse2::<ALPHA>(_mm_unpackhi_epi16(lo, zero));
+            let p2 = unpremultiply_px_sse2::<ALPHA>(_mm_unpacklo_epi16(hi, zero));
+            let p3 = unpremultiply_px_sse2::<ALPHA>(_mm_unpackhi_epi16(hi, zero));
+            let p = _mm_packus_epi16(_mm_packs_epi32(p0, p1), _mm_packs_epi32(p2, p3));
+            _mm_storeu_si128(dst.as_mut_ptr().add(4 * i).cast(), p);
+            i += 4;
+        }
+        i
+    }
+
+    pub unsafe fn u8_to_f32_sse2(src: &[u8], dst: &mut [f32]) -> usize {
+        let zero = _mm_setzero_si128();
+        let max = _mm_set1_ps(255.);
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = _mm_loadu_si128(src.as_ptr().add(i).cast());
+            let lo = _mm_unpacklo_epi8(v, zero);
+            let hi = _mm_unpackhi_epi8(v, zero);
+            let quarters = [_mm_unpacklo_epi16(lo, zero), _mm_unpackhi_epi16(lo, zero), _mm_unpacklo_epi16(hi, zero), _mm_unpackhi_epi16(hi, zero)];
+            for (k, q) in quarters.into_iter().enumerate() {
+                _mm_storeu_ps(dst.as_mut_ptr().add(i + 4 * k), _mm_div_ps(_mm_cvtepi32_ps(q), max));
+            }
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn u8_to_f32_avx2(src: &[u8], dst: &mut [f32]) -> usize {
+        let max = _mm256_set1_ps(255.);
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 8 <= n {
+            let v = _mm256_cvtepu8_epi32(_mm_loadl_epi64(src.as_ptr().add(i).cast()));
+            _mm256_storeu_ps(dst.as_mut_ptr().add(i), _mm256_div_ps(_mm256_cvtepi32_ps(v), max));
+            i += 8;
+        }
+        i
+    }
+
+    /// `max` returns its second operand if either is NaN, so NaN becomes 0
+    #[inline(always)]
+    unsafe fn f32_to_i32_sse2(x: __m128) -> __m128i {
+        let x = _mm_min_ps(_mm_max_ps(x, _mm_setzero_ps()), _mm_set1_ps(1.));
+        _mm_cvttps_epi32(_mm_add_ps(_mm_mul_ps(x, _mm_set1_ps(255.)), _mm_set1_ps(0.5)))
+    }
+
+    pub unsafe fn f32_to_u8_sse2(src: &[f32], dst: &mut [u8]) -> usize {
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 16 <= n {
+            let [q0, q1, q2, q3] = [0, 4, 8, 12].map(|k| f32_to_i32_sse2(_mm_loadu_ps(src.as_ptr().add(i + k))));
+            let p = _mm_packus_epi16(_mm_packs_epi32(q0, q1), _mm_packs_epi32(q2, q3));
+            _mm_storeu_si128(dst.as_mut_ptr().add(i).cast(), p);
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn f32_to_u8_avx2(src: &[f32], dst: &mut [u8]) -> usize {
+        let zero = _mm256_setzero_ps();
+        let one = _mm256_set1_ps(1.);
+        let max = _mm256_set1_ps(255.);
+        let half = _mm256_set1_ps(0.5);
+        // the packs interleave the 128-bit halves
+        let order = _mm256_setr_epi32(0, 4, 1, 5, 2, 6, 3, 7);
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 32 <= n {
+            let mut q = [_mm256_setzero_si256(); 4];
+            for (k, q) in q.iter_mut().enumerate() {
+                let x = _mm256_loadu_ps(src.as_ptr().add(i + 8 * k));
+                let x = _mm256_min_ps(_mm256_max_ps(x, zero), one);
+                *q = _mm256_cvttps_epi32(_mm256_add_ps(_mm256_mul_ps(x, max), half));
+            }
+            let p = _mm256_packus_epi16(_mm256_packs_epi32(q[0], q[1]), _mm256_packs_epi32(q[2], q[3]));
+            _mm256_storeu_si256(dst.as_mut_ptr().add(i).cast(), _mm256_permutevar8x32_epi32(p, order));
+            i += 32;
+        }
+        i
+    }
+}
+
+/// See the `x86` module for the arithmetic.
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use core::arch::aarch64::*;
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn expand3(src: &[u8], alpha: u8, dst: &mut [u8]) -> usize {
+        let alpha = vdupq_n_u8(alpha);
+        let n = (src.len() / 3).min(dst.len() / 4);
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = vld3q_u8(src.as_ptr().add(3 * i));
+            vst4q_u8(dst.as_mut_ptr().add(4 * i), uint8x16x4_t(v.0, v.1, v.2, alpha));
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn strip4(src: &[u8], dst: &mut [u8]) -> usize {
+        let n = (src.len() / 4).min(dst.len() / 3);
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = vld4q_u8(src.as_ptr().add(4 * i));
+            vst3q_u8(dst.as_mut_ptr().add(3 * i), uint8x16x3_t(v.0, v.1, v.2));
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn permute4(pixels: &mut [u8], mask: &[u8; 16]) -> usize {
+        let mask = vld1q_u8(mask.as_ptr());
+        let n = pixels.len() / 4;
+        let mut i = 0;
+        while i + 4 <= n {
+            let p = pixels.as_mut_ptr().add(4 * i);
+            vst1q_u8(p, vqtbl1q_u8(vld1q_u8(p), mask));
+            i += 4;
+        }
+        i
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn mul_div255(c: uint8x16_t, a: uint8x16_t) -> uint8x16_t {
+        let bias = vdupq_n_u16(127);
+        let one = vdupq_n_u16(1);
+        let lo = vaddq_u16(vmull_u8(vget_low_u8(c), vget_low_u8(a)), bias);
+        let hi = vaddq_u16(vmull_high_u8(c, a), bias);
+        let lo = vshrn_n_u16::<8>(vaddq_u16(vaddq_u16(lo, one), vshrq_n_u16::<8>(lo)));
+        let hi = vshrn_n_u16::<8>(vaddq_u16(vaddq_u16(hi, one), vshrq_n_u16::<8>(hi)));
+        vcombine_u8(lo, hi)
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn premultiply4<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) -> usize {
+        let n = src.len().min(dst.len()) / 4;
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = vld4q_u8(src.as_ptr().add(4 * i));
+            let mut c = [v.0, v.1, v.2, v.3];
+            let a = c[ALPHA];
+            for (k, c) in c.iter_mut().enumerate() {
+                if k != ALPHA {
+                    *c = mul_div255(*c, a);
+                }
+            }
+            vst4q_u8(dst.as_mut_ptr().add(4 * i), uint8x16x4_t(c[0], c[1], c[2], c[3]));
+            i += 16;
+        }
+        i
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn unpremultiply_u32(c: uint32x4_t, a: uint32x4_t) -> uint32x4_t {
+        let x = vaddq_f32(vmulq_f32(vcvtq_f32_u32(c), vdupq_n_f32(255.)), vcvtq_f32_u32(vshrq_n_u32::<1>(a)));
+        let q = vminq_f32(vdivq_f32(x, vcvtq_f32_u32(a)), vdupq_n_f32(255.));
+        // division by zero gives garbage, but zero alpha makes colors zero
+        vbicq_u32(vcvtq_u32_f32(q), vceqzq_u32(a))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn unpremultiply_u8(c: uint8x16_t, a: uint8x16_t) -> uint8x16_t {
+        let (c_lo, c_hi) = (vmovl_u8(vget_low_u8(c)), vmovl_high_u8(c));
+        let (a_lo, a_hi) = (vmovl_u8(vget_low_u8(a)), vmovl_high_u8(a));
+        let q0 = unpremultiply_u32(vmovl_u16(vget_low_u16(c_lo)), vmovl_u16(vget_low_u16(a_lo)));
+        let q1 = unpremultiply_u32(vmovl_high_u16(c_lo), vmovl_high_u16(a_lo));
+        let q2 = unpremultiply_u32(vmovl_u16(vget_low_u16(c_hi)), vmovl_u16(vget_low_u16(a_hi)));
+        let q3 = unpremultiply_u32(vmovl_high_u16(c_hi), vmovl_high_u16(a_hi));
+        let lo = vcombine_u16(vmovn_u32(q0), vmovn_u32(q1));
+        let hi = vcombine_u16(vmovn_u32(q2), vmovn_u32(q3));
+        vcombine_u8(vmovn_u16(lo), vmovn_u16(hi))
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn unpremultiply4<const ALPHA: usize>(src: &[u8], dst: &mut [u8]) -> usize {
+        let n = src.len().min(dst.len()) / 4;
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = vld4q_u8(src.as_ptr().add(4 * i));
+            let mut c = [v.0, v.1, v.2, v.3];
+            let a = c[ALPHA];
+            for (k, c) in c.iter_mut().enumerate() {
+                if k != ALPHA {
+                    *c = unpremultiply_u8(*c, a);
+                }
+            }
+            vst4q_u8(dst.as_mut_ptr().add(4 * i), uint8x16x4_t(c[0], c[1], c[2], c[3]));
+            i += 16;
+        }
+        i
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn u8_to_f32(src: &[u8], dst: &mut [f32]) -> usize {
+        let max = vdupq_n_f32(255.);
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = vld1q_u8(src.as_ptr().add(i));
+            let (lo, hi) = (vmovl_u8(vget_low_u8(v)), vmovl_high_u8(v));
+            let quarters = [vmovl_u16(vget_low_u16(lo)), vmovl_high_u16(lo), vmovl_u16(vget_low_u16(hi)), vmovl_high_u16(hi)];
+            for (k, q) in quarters.into_iter().enumerate() {
+                vst1q_f32(dst.as_mut_ptr().add(i + 4 * k), vdivq_f32(vcvtq_f32_u32(q), max));
+            }
+            i += 16;
+        }
+        i
+    }
+
+    /// `maxnm` returns the number if the other operand is NaN, so NaN becomes 0
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn f32_to_u32(x: float32x4_t) -> uint32x4_t {
+        let x = vminq_f32(vmaxnmq_f32(x, vdupq_n_f32(0.)), vdupq_n_f32(1.));
+        vcvtq_u32_f32(vaddq_f32(vmulq_f32(x, vdupq_n_f32(255.)), vdupq_n_f32(0.5)))
+    }
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn f32_to_u8(src: &[f32], dst: &mut [u8]) -> usize {
+        let n = src.len().min(dst.len());
+        let mut i = 0;
+        while i + 16 <= n {
+            let [q0, q1, q2, q3] = [0, 4, 8, 12].map(|k| f32_to_u32(vld1q_f32(src.as_ptr().add(i + k))));
+            let lo = vcombine_u16(vmovn_u32(q0), vmovn_u32(q1));
+            let hi = vcombine_u16(vmovn_u32(q2), vmovn_u32(q3));
+            vst1q_u8(dst.as_mut_ptr().add(i), vcombine_u8(vmovn_u16(lo), vmovn_u16(hi)));
+            i += 16;
+        }
+        i
+    }
+}
+
+// SAFETY for all the dispatch functions below: the kernels are only called with a `level`
+// that the CPU supports, and they only access memory within the given slices.
+
+fn expand3(level: Level, src: &[u8], alpha: u8, dst: &mut [u8]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::expand3_avx2(src, alpha, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Ssse3 => unsafe { x86::expand3_ssse3(src, alpha, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::expand3(src, alpha, dst) },
+        _ => 0,
+    };
+    scalar::expand3(&src[3 * done..], alpha, &mut dst[4 * done..]);
+}
+
+fn strip4(level: Level, src: &[u8], dst: &mut [u8]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::strip4_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Ssse3 => unsafe { x86::strip4_ssse3(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::strip4(src, dst) },
+        _ => 0,
+    };
+    scalar::strip4(&src[4 * done..], &mut dst[3 * done..]);
+}
+
+fn permute4(level: Level, pixels: &mut [u8], perm: [u8; 4]) {
+    let mut mask = [0; 16];
+    for (i, m) in mask.iter_mut().enumerate() {
+        *m = (i & !3) as u8 + perm[i & 3];
+    }
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::permute4_avx2(pixels, &mask) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Ssse3 => unsafe { x86::permute4_ssse3(pixels, &mask) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::permute4(pixels, &mask) },
+        _ => 0,
+    };
+    scalar::permute4(&mut pixels[4 * done..], perm);
+}
+
+fn premultiply4<const ALPHA: usize>(level: Level, src: &[u8], dst: &mut [u8]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::premultiply4_avx2::<ALPHA>(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Sse2 | Level::Ssse3 => unsafe { x86::premultiply4_sse2::<ALPHA>(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::premultiply4::<ALPHA>(src, dst) },
+        _ => 0,
+    };
+    scalar::premultiply4::<ALPHA>(&src[4 * done..], &mut dst[4 * done..]);
+}
+
+fn unpremultiply4<const ALPHA: usize>(level: Level, src: &[u8], dst: &mut [u8]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Sse2 | Level::Ssse3 | Level::Avx2 => unsafe { x86::unpremultiply4_sse2::<ALPHA>(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::unpremultiply4::<ALPHA>(src, dst) },
+        _ => 0,
+    };
+    scalar::unpremultiply4::<ALPHA>(&src[4 * done..], &mut dst[4 * done..]);
+}
+
+fn u8_to_f32_with(level: Level, src: &[u8], dst: &mut [f32]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::u8_to_f32_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Sse2 | Level::Ssse3 => unsafe { x86::u8_to_f32_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::u8_to_f32(src, dst) },
+        _ => 0,
+    };
+    scalar::u8_to_f32(&src[done..], &mut dst[done..]);
+}
+
+fn f32_to_u8_with(level: Level, src: &[f32], dst: &mut [u8]) {
+    let done = match level {
+        #[cfg(target_arch = "x86_64")]
+        Level::Avx2 => unsafe { x86::f32_to_u8_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Level::Sse2 | Level::Ssse3 => unsafe { x86::f32_to_u8_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Level::Neon => unsafe { neon::f32_to_u8(src, dst) },
+        _ => 0,
+    };
+    scalar::f32_to_u8(&src[done..], &mut dst[done..]);
+}
+
+/// Appends `alpha` to every 3 bytes of `src`
+#[inline]
+pub(crate) fn expand_bytes(src: &[u8], alpha: u8, dst: &mut [u8]) {
+    expand3(detect(), src, alpha, dst);
+}
+
+/// Drops every fourth byte of `src`
+#[inline]
+pub(crate) fn strip_bytes(src: &[u8], dst: &mut [u8]) {
+    strip4(detect(), src, dst);
+}
+
+/// Reorders the components of pixels of 4 bytes each, so that the component `i` becomes the old component `perm[i]`.
+#[inline]
+pub(crate) fn permute_in_place(pixels: &mut [u8], perm: [u8; 4]) {
+    permute4(detect(), pixels, perm);
+}
+
+/// Copies RGB pixels to RGBA, setting alpha of all of them to `alpha`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn rgb_to_rgba(src: &[Rgb<u8>], alpha: u8, dst: &mut [Rgba<u8>]) {
+    assert_eq!(src.len(), dst.len());
+    // SAFETY: both are repr(C) structs of u8
+    expand3(detect(), unsafe { as_bytes(src) }, alpha, unsafe { as_bytes_mut(dst) });
+}
+
+/// Copies RGBA pixels to RGB, dropping alpha. The colors aren't changed, so they should not be premultiplied.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn rgba_to_rgb(src: &[Rgba<u8>], dst: &mut [Rgb<u8>]) {
+    assert_eq!(src.len(), dst.len());
+    // SAFETY: both are repr(C) structs of u8
+    strip4(detect(), unsafe { as_bytes(src) }, unsafe { as_bytes_mut(dst) });
+}
+
+/// A straight-alpha pixel with `u8` components, supported by [`premultiply()`] and [`unpremultiply()`].
+///
+/// This trait is sealed, and implemented for [`Rgba<u8>`], [`Bgra<u8>`], [`Argb<u8>`], and [`Abgr<u8>`].
+pub trait StraightAlpha8: Copy + sealed::Sealed {
+    /// The same pixel type with premultiplied alpha, e.g. [`PremulRgba<u8>`] for [`Rgba<u8>`]
+    type Premul: Copy;
+}
+
+mod sealed {
+    pub trait Sealed {
+        /// Index of the alpha byte
+        const ALPHA: usize;
+    }
+}
+
+macro_rules! straight_alpha {
+    ($straight:ident, $premul:ident, $alpha:literal) => {
+        impl sealed::Sealed for $straight<u8> {
+            const ALPHA: usize = $alpha;
+        }
+
+        impl StraightAlpha8 for $straight<u8> {
+            type Premul = $premul<u8>;
+        }
+    };
+}
+
+straight_alpha!(Rgba, PremulRgba, 3);
+straight_alpha!(Bgra, PremulBgra, 3);
+straight_alpha!(Argb, PremulArgb, 0);
+straight_alpha!(Abgr, PremulAbgr, 0);
+
+fn premultiply_with<P: StraightAlpha8>(level: Level, src: &[P], dst: &mut [P::Premul]) {
+    assert_eq!(src.len(), dst.len());
+    // SAFETY: the sealed pixel types are repr(C) structs of u8
+    let (src, dst) = unsafe { (as_bytes(src), as_bytes_mut(dst)) };
+    if P::ALPHA == 0 {
+        premultiply4::<0>(level, src, dst);
+    } else {
+        premultiply4::<3>(level, src, dst);
+    }
+}
+
+fn unpremultiply_with<P: StraightAlpha8>(level: Level, src: &[P::Premul], dst: &mut [P]) {
+    assert_eq!(src.len(), dst.len());
+    // SAFETY: the sealed pixel types are repr(C) structs of u8
+    let (src, dst) = unsafe { (as_bytes(src), as_bytes_mut(dst)) };
+    if P::ALPHA == 0 {
+        unpremultiply4::<0>(level, src, dst);
+    } else {
+        unpremultiply4::<3>(level, src, dst);
+    }
+}
+
+/// Premultiplies the colors of all pixels by their alpha, like [`From`] for each pixel (see [`PremultiplyComponent`](crate::premul::PremultiplyComponent)).
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn premultiply<P: StraightAlpha8>(src: &[P], dst: &mut [P::Premul]) {
+    premultiply_with(detect(), src, dst);
+}
+
+/// Divides the colors of all premultiplied pixels by their alpha, like [`From`] for each pixel (see [`PremultiplyComponent`](crate::premul::PremultiplyComponent)).
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{Bgra, PremulBgra};
+///
+/// let src = [PremulBgra::new_bgra(64u8, 0, 128, 128), PremulBgra::new_bgra(1, 2, 3, 0)];
+/// let mut dst = [Bgra::default(); 2];
+/// rgb::simd::unpremultiply(&src, &mut dst);
+/// assert_eq!(dst, [Bgra::new_bgra(128, 0, 255, 128), Bgra::new_bgra(0, 0, 0, 0)]);
+/// ```
+pub fn unpremultiply<P: StraightAlpha8>(src: &[P::Premul], dst: &mut [P]) {
+    unpremultiply_with(detect(), src, dst);
+}
+
+/// Scales components from `0..=255` to `0.0..=1.0`, like [`ConvertDepth`](crate::depth::ConvertDepth).
+///
+/// Slices of pixels can be viewed as components with [`ComponentSlice`](crate::ComponentSlice).
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{ComponentSlice, Rgba};
+///
+/// let src = [Rgba::new(255u8, 0, 51, 255); 10];
+/// let mut dst = [Rgba::new(0f32, 0., 0., 0.); 10];
+/// rgb::simd::u8_to_f32(src[..].as_slice(), dst[..].as_mut_slice());
+/// assert_eq!(dst[9], Rgba::new(1., 0., 0.2, 1.));
+/// ```
+pub fn u8_to_f32(src: &[u8], dst: &mut [f32]) {
+    assert_eq!(src.len(), dst.len());
+    u8_to_f32_with(detect(), src, dst);
+}
+
+/// Scales components from `0.0..=1.0` to `0..=255`, like [`ConvertDepth`](crate::depth::ConvertDepth).
+///
+/// Values are clamped and rounded, and NaN becomes 0.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn f32_to_u8(src: &[f32], dst: &mut [u8]) {
+    assert_eq!(src.len(), dst.len());
+    f32_to_u8_with(detect(), src, dst);
+}
+
+#[cfg(test)]
+fn levels() -> std::vec::Vec<Level> {
+    let mut levels = vec![Level::Scalar];
+    #[cfg(target_arch = "x86_64")]
+    {
+        levels.push(Level::Sse2);
+        if has_feature!(x86, "ssse3") {
+            levels.push(Level::Ssse3);
+        }
+        if has_feature!(x86, "avx2") {
+            levels.push(Level::Avx2);
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    if has_feature!(aarch64, "neon") {
+        levels.push(Level::Neon);
+    }
+    assert_eq!(*levels.last().unwrap(), detect());
+    levels
+}
+
+#[cfg(test)]
+fn random_bytes(len: usize, mut seed: u32) -> std::vec::Vec<u8> {
+    (0..len).map(|_| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed >> 24) as u8
+    }).collect()
+}
+
+#[test]
+fn simd_bytes_identical() {
+    for level in levels() {
+        for len in 0..100 {
+            let src3 = random_bytes(3 * len, 1 + len as u32);
+            let src4 = random_bytes(4 * len, 2 + len as u32);
+
+            let mut expected = vec![0; 4 * len];
+            let mut actual = vec![1; 4 * len];
+            scalar::expand3(&src3, 200, &mut expected);
+            expand3(level, &src3, 200, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+
+            let mut expected = vec![0; 3 * len];
+            let mut actual = vec![1; 3 * len];
+            scalar::strip4(&src4, &mut expected);
+            strip4(level, &src4, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+
+            for perm in [[0, 1, 2, 3], [3, 2, 1, 0], [2, 1, 0, 3], [1, 2, 3, 0], [3, 0, 1, 2]] {
+                let mut expected = src4.clone();
+                let mut actual = src4.clone();
+                scalar::permute4(&mut expected, perm);
+                permute4(level, &mut actual, perm);
+                assert_eq!(expected, actual, "{level:?}");
+            }
+
+            let mut expected = vec![0; len];
+            let mut actual = vec![1; len];
+            scalar::f32_to_u8(&[0.5; 100][..len], &mut expected);
+            f32_to_u8_with(level, &[0.5; 100][..len], &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+        }
+    }
+}
+
+#[test]
+fn simd_premultiply_identical() {
+    // every combination of color and alpha, in both alpha positions
+    let src: std::vec::Vec<u8> = (0..=255u8).flat_map(|a| (0..=255u8).flat_map(move |c| [c, a, 255 - c, a])).collect();
+    let swapped: std::vec::Vec<u8> = src.chunks_exact(4).flat_map(|p| [p[3], p[2], p[0], p[1]]).collect();
+    for level in levels() {
+        for src in [&src[..], &src[4..], &src[..4 * 7], &swapped[..], &swapped[4 * 3..]] {
+            let mut expected = vec![0; src.len()];
+            let mut actual = vec![1; src.len()];
+            scalar::premultiply4::<3>(src, &mut expected);
+            premultiply4::<3>(level, src, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+            scalar::premultiply4::<0>(src, &mut expected);
+            premultiply4::<0>(level, src, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+            scalar::unpremultiply4::<3>(src, &mut expected);
+            unpremultiply4::<3>(level, src, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+            scalar::unpremultiply4::<0>(src, &mut expected);
+            unpremultiply4::<0>(level, src, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+        }
+
+        let px = [Argb::new_argb(128u8, 255, 2, 0); 33];
+        let mut premul = [PremulArgb::new_argb(0, 0, 0, 0); 33];
+        premultiply_with(level, &px, &mut premul);
+        assert_eq!(premul[32], px[32].into());
+        let mut back = [Argb::new_argb(0, 0, 0, 0); 33];
+        unpremultiply_with(level, &premul, &mut back);
+        assert_eq!(back[0], Argb::new_argb(128, 255, 2, 0));
+    }
+}
+
+#[test]
+fn simd_float_identical() {
+    let all: std::vec::Vec<u8> = (0..=255).chain(0..=255).collect();
+    let mut floats = vec![f32::NAN, -f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -0., 0., 1., 1.0001, -1e-9, f32::MIN_POSITIVE, f32::MAX];
+    floats.extend((0..=2550).map(|i| i as f32 / 2550.));
+    floats.extend((0..255).map(|i| (i as f32 + 0.5) / 255.));
+    floats.extend(random_bytes(4 * 999, 7).chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3] & 0x3F])));
+    for level in levels() {
+        for len in [0, 1, 15, 16, 17, 100, all.len()] {
+            let mut expected = vec![0.; len];
+            let mut actual = vec![1.; len];
+            scalar::u8_to_f32(&all[..len], &mut expected);
+            u8_to_f32_with(level, &all[..len], &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+        }
+        for start in [0, 1, 5, 11] {
+            let src = &floats[start..];
+            let mut expected = vec![0; src.len()];
+            let mut actual = vec![1; src.len()];
+            scalar::f32_to_u8(src, &mut expected);
+            f32_to_u8_with(level, src, &mut actual);
+            assert_eq!(expected, actual, "{level:?}");
+        }
+    }
+}
+
+#[test]
+fn simd_public() {
+    let rgb: std::vec::Vec<_> = (0..50u8).map(|i| Rgb::new(i, i + 1, i + 2)).collect();
+    let mut rgba = vec![Rgba::new(0, 0, 0, 0); 50];
+    rgb_to_rgba(&rgb, 7, &mut rgba);
+    assert_eq!(rgba[49], Rgba::new(49, 50, 51, 7));
+    let mut back = vec![Rgb::new(0, 0, 0); 50];
+    rgba_to_rgb(&rgba, &mut back);
+    assert_eq!(back, rgb);
+
+    let mut floats = vec![0.; 200];
+    u8_to_f32(crate::ComponentSlice::as_slice(&rgba[..]), &mut floats);
+    assert_eq!(floats[199], 7. / 255.);
+    let mut bytes = vec![0; 200];
+    f32_to_u8(&floats, &mut bytes);
+    assert_eq!(bytes, crate::ComponentSlice::as_slice(&rgba[..]));
+}
@@ -0,0 +1,325 @@
+//! Conversions between gamma-encoded sRGB and linear-light components.
+//!
+//! Pixels in files and on screen are usually stored with the sRGB transfer function
+//! ("gamma") applied. Blending, resizing and other math on colors needs to be done
+//! on linear-light values instead, otherwise the results come out too dark.
+//!
+//! Color components are converted with the sRGB curve. Alpha is never gamma-corrected, it's
+//! only scaled to the `0.0..=1.0` range.
+//!
+//! ```
+//! use rgb::{Rgba, RGBA8, RGBA32F};
+//! use rgb::srgb::SrgbPixel;
+//!
+//! let px = RGBA8::new(255, 188, 0, 128);
+//! let linear: RGBA32F = px.to_linear();
+//! assert_eq!(linear.r, 1.);
+//! assert!((linear.g - 0.5).abs() < 0.01);
+//! assert_eq!(RGBA8::from_linear(linear), px);
+//! ```
+
+use crate::formats::gray::Gray_v09;
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Grb, HetPixel, Rgb, Rgba, Rgbw};
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Decodes an sRGB gamma-encoded value in `0.0..=1.0` range to linear light.
+///
+/// ```
+/// assert_eq!(rgb::srgb::srgb_to_linear(1.), 1.);
+/// assert!((rgb::srgb::srgb_to_linear(0.5) - 0.214).abs() < 0.001);
+/// ```
+#[inline]
+#[must_use]
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        crate::math::powf((c + 0.055) / 1.055, 2.4)
+    }
+}
+
+/// Encodes a linear-light value in `0.0..=1.0` range with the sRGB transfer function.
+///
+/// ```
+/// assert_eq!(rgb::srgb::linear_to_srgb(0.), 0.);
+/// assert!((rgb::srgb::linear_to_srgb(0.214) - 0.5).abs() < 0.001);
+/// ```
+#[inline]
+#[must_use]
+pub fn linear_to_srgb(l: f32) -> f32 {
+    if l <= 0.003_130_8 {
+        l * 12.92
+    } else {
+        1.055 * crate::math::powf(l, 1. / 2.4) - 0.055
+    }
+}
+
+/// An integer component type that can be stored gamma-encoded with the sRGB curve.
+///
+/// Implemented for `u8` and `u16`.
+pub trait SrgbComponent: Copy + 'static {
+    /// Decodes a gamma-encoded color component to linear light in `0.0..=1.0` range.
+    fn to_linear(self) -> f32;
+
+    /// Encodes a linear-light color component. Out-of-range values are clamped, and the result is rounded.
+    fn from_linear(linear: f32) -> Self;
+
+    /// Scales an alpha component to `0.0..=1.0` range, without applying any curve.
+    fn alpha_to_f32(self) -> f32;
+
+    /// Scales an alpha component from `0.0..=1.0` range, without applying any curve. Out-of-range values are clamped.
+    fn alpha_from_f32(alpha: f32) -> Self;
+}
+
+impl SrgbComponent for u8 {
+    #[inline]
+    fn to_linear(self) -> f32 {
+        SRGB_U8_TO_LINEAR[self as usize]
+    }
+
+    #[inline]
+    fn from_linear(linear: f32) -> Self {
+        (linear_to_srgb(linear.clamp(0., 1.)) * 255. + 0.5) as u8
+    }
+
+    #[inline]
+    fn alpha_to_f32(self) -> f32 {
+        f32::from(self) * (1. / 255.)
+    }
+
+    #[inline]
+    fn alpha_from_f32(alpha: f32) -> Self {
+        (alpha.clamp(0., 1.) * 255. + 0.5) as u8
+    }
+}
+
+impl SrgbComponent for u16 {
+    #[inline]
+    fn to_linear(self) -> f32 {
+        srgb_to_linear(f32::from(self) * (1. / 65535.))
+    }
+
+    #[inline]
+    fn from_linear(linear: f32) -> Self {
+        (linear_to_srgb(linear.clamp(0., 1.)) * 65535. + 0.5) as u16
+    }
+
+    #[inline]
+    fn alpha_to_f32(self) -> f32 {
+        f32::from(self) * (1. / 65535.)
+    }
+
+    #[inline]
+    fn alpha_from_f32(alpha: f32) -> Self {
+        (alpha.clamp(0., 1.) * 65535. + 0.5) as u16
+    }
+}
+
+/// A pixel that can be converted between gamma-encoded sRGB and linear light.
+///
+/// Implemented for pixels with `u8` or `u16` components. The linear pixel type is
+/// the same kind of pixel with `f32` components, e.g. [`Rgba<u8>`] ↔ [`Rgba<f32>`].
+pub trait SrgbPixel: HetPixel {
+    /// The same pixel type with linear-light `f32` components.
+    type Linear: HetPixel<ColorComponent = f32, AlphaComponent = f32>;
+
+    /// Decodes color components to linear light. The alpha component is only scaled to `0.0..=1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::{Rgb, GrayA};
+    /// use rgb::srgb::SrgbPixel;
+    ///
+    /// assert_eq!(Rgb::<u8>::new(255, 0, 255).to_linear(), Rgb::new(1., 0., 1.));
+    /// assert_eq!(GrayA::<u16>::new(0, 65535).to_linear(), GrayA::new(0., 1.));
+    /// ```
+    fn to_linear(&self) -> Self::Linear;
+
+    /// Encodes linear-light color components with the sRGB curve, rounding to the nearest integer.
+    ///
+    /// Values outside of `0.0..=1.0` range are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgb::Rgba;
+    /// use rgb::srgb::SrgbPixel;
+    ///
+    /// let px = Rgba::<u8>::from_linear(Rgba::new(0.2159, 1.5, -1., 0.5));
+    /// assert_eq!(px, Rgba::new(128, 255, 0, 128));
+    /// ```
+    fn from_linear(linear: Self::Linear) -> Self;
+}
+
+macro_rules! srgb_without_alpha {
+    ($name:ident) => {
+        impl<T: SrgbComponent> SrgbPixel for $name<T> {
+            type Linear = $name<f32>;
+
+            #[inline]
+            fn to_linear(&self) -> Self::Linear {
+                self.map_colors(T::to_linear)
+            }
+
+            #[inline]
+            fn from_linear(linear: Self::Linear) -> Self {
+                linear.map_colors(T::from_linear)
+            }
+        }
+    };
+}
+
+macro_rules! srgb_with_alpha {
+    ($name:ident) => {
+        impl<T: SrgbComponent> SrgbPixel for $name<T> {
+            type Linear = $name<f32>;
+
+            #[inline]
+            fn to_linear(&self) -> Self::Linear {
+                self.map_colors(T::to_linear).map_alpha(T::alpha_to_f32)
+            }
+
+            #[inline]
+            fn from_linear(linear: Self::Linear) -> Self {
+                linear.map_colors(T::from_linear).map_alpha(T::alpha_from_f32)
+            }
+        }
+    };
+}
+
+srgb_without_alpha!(Rgb);
+srgb_without_alpha!(Bgr);
+srgb_without_alpha!(Grb);
+srgb_without_alpha!(Rgbw);
+srgb_without_alpha!(Gray_v09);
+
+srgb_with_alpha!(Rgba);
+srgb_with_alpha!(Argb);
+srgb_with_alpha!(Bgra);
+srgb_with_alpha!(Abgr);
+srgb_with_alpha!(GrayA);
+
+/// Decodes all pixels of `src` to linear light, writing them to `dst`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{RGB8, Rgb};
+///
+/// let src = [RGB8::new(0, 128, 255)];
+/// let mut dst = [Rgb::new(0., 0., 0.)];
+/// rgb::srgb::to_linear_slice(&src, &mut dst);
+/// assert_eq!(dst[0].b, 1.);
+/// ```
+pub fn to_linear_slice<P: SrgbPixel>(src: &[P], dst: &mut [P::Linear]) {
+    assert_eq!(src.len(), dst.len());
+    for (src, dst) in src.iter().zip(dst) {
+        *dst = src.to_linear();
+    }
+}
+
+/// Encodes all linear-light pixels of `src` with the sRGB curve, writing them to `dst`.
+///
+/// # Panics
+///
+/// If the slices have different lengths.
+pub fn from_linear_slice<P: SrgbPixel>(src: &[P::Linear], dst: &mut [P]) {
+    assert_eq!(src.len(), dst.len());
+    for (src, dst) in src.iter().zip(dst) {
+        *dst = P::from_linear(*src);
+    }
+}
+
+/// Decodes all pixels to a new `Vec` of linear-light pixels, e.g. `&[RGBA8]` to `Vec<RGBA32F>`.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{RGBA8, RGBA32F};
+///
+/// let pixels = vec![RGBA8::new(255, 255, 255, 0); 10];
+/// let linear: Vec<RGBA32F> = rgb::srgb::to_linear_vec(&pixels);
+/// assert_eq!(linear[0], RGBA32F::new(1., 1., 1., 0.));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn to_linear_vec<P: SrgbPixel>(src: &[P]) -> Vec<P::Linear> {
+    src.iter().map(SrgbPixel::to_linear).collect()
+}
+
+/// Encodes all linear-light pixels to a new `Vec` of gamma-encoded pixels, e.g. `&[RGBA32F]` to `Vec<RGBA8>`.
+///
+/// The target pixel type usually needs to be specified explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use rgb::{RGBA8, RGBA32F};
+///
+/// let linear = vec![RGBA32F::new(1., 0., 0., 1.); 10];
+/// let pixels: Vec<RGBA8> = rgb::srgb::from_linear_vec(&linear);
+/// assert_eq!(pixels[0], RGBA8::new(255, 0, 0, 255));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn from_linear_vec<P: SrgbPixel>(src: &[P::Linear]) -> Vec<P> {
+    src.iter().map(|&px| P::from_linear(px)).collect()
+}
+
+/// Exact sRGB decoding of all 8-bit values
+#[rustfmt::skip]
+static SRGB_U8_TO_LINEAR: [f32; 256] = [
+    0., 0.000303527, 0.000607054, 0.000910581, 0.001214108, 0.001517635, 0.001821162, 0.0021246888,
+    0.002428216, 0.0027317428, 0.00303527, 0.0033465358, 0.0036765074, 0.004024717, 0.004391442, 0.0047769533,
+    0.0051815165, 0.0056053917, 0.006048833, 0.0065120906, 0.00699541, 0.007499032, 0.008023193, 0.008568126,
+    0.009134059, 0.009721218, 0.010329823, 0.010960094, 0.011612245, 0.012286488, 0.0129830325, 0.013702083,
+    0.014443844, 0.015208514, 0.015996294, 0.016807375, 0.017641954, 0.01850022, 0.019382361, 0.020288562,
+    0.02121901, 0.022173885, 0.023153367, 0.024157632, 0.02518686, 0.026241222, 0.027320892, 0.02842604,
+    0.029556835, 0.030713445, 0.031896032, 0.033104766, 0.034339808, 0.035601314, 0.03688945, 0.038204372,
+    0.039546236, 0.0409152, 0.04231141, 0.04373503, 0.045186203, 0.046665087, 0.048171826, 0.049706567,
+    0.051269457, 0.052860647, 0.054480277, 0.05612849, 0.05780543, 0.059511237, 0.061246052, 0.063010015,
+    0.064803265, 0.06662594, 0.06847817, 0.070360094, 0.07227185, 0.07421357, 0.07618538, 0.07818742,
+    0.08021982, 0.08228271, 0.08437621, 0.08650046, 0.08865558, 0.09084171, 0.093058966, 0.09530747,
+    0.09758735, 0.099898726, 0.10224173, 0.104616486, 0.107023105, 0.10946171, 0.11193243, 0.114435375,
+    0.116970666, 0.11953843, 0.122138776, 0.12477182, 0.12743768, 0.13013647, 0.13286832, 0.13563333,
+    0.13843161, 0.14126329, 0.14412847, 0.14702727, 0.14995979, 0.15292615, 0.15592647, 0.15896083,
+    0.16202937, 0.1651322, 0.1682694, 0.17144111, 0.1746474, 0.17788842, 0.18116425, 0.18447499,
+    0.18782078, 0.19120169, 0.19461784, 0.19806932, 0.20155625, 0.20507874, 0.20863687, 0.21223076,
+    0.2158605, 0.2195262, 0.22322796, 0.22696587, 0.23074006, 0.23455058, 0.23839757, 0.24228112,
+    0.24620132, 0.25015828, 0.2541521, 0.25818285, 0.26225066, 0.2663556, 0.2704978, 0.2746773,
+    0.27889428, 0.28314874, 0.28744084, 0.29177064, 0.29613826, 0.30054379, 0.3049873, 0.30946892,
+    0.31398872, 0.31854677, 0.3231432, 0.3277781, 0.33245152, 0.33716363, 0.34191442, 0.34670407,
+    0.3515326, 0.35640013, 0.3613068, 0.3662526, 0.3712377, 0.37626213, 0.38132602, 0.38642943,
+    0.39157248, 0.39675522, 0.40197778, 0.4072402, 0.4125426, 0.41788507, 0.42326766, 0.4286905,
+    0.43415365, 0.43965718, 0.4452012, 0.4507858, 0.45641103, 0.462077, 0.4677838, 0.47353148,
+    0.47932017, 0.48514995, 0.49102086, 0.49693298, 0.5028865, 0.50888133, 0.5149177, 0.52099556,
+    0.5271151, 0.5332764, 0.5394795, 0.54572445, 0.55201143, 0.5583404, 0.5647115, 0.57112485,
+    0.57758045, 0.58407843, 0.59061885, 0.59720176, 0.60382736, 0.61049557, 0.6172066, 0.6239604,
+    0.63075715, 0.63759685, 0.6444797, 0.65140563, 0.65837485, 0.6653873, 0.67244315, 0.6795425,
+    0.6866853, 0.69387174, 0.7011019, 0.70837575, 0.7156935, 0.7230551, 0.73046076, 0.7379104,
+    0.7454042, 0.7529422, 0.7605245, 0.76815116, 0.7758222, 0.7835378, 0.7912979, 0.7991027,
+    0.80695224, 0.8148466, 0.82278574, 0.8307699, 0.838799, 0.8468732, 0.8549926, 0.8631572,
+    0.8713671, 0.8796224, 0.8879231, 0.8962694, 0.9046612, 0.91309863, 0.92158186, 0.9301109,
+    0.9386857, 0.9473065, 0.9559733, 0.9646863, 0.9734453, 0.9822506, 0.9911021, 1.,
+];
+
+#[test]
+fn srgb_roundtrip() {
+    for i in 0..=255u8 {
+        assert_eq!(i, u8::from_linear(i.to_linear()));
+        assert!((i.to_linear() - srgb_to_linear(f32::from(i) / 255.)).abs() < 1e-6);
+    }
+    for i in (0..=65535u16).step_by(7) {
+        assert_eq!(i, u16::from_linear(i.to_linear()));
+    }
+    let px = Bgra::<u16>::new_bgra(1, 1000, 30000, 12345);
+    assert_eq!(px, Bgra::from_linear(px.to_linear()));
+    assert_eq!(12345. / 65535., px.to_linear().a);
+}
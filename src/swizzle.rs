@@ -0,0 +1,155 @@
+//! Reordering the channels of pixel slices in place, e.g. from `Bgra` to `Rgba`, without allocating.
+//!
+//! [`swizzle_in_place()`] converts every pixel, and returns the same memory as a slice of the new type.
+//! It works between pixel types of the same channels in a different order:
+//!
+//! * [`Rgba`], [`Argb`], [`Bgra`], and [`Abgr`]
+//! * [`PremulRgba`], [`PremulArgb`], [`PremulBgra`], and [`PremulAbgr`]
+//! * [`Rgb`], [`Bgr`], and [`Grb`]
+//! * [`Rgbx`], [`Bgrx`], [`Xrgb`], and [`Xbgr`], keeping the padding
+//!
+//! ```
+//! use rgb::{Bgra, Rgba};
+//! use rgb::swizzle::swizzle_in_place;
+//!
+//! let mut captured = [Bgra::new_bgra(1u8, 2, 3, 255); 4];
+//! let rgba: &mut [Rgba<u8>] = swizzle_in_place(&mut captured);
+//! assert_eq!(rgba[0], Rgba::new(3, 2, 1, 255));
+//! ```
+
+use crate::{Abgr, Argb, Bgr, Bgra, Grb, Rgb, Rgba};
+use crate::{Bgrx, Rgbx, Xbgr, Xrgb};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
+use core::mem::{align_of, size_of};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A pixel type that can be reordered in place to `To`, because it has the same channels and size.
+///
+/// This trait is sealed, and implemented for the pixel types listed in [the module docs](self).
+pub trait Swizzle<To>: Copy + Into<To> + sealed::Sealed<To> {}
+
+mod sealed {
+    pub trait Sealed<To> {
+        /// For 4-channel pixels, the index in `Self` of every component of `To`
+        const PERMUTATION: Option<[u8; 4]>;
+    }
+}
+
+/// Finds the channels of `to` in `from`, given as strings like `b"bgra"`
+const fn permutation(from: &[u8], to: &[u8]) -> Option<[u8; 4]> {
+    if to.len() != 4 {
+        return None;
+    }
+    let mut perm = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        while from[perm[i] as usize] != to[i] {
+            perm[i] += 1;
+        }
+        i += 1;
+    }
+    Some(perm)
+}
+
+macro_rules! swizzle {
+    ([$($from:ident $from_order:literal),*] => $to:ident $to_order:literal) => {$(
+        impl<T: Copy> sealed::Sealed<$to<T>> for $from<T> {
+            const PERMUTATION: Option<[u8; 4]> = permutation($from_order, $to_order);
+        }
+        impl<T: Copy> Swizzle<$to<T>> for $from<T> {}
+    )*};
+}
+
+swizzle!([Rgba b"rgba", Argb b"argb", Bgra b"bgra", Abgr b"abgr"] => Rgba b"rgba");
+swizzle!([Rgba b"rgba", Argb b"argb", Bgra b"bgra", Abgr b"abgr"] => Argb b"argb");
+swizzle!([Rgba b"rgba", Argb b"argb", Bgra b"bgra", Abgr b"abgr"] => Bgra b"bgra");
+swizzle!([Rgba b"rgba", Argb b"argb", Bgra b"bgra", Abgr b"abgr"] => Abgr b"abgr");
+swizzle!([PremulRgba b"rgba", PremulArgb b"argb", PremulBgra b"bgra", PremulAbgr b"abgr"] => PremulRgba b"rgba");
+swizzle!([PremulRgba b"rgba", PremulArgb b"argb", PremulBgra b"bgra", PremulAbgr b"abgr"] => PremulArgb b"argb");
+swizzle!([PremulRgba b"rgba", PremulArgb b"argb", PremulBgra b"bgra", PremulAbgr b"abgr"] => PremulBgra b"bgra");
+swizzle!([PremulRgba b"rgba", PremulArgb b"argb", PremulBgra b"bgra", PremulAbgr b"abgr"] => PremulAbgr b"abgr");
+swizzle!([Rgb b"rgb", Bgr b"bgr", Grb b"grb"] => Rgb b"rgb");
+swizzle!([Rgb b"rgb", Bgr b"bgr", Grb b"grb"] => Bgr b"bgr");
+swizzle!([Rgb b"rgb", Bgr b"bgr", Grb b"grb"] => Grb b"grb");
+swizzle!([Rgbx b"rgbx", Bgrx b"bgrx", Xrgb b"xrgb", Xbgr b"xbgr"] => Rgbx b"rgbx");
+swizzle!([Rgbx b"rgbx", Bgrx b"bgrx", Xrgb b"xrgb", Xbgr b"xbgr"] => Bgrx b"bgrx");
+swizzle!([Rgbx b"rgbx", Bgrx b"bgrx", Xrgb b"xrgb", Xbgr b"xbgr"] => Xrgb b"xrgb");
+swizzle!([Rgbx b"rgbx", Bgrx b"bgrx", Xrgb b"xrgb", Xbgr b"xbgr"] => Xbgr b"xbgr");
+
+/// Reorders the channels of every pixel, and returns the same memory as a slice of `To`.
+///
+/// The original slice stays borrowed, so it can't be observed in the intermediate state.
+///
+/// Pixels with 4 one-byte components, like `Bgra<u8>`, are reordered with [SIMD instructions](crate::simd).
+#[inline]
+pub fn swizzle_in_place<From: Swizzle<To>, To>(pixels: &mut [From]) -> &mut [To] {
+    const { assert!(size_of::<From>() == size_of::<To>() && align_of::<From>() == align_of::<To>()) };
+    let len = pixels.len();
+    let ptr = pixels.as_mut_ptr();
+    if let (4, Some(perm)) = (size_of::<From>(), <From as sealed::Sealed<To>>::PERMUTATION) {
+        // SAFETY: the components are one byte each, of the same type, so any reordering of the bytes is valid
+        crate::simd::permute_in_place(unsafe { core::slice::from_raw_parts_mut(ptr.cast::<u8>(), 4 * len) }, perm);
+        // SAFETY: all elements have been converted to `To`
+        return unsafe { core::slice::from_raw_parts_mut(ptr.cast::<To>(), len) };
+    }
+    for i in 0..len {
+        // SAFETY: the types have the same size and alignment, and both are repr(C) structs of the same
+        // component types, so any bit pattern of one is valid for the other
+        unsafe {
+            let px = ptr.add(i).read();
+            ptr.add(i).cast::<To>().write(px.into());
+        }
+    }
+    // SAFETY: all elements have been converted to `To`
+    unsafe { core::slice::from_raw_parts_mut(ptr.cast::<To>(), len) }
+}
+
+/// Reorders the channels of every pixel, reusing the `Vec`'s memory.
+///
+/// ```
+/// use rgb::{Abgr, Rgba};
+///
+/// let pixels = vec![Rgba::new(1u16, 2, 3, 4); 1000];
+/// let abgr: Vec<Abgr<u16>> = rgb::swizzle::swizzle_vec(pixels);
+/// assert_eq!(abgr[999], Abgr::new_abgr(4, 3, 2, 1));
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn swizzle_vec<From: Swizzle<To>, To>(mut pixels: Vec<From>) -> Vec<To> {
+    swizzle_in_place::<From, To>(&mut pixels);
+    let mut pixels = core::mem::ManuallyDrop::new(pixels);
+    // SAFETY: same size and alignment, so the allocation's layout is the same, and the elements are converted
+    unsafe { Vec::from_raw_parts(pixels.as_mut_ptr().cast::<To>(), pixels.len(), pixels.capacity()) }
+}
+
+#[test]
+fn swizzle() {
+    let mut px = [Argb::new_argb(1u8, 2, 3, 4), Argb::new_argb(5, 6, 7, 8)];
+    let abgr: &mut [Abgr<u8>] = swizzle_in_place(&mut px);
+    assert_eq!(abgr, [Abgr::new_abgr(1, 4, 3, 2), Abgr::new_abgr(5, 8, 7, 6)]);
+    let bgra: &mut [Bgra<u8>] = swizzle_in_place(abgr);
+    assert_eq!(bgra[1], Bgra::new_bgra(8, 7, 6, 5));
+
+    let mut px = [Grb::new_grb(1.5f32, 2., 3.)];
+    let bgr: &mut [Bgr<f32>] = swizzle_in_place(&mut px);
+    assert_eq!(bgr[0], Bgr::new_bgr(3., 1.5, 2.));
+
+    let mut px = [PremulBgra::new_bgra(1u16, 2, 3, 4)];
+    let rgba: &mut [PremulRgba<u16>] = swizzle_in_place(&mut px);
+    assert_eq!(rgba[0], PremulRgba::new(3, 2, 1, 4));
+
+    let mut px: [Rgba<u8>; 37] = core::array::from_fn(|i| Rgba::new(i as u8, 100 + i as u8, 200, 255 - i as u8));
+    let argb: &mut [Argb<u8>] = swizzle_in_place(&mut px);
+    assert_eq!(argb[36], Argb::new_argb(219, 36, 136, 200));
+    let bgra: &mut [Bgra<u8>] = swizzle_in_place(argb);
+    assert_eq!(bgra[0], Bgra::new_bgra(200, 100, 0, 255));
+
+    let mut px = [Bgrx::new_bgrx(1u8, 2, 3, 4); 20];
+    let rgbx: &mut [Rgbx<u8>] = swizzle_in_place(&mut px);
+    assert_eq!(rgbx[19], Rgbx::new(3, 2, 1, 0));
+    assert_eq!(rgbx[19].x, 4);
+
+    let empty: &mut [Rgb<u8>] = swizzle_in_place::<Rgb<u8>, _>(&mut []);
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,404 @@
+//! Flipping, rotating, transposing and cropping [images](crate::image).
+//!
+//! The functions copy from a source view to a destination view of the right size, and the `_in_place`
+//! variants modify one view, which for rotations and transposition must be square.
+//! Transposition and rotations by 90° work on tiles of pixels, so that neither image is read
+//! one pixel per cache line.
+//!
+//! [`Orientation`] applies the EXIF orientation tag:
+//!
+//! ```
+//! use rgb::Rgba;
+//! use rgb::image::{ImageView, ImageViewMut};
+//! use rgb::transform::Orientation;
+//!
+//! let pixels: [Rgba<u8>; 4 * 3] = core::array::from_fn(|i| Rgba::new(i as u8, 0, 0, 255));
+//! let src = ImageView::new(&pixels, 4, 3).unwrap();
+//!
+//! let orientation = Orientation::from_exif(6).unwrap();
+//! let (width, height) = orientation.output_size(src.width(), src.height());
+//! assert_eq!((width, height), (3, 4));
+//! let mut upright = [Rgba::default(); 4 * 3];
+//! let mut dst = ImageViewMut::new(&mut upright, width, height).unwrap();
+//! orientation.apply(src, &mut dst);
+//! // the bottom left corner is now at the top left
+//! assert_eq!(dst[(0, 0)], src[(0, 2)]);
+//! ```
+
+use crate::image::{ImageView, ImageViewMut};
+
+/// Width and height of the tiles, in pixels
+const TILE: usize = 16;
+
+/// Mirrors the image left to right.
+///
+/// # Panics
+///
+/// If the images have different sizes.
+pub fn flip_horizontal<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    assert_eq!((src.width(), src.height()), (dst.width(), dst.height()));
+    for (dst, src) in dst.rows_mut().zip(src.rows()) {
+        for (d, &s) in dst.iter_mut().zip(src.iter().rev()) {
+            *d = s;
+        }
+    }
+}
+
+/// Mirrors the image left to right, in place
+pub fn flip_horizontal_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    for row in img.rows_mut() {
+        row.reverse();
+    }
+}
+
+/// Mirrors the image top to bottom.
+///
+/// # Panics
+///
+/// If the images have different sizes.
+pub fn flip_vertical<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    assert_eq!((src.width(), src.height()), (dst.width(), dst.height()));
+    for (dst, src) in dst.rows_mut().zip(src.rows().rev()) {
+        dst.copy_from_slice(src);
+    }
+}
+
+/// Mirrors the image top to bottom, in place
+pub fn flip_vertical_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    let mut rows = img.rows_mut();
+    while let (Some(top), Some(bottom)) = (rows.next(), rows.next_back()) {
+        top.swap_with_slice(bottom);
+    }
+}
+
+/// Rotates the image by 180°.
+///
+/// # Panics
+///
+/// If the images have different sizes.
+pub fn rotate_180<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    assert_eq!((src.width(), src.height()), (dst.width(), dst.height()));
+    for (dst, src) in dst.rows_mut().zip(src.rows().rev()) {
+        for (d, &s) in dst.iter_mut().zip(src.iter().rev()) {
+            *d = s;
+        }
+    }
+}
+
+/// Rotates the image by 180°, in place
+pub fn rotate_180_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    let mut rows = img.rows_mut();
+    loop {
+        match (rows.next(), rows.next_back()) {
+            (Some(top), Some(bottom)) => {
+                top.swap_with_slice(bottom);
+                top.reverse();
+                bottom.reverse();
+            },
+            (Some(middle), None) => middle.reverse(),
+            _ => break,
+        }
+    }
+}
+
+/// Copies `src` transposed, and then mirrored horizontally and/or vertically, tile by tile
+fn transpose_flipped<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>, flip_h: bool, flip_v: bool) {
+    let (width, height) = (src.width(), src.height());
+    assert_eq!((height, width), (dst.width(), dst.height()));
+    let (buf, stride) = (src.buf(), src.stride());
+    for x0 in (0..width).step_by(TILE) {
+        for y0 in (0..height).step_by(TILE) {
+            for x in x0..(x0 + TILE).min(width) {
+                let dst_row = dst.row_mut(if flip_v { width - 1 - x } else { x }).unwrap();
+                for y in y0..(y0 + TILE).min(height) {
+                    dst_row[if flip_h { height - 1 - y } else { y }] = buf[y * stride + x];
+                }
+            }
+        }
+    }
+}
+
+/// Swaps the rows and columns of the image, i.e. mirrors it along the diagonal from the top left corner.
+///
+/// # Panics
+///
+/// If the width of `dst` isn't the height of `src`, or the height of `dst` isn't the width of `src`.
+pub fn transpose<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    transpose_flipped(src, dst, false, false);
+}
+
+/// Swaps the rows and columns of a square image, in place.
+///
+/// # Panics
+///
+/// If the image isn't square.
+pub fn transpose_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    let size = img.width();
+    assert_eq!(size, img.height(), "the image must be square");
+    let stride = img.stride();
+    let buf = img.buf_mut();
+    for y0 in (0..size).step_by(TILE) {
+        for x0 in (y0..size).step_by(TILE) {
+            for y in y0..(y0 + TILE).min(size) {
+                for x in x0.max(y + 1)..(x0 + TILE).min(size) {
+                    buf.swap(y * stride + x, x * stride + y);
+                }
+            }
+        }
+    }
+}
+
+/// Rotates the image by 90° clockwise.
+///
+/// # Panics
+///
+/// If the width of `dst` isn't the height of `src`, or the height of `dst` isn't the width of `src`.
+pub fn rotate_90<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    transpose_flipped(src, dst, true, false);
+}
+
+/// Rotates a square image by 90° clockwise, in place.
+///
+/// # Panics
+///
+/// If the image isn't square.
+pub fn rotate_90_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    transpose_in_place(img);
+    flip_horizontal_in_place(img);
+}
+
+/// Rotates the image by 270° clockwise, i.e. 90° counter-clockwise.
+///
+/// # Panics
+///
+/// If the width of `dst` isn't the height of `src`, or the height of `dst` isn't the width of `src`.
+pub fn rotate_270<P: Copy>(src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+    transpose_flipped(src, dst, false, true);
+}
+
+/// Rotates a square image by 270° clockwise, in place.
+///
+/// # Panics
+///
+/// If the image isn't square.
+pub fn rotate_270_in_place<P>(img: &mut ImageViewMut<'_, P>) {
+    transpose_in_place(img);
+    flip_vertical_in_place(img);
+}
+
+/// Copies the rectangle of `dst`'s size at `x`, `y` in `src`, and fills the parts outside of `src` with `fill`.
+///
+/// The position can be negative, and the rectangle can be larger than `src`, e.g. to pad the image to a multiple of a block size.
+/// To crop without padding, use [`ImageView::sub_view()`].
+///
+/// ```
+/// use rgb::Gray_v09 as Gray;
+/// use rgb::image::{ImageView, ImageViewMut};
+///
+/// let src = [Gray::new(1u8); 4];
+/// let mut dst = [Gray::new(0u8); 9];
+/// let src = ImageView::new(&src, 2, 2).unwrap();
+/// rgb::transform::crop_padded(src, -1, 0, &mut ImageViewMut::new(&mut dst, 3, 3).unwrap(), Gray::new(9));
+/// assert_eq!(dst.map(|g| g.v), [9, 1, 1, 9, 1, 1, 9, 9, 9]);
+/// ```
+pub fn crop_padded<P: Copy>(src: ImageView<'_, P>, x: isize, y: isize, dst: &mut ImageViewMut<'_, P>, fill: P) {
+    let width = dst.width() as isize;
+    // the columns of `dst` that are inside `src`
+    let start = x.saturating_neg().clamp(0, width);
+    let end = (src.width() as isize).saturating_sub(x).clamp(start, width);
+    for (dy, row) in dst.rows_mut().enumerate() {
+        let src_row = usize::try_from(y.saturating_add(dy as isize)).ok().and_then(|y| src.row(y));
+        match src_row {
+            Some(src_row) if start < end => {
+                let (start, end) = (start as usize, end as usize);
+                let src_start = (x + start as isize) as usize;
+                row[..start].fill(fill);
+                row[start..end].copy_from_slice(&src_row[src_start..src_start + (end - start)]);
+                row[end..].fill(fill);
+            },
+            _ => row.fill(fill),
+        }
+    }
+}
+
+/// The orientation of an image, as in the EXIF `Orientation` tag. Every variant describes how to transform
+/// the stored image to display it upright.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum Orientation {
+    /// Already upright (1)
+    #[default]
+    Normal = 1,
+    /// Mirror left to right (2)
+    FlipHorizontal = 2,
+    /// Rotate by 180° (3)
+    Rotate180 = 3,
+    /// Mirror top to bottom (4)
+    FlipVertical = 4,
+    /// Mirror along the diagonal from the top left corner (5)
+    Transpose = 5,
+    /// Rotate by 90° clockwise (6)
+    Rotate90 = 6,
+    /// Mirror along the diagonal from the top right corner (7)
+    Transverse = 7,
+    /// Rotate by 270° clockwise (8)
+    Rotate270 = 8,
+}
+
+impl Orientation {
+    /// The orientation for the value of the EXIF tag, or `None` if it's not in `1..=8`
+    #[inline]
+    #[must_use]
+    pub fn from_exif(value: u16) -> Option<Self> {
+        Some(match value {
+            1 => Self::Normal,
+            2 => Self::FlipHorizontal,
+            3 => Self::Rotate180,
+            4 => Self::FlipVertical,
+            5 => Self::Transpose,
+            6 => Self::Rotate90,
+            7 => Self::Transverse,
+            8 => Self::Rotate270,
+            _ => return None,
+        })
+    }
+
+    /// Whether the width and height of the image are swapped
+    #[inline]
+    #[must_use]
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self, Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270)
+    }
+
+    /// The width and height of the upright image
+    #[inline]
+    #[must_use]
+    pub fn output_size(self, width: usize, height: usize) -> (usize, usize) {
+        if self.swaps_dimensions() { (height, width) } else { (width, height) }
+    }
+
+    /// Copies `src` upright to `dst`, which must have the size given by [`Orientation::output_size()`].
+    ///
+    /// # Panics
+    ///
+    /// If `dst` has a different size.
+    pub fn apply<P: Copy>(self, src: ImageView<'_, P>, dst: &mut ImageViewMut<'_, P>) {
+        match self {
+            Self::Normal => dst.copy_from(src),
+            Self::FlipHorizontal => flip_horizontal(src, dst),
+            Self::Rotate180 => rotate_180(src, dst),
+            Self::FlipVertical => flip_vertical(src, dst),
+            Self::Transpose => transpose_flipped(src, dst, false, false),
+            Self::Rotate90 => transpose_flipped(src, dst, true, false),
+            Self::Transverse => transpose_flipped(src, dst, true, true),
+            Self::Rotate270 => transpose_flipped(src, dst, false, true),
+        }
+    }
+
+    /// Makes the image upright in place.
+    ///
+    /// # Panics
+    ///
+    /// If the orientation swaps the dimensions, and the image isn't square.
+    pub fn apply_in_place<P>(self, img: &mut ImageViewMut<'_, P>) {
+        match self {
+            Self::Normal => {},
+            Self::FlipHorizontal => flip_horizontal_in_place(img),
+            Self::Rotate180 => rotate_180_in_place(img),
+            Self::FlipVertical => flip_vertical_in_place(img),
+            Self::Transpose => transpose_in_place(img),
+            Self::Rotate90 => rotate_90_in_place(img),
+            Self::Transverse => {
+                transpose_in_place(img);
+                rotate_180_in_place(img);
+            },
+            Self::Rotate270 => rotate_270_in_place(img),
+        }
+    }
+}
+
+#[cfg(test)]
+fn upright_reference(o: Orientation, width: usize, height: usize, x: usize, y: usize) -> (usize, usize) {
+    match o {
+        Orientation::Normal => (x, y),
+        Orientation::FlipHorizontal => (width - 1 - x, y),
+        Orientation::Rotate180 => (width - 1 - x, height - 1 - y),
+        Orientation::FlipVertical => (x, height - 1 - y),
+        Orientation::Transpose => (y, x),
+        Orientation::Rotate90 => (height - 1 - y, x),
+        Orientation::Transverse => (height - 1 - y, width - 1 - x),
+        Orientation::Rotate270 => (y, width - 1 - x),
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn transforms() {
+    use crate::image::ImageBuf;
+    use crate::Rgb;
+
+    // padded source, crossing tile boundaries
+    let (width, height, stride) = (37, 21, 40);
+    let pixels: std::vec::Vec<_> = (0..stride * height).map(|i| Rgb::new((i % stride) as u16, (i / stride) as u16, 0)).collect();
+    let src = ImageView::with_stride(&pixels, width, height, stride).unwrap();
+
+    for value in 1..=8 {
+        let o = Orientation::from_exif(value).unwrap();
+        assert_eq!(o as u16, value);
+        let (w, h) = o.output_size(width, height);
+        let mut dst = ImageBuf::new(w, h, Rgb::new(0, 0, 0xFFFF));
+        o.apply(src, &mut dst.as_view_mut());
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = upright_reference(o, width, height, x, y);
+                assert_eq!(dst[(dx, dy)], src[(x, y)], "{o:?} {x},{y}");
+            }
+        }
+
+        for size in [0, 1, 2, 17, 33] {
+            let square = ImageBuf::from_fn(size, size, |x, y| Rgb::new(x as u16, y as u16, 0));
+            let mut expected = ImageBuf::new(size, size, Rgb::default());
+            o.apply(square.as_view(), &mut expected.as_view_mut());
+            let mut in_place = square.clone();
+            o.apply_in_place(&mut in_place.as_view_mut());
+            assert_eq!(in_place, expected, "{o:?} {size}");
+        }
+    }
+    assert_eq!(Orientation::from_exif(9), None);
+
+    // the padding between rows is left alone
+    let mut padded: [Rgb<u16>; 12] = core::array::from_fn(|i| Rgb::new(if i % 4 < 2 { 0 } else { 7 }, i as u16, 0));
+    let mut flipped = ImageViewMut::with_stride(&mut padded, 2, 3, 4).unwrap();
+    rotate_180_in_place(&mut flipped);
+    flip_vertical_in_place(&mut flipped);
+    flip_horizontal_in_place(&mut flipped);
+    for (i, px) in padded.iter().enumerate() {
+        assert_eq!(px.r, if i % 4 < 2 { 0 } else { 7 });
+        assert_eq!(px.g, i as u16);
+    }
+}
+
+#[test]
+#[should_panic(expected = "square")]
+fn transpose_in_place_not_square() {
+    let mut buf = [0u8; 6];
+    transpose_in_place(&mut ImageViewMut::new(&mut buf, 3, 2).unwrap());
+}
+
+#[test]
+fn crop() {
+    let pixels: [u8; 12] = core::array::from_fn(|i| i as u8);
+    let src = ImageView::new(&pixels, 4, 3).unwrap();
+    let crop = |x, y, width, fill| {
+        let mut buf = [0u8; 12];
+        crop_padded(src, x, y, &mut ImageViewMut::new(&mut buf, width, 2).unwrap(), fill);
+        buf
+    };
+
+    assert_eq!(crop(-1, 2, 6, 99), [99, 8, 9, 10, 11, 99, 99, 99, 99, 99, 99, 99]);
+    assert_eq!(crop(1, 1, 3, 99)[..6], [5, 6, 7, 9, 10, 11]);
+    assert_eq!(crop(3, -1, 3, 99)[..6], [99, 99, 99, 3, 99, 99]);
+    assert_eq!(crop(-5, 0, 3, 98)[..6], [98; 6]);
+    assert_eq!(crop(isize::MIN, isize::MAX, 3, 97)[..6], [97; 6]);
+    assert_eq!(crop(isize::MAX, 0, 3, 96)[..6], [96; 6]);
+}
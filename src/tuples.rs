@@ -1,6 +1,7 @@
 use crate::formats::gray::{Gray_v08, Gray_v09};
 use crate::formats::gray_alpha::GrayAlpha_v08;
-use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Grb, Rgb, Rgba, Rgbw};
+use crate::{Abgr, Argb, Bgr, Bgra, GrayA, Grb, Rgb, Rgba, Rgbw, Ycbcr, Ycbcra};
+use crate::{PremulAbgr, PremulArgb, PremulBgra, PremulRgba};
 
 macro_rules! tuple_conversion {
     ($name:ident, 1, [$($bit:tt:$num:tt),*]) => {
@@ -71,10 +72,16 @@ tuple_conversion!(Grb, 3, [g:0, r:1, b:2]);
 tuple_conversion!(Gray_v09, 1, [v:0]);
 tuple_conversion!(Gray_v08, 1, [0:0]);
 tuple_conversion!(Rgbw, 4, [r:0, g:1, b:2, w:3]);
+tuple_conversion!(Ycbcr, 3, [y:0, cb:1, cr:2]);
 
 tuple_conversion!(Rgba, 4, [r:0, g:1, b:2, a:3]);
 tuple_conversion!(Argb, 4, [a:0, r:1, g:2, b:3]);
 tuple_conversion!(Bgra, 4, [b:0, g:1, r:2, a:3]);
 tuple_conversion!(Abgr, 4, [a:0, b:1, g:2, r:3]);
 tuple_conversion!(GrayA, 2, [v:0, a:1]);
+tuple_conversion!(Ycbcra, 4, [y:0, cb:1, cr:2, a:3]);
+tuple_conversion!(PremulRgba, 4, [r:0, g:1, b:2, a:3]);
+tuple_conversion!(PremulArgb, 4, [a:0, r:1, g:2, b:3]);
+tuple_conversion!(PremulBgra, 4, [b:0, g:1, r:2, a:3]);
+tuple_conversion!(PremulAbgr, 4, [a:0, b:1, g:2, r:3]);
 tuple_conversion!(GrayAlpha_v08, 2, [0:0, 1:1]);
@@ -0,0 +1,227 @@
+//! Conversions between [`Ycbcr`]/[`Ycbcra`] and [`Rgb`]/[`Rgba`], e.g. for decoded video and JPEG.
+//!
+//! The conversion needs to know which [`Matrix`] and [`ColorRange`] the YCbCr values use.
+//! They don't change the transfer function or the primaries, so the RGB values are in the same
+//! (usually gamma-compressed) color space as the YCbCr ones.
+//!
+//! `u8`, `u16`, and `f32` components are supported:
+//!
+//! * `u8` and `u16` use the code values of 8-bit and 16-bit video. Chroma is centered at 128 (or 32768).
+//!   The results are rounded, and colors outside of the RGB gamut are clamped.
+//! * `f32` has luma in `0.0..=1.0`, and chroma centered at `0.0`, in `-0.5..=0.5`.
+//!   Limited range uses the 8-bit code values divided by 255, e.g. luma `16.0/255.0..=235.0/255.0`, and chroma
+//!   `-112.0/255.0..=112.0/255.0`. Values are never clamped.
+//!
+//! ```
+//! use rgb::{Rgb, Ycbcr};
+//! use rgb::ycbcr::{ColorRange, Matrix};
+//!
+//! let white = Ycbcr::from_rgb(Rgb::new(255u8, 255, 255), Matrix::Bt709, ColorRange::Limited);
+//! assert_eq!(white, Ycbcr::new(235, 128, 128));
+//!
+//! // JPEG uses BT.601 in full range
+//! let red = Ycbcr::new(76u8, 85, 255);
+//! assert_eq!(red.to_rgb(Matrix::Bt601, ColorRange::Full), Rgb::new(254, 0, 0));
+//! ```
+
+use crate::{Rgb, Rgba, Ycbcr, Ycbcra};
+
+/// The coefficients for computing luma from RGB.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Matrix {
+    /// ITU-R BT.601, used by JPEG and standard definition video
+    Bt601,
+    /// ITU-R BT.709, used by HD video
+    Bt709,
+    /// ITU-R BT.2020 non-constant luminance, used by UHD and HDR video
+    Bt2020,
+}
+
+impl Matrix {
+    /// The red and blue luma coefficients, `Kr` and `Kb`. Green is `1 - Kr - Kb`.
+    #[inline]
+    #[must_use]
+    pub const fn kr_kb(self) -> (f32, f32) {
+        match self {
+            Self::Bt601 => (0.299, 0.114),
+            Self::Bt709 => (0.2126, 0.0722),
+            Self::Bt2020 => (0.2627, 0.0593),
+        }
+    }
+}
+
+/// The range of code values used for YCbCr components.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorRange {
+    /// All values are used. This is used by JPEG, and is also called "PC" or "JPEG" range.
+    Full,
+    /// Luma is in `16..=235`, and chroma in `16..=240` (scaled for higher bit depths).
+    /// This is the usual range for video, and is also called "TV", "studio", or "MPEG" range.
+    Limited,
+}
+
+/// Component types supported by the conversions: `u8`, `u16`, and `f32`
+pub trait YcbcrComponent: Copy + 'static {
+    /// The maximum RGB value
+    #[doc(hidden)]
+    const MAX: f32;
+
+    /// Luma offset, luma scale, chroma offset, chroma scale
+    #[doc(hidden)]
+    fn levels(range: ColorRange) -> [f32; 4];
+
+    #[doc(hidden)]
+    fn to_f32(self) -> f32;
+
+    /// Rounds and clamps integers
+    #[doc(hidden)]
+    fn from_f32(value: f32) -> Self;
+}
+
+macro_rules! ycbcr_int {
+    ($t:ty, $scale:literal) => {
+        impl YcbcrComponent for $t {
+            const MAX: f32 = <$t>::MAX as f32;
+
+            #[inline]
+            fn levels(range: ColorRange) -> [f32; 4] {
+                match range {
+                    ColorRange::Full => [0., <$t>::MAX as f32, (<$t>::MAX / 2 + 1) as f32, <$t>::MAX as f32],
+                    ColorRange::Limited => [16. * $scale, 219. * $scale, 128. * $scale, 224. * $scale],
+                }
+            }
+
+            #[inline(always)]
+            fn to_f32(self) -> f32 {
+                f32::from(self)
+            }
+
+            #[inline(always)]
+            fn from_f32(value: f32) -> Self {
+                (value.clamp(0., <$t>::MAX as f32) + 0.5) as $t
+            }
+        }
+    };
+}
+
+ycbcr_int!(u8, 1.);
+ycbcr_int!(u16, 256.);
+
+impl YcbcrComponent for f32 {
+    const MAX: f32 = 1.;
+
+    #[inline]
+    fn levels(range: ColorRange) -> [f32; 4] {
+        match range {
+            ColorRange::Full => [0., 1., 0., 1.],
+            ColorRange::Limited => [16. / 255., 219. / 255., 0., 224. / 255.],
+        }
+    }
+
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+#[inline]
+fn encode<T: YcbcrComponent>([r, g, b]: [T; 3], matrix: Matrix, range: ColorRange) -> [T; 3] {
+    let (kr, kb) = matrix.kr_kb();
+    let [r, g, b] = [r, g, b].map(|c| c.to_f32() / T::MAX);
+    let y = kr * r + (1. - kr - kb) * g + kb * b;
+    let cb = (b - y) / (2. * (1. - kb));
+    let cr = (r - y) / (2. * (1. - kr));
+    let [y_offset, y_scale, c_offset, c_scale] = T::levels(range);
+    [y * y_scale + y_offset, cb * c_scale + c_offset, cr * c_scale + c_offset].map(T::from_f32)
+}
+
+#[inline]
+fn decode<T: YcbcrComponent>([y, cb, cr]: [T; 3], matrix: Matrix, range: ColorRange) -> [T; 3] {
+    let (kr, kb) = matrix.kr_kb();
+    let [y_offset, y_scale, c_offset, c_scale] = T::levels(range);
+    let y = (y.to_f32() - y_offset) / y_scale;
+    let cb = (cb.to_f32() - c_offset) / c_scale;
+    let cr = (cr.to_f32() - c_offset) / c_scale;
+    let r = y + 2. * (1. - kr) * cr;
+    let b = y + 2. * (1. - kb) * cb;
+    let g = (y - kr * r - kb * b) / (1. - kr - kb);
+    [r, g, b].map(|c| T::from_f32(c * T::MAX))
+}
+
+impl<T: YcbcrComponent> Ycbcr<T> {
+    /// Converts from RGB, using the given matrix and range for the result.
+    #[must_use]
+    pub fn from_rgb(rgb: Rgb<T>, matrix: Matrix, range: ColorRange) -> Self {
+        let [y, cb, cr] = encode([rgb.r, rgb.g, rgb.b], matrix, range);
+        Self { y, cb, cr }
+    }
+
+    /// Converts to RGB, interpreting the pixel with the given matrix and range.
+    #[must_use]
+    pub fn to_rgb(self, matrix: Matrix, range: ColorRange) -> Rgb<T> {
+        let [r, g, b] = decode([self.y, self.cb, self.cr], matrix, range);
+        Rgb { r, g, b }
+    }
+}
+
+impl<T: YcbcrComponent, A> Ycbcra<T, A> {
+    /// Converts from RGBA, using the given matrix and range for the result. Alpha is copied unchanged.
+    #[must_use]
+    pub fn from_rgba(rgba: Rgba<T, A>, matrix: Matrix, range: ColorRange) -> Self {
+        let [y, cb, cr] = encode([rgba.r, rgba.g, rgba.b], matrix, range);
+        Self { y, cb, cr, a: rgba.a }
+    }
+
+    /// Converts to RGBA, interpreting the pixel with the given matrix and range. Alpha is copied unchanged.
+    #[must_use]
+    pub fn to_rgba(self, matrix: Matrix, range: ColorRange) -> Rgba<T, A> {
+        let [r, g, b] = decode([self.y, self.cb, self.cr], matrix, range);
+        Rgba { r, g, b, a: self.a }
+    }
+}
+
+#[test]
+fn ycbcr_levels() {
+    use ColorRange::{Full, Limited};
+
+    for matrix in [Matrix::Bt601, Matrix::Bt709, Matrix::Bt2020] {
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(0u8, 0, 0), matrix, Limited), Ycbcr::new(16, 128, 128));
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(255u8, 255, 255), matrix, Full), Ycbcr::new(255, 128, 128));
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(65535u16, 65535, 65535), matrix, Limited), Ycbcr::new(60160, 32768, 32768));
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(0., 0., 1.), matrix, Full).cb, 0.5);
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(1., 0., 0.), matrix, Full).cr, 0.5);
+        assert_eq!(Ycbcr::from_rgb(Rgb::new(0u8, 0, 255), matrix, Limited).cb, 240);
+        assert_eq!(Ycbcr::new(16u8, 128, 128).to_rgb(matrix, Limited), Rgb::new(0, 0, 0));
+        assert_eq!(Ycbcr::new(0u8, 0, 0).to_rgb(matrix, Limited).b, 0);
+    }
+    assert_eq!(Ycbcr::from_rgb(Rgb::new(255u8, 0, 0), Matrix::Bt601, Full), Ycbcr::new(76, 85, 255));
+    assert_eq!(Ycbcr::from_rgb(Rgb::new(255u8, 0, 0), Matrix::Bt709, Limited), Ycbcr::new(63, 102, 240));
+    let px = Ycbcra::from_rgba(Rgba::new(0.25f32, 0.5, 0.75, 0.1), Matrix::Bt2020, Limited);
+    assert_eq!(px.a, 0.1);
+    let back = px.to_rgba(Matrix::Bt2020, Limited);
+    assert!((back.r - 0.25).abs() < 1e-6 && (back.g - 0.5).abs() < 1e-6 && (back.b - 0.75).abs() < 1e-6);
+}
+
+#[test]
+fn ycbcr_roundtrip() {
+    for matrix in [Matrix::Bt601, Matrix::Bt709, Matrix::Bt2020] {
+        for (range, tolerance) in [(ColorRange::Full, 1), (ColorRange::Limited, 2)] {
+            for i in 0..4096u32 {
+                let rgb = Rgb::new((i * 16 % 256) as u8, (i / 16 * 7 % 256) as u8, (i / 2 % 256) as u8);
+                let back = Ycbcr::from_rgb(rgb, matrix, range).to_rgb(matrix, range);
+                let diff = |a: u8, b: u8| a.abs_diff(b);
+                assert!(diff(rgb.r, back.r) <= tolerance && diff(rgb.g, back.g) <= tolerance && diff(rgb.b, back.b) <= tolerance, "{rgb} {back} {matrix:?} {range:?}");
+
+                let rgb = Rgba { r: u16::from(rgb.r) * 257, g: u16::from(rgb.g) * 257, b: u16::from(rgb.b) * 257, a: 7u8 };
+                let back = Ycbcra::from_rgba(rgb, matrix, range).to_rgba(matrix, range);
+                assert!(rgb.r.abs_diff(back.r) <= 2 && rgb.g.abs_diff(back.g) <= 2 && rgb.b.abs_diff(back.b) <= 2);
+                assert_eq!(back.a, 7);
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Conversions between chroma-subsampled YUV frames, like NV12 or YUYV, and frames of RGB pixels.
+//!
+//! [`YuvFrame`] reads a frame in one of the [`YuvLayout`]s, and converts it to any pixel type that
+//! can be made from `Rgba<u8>`, e.g. [`Rgb<u8>`](crate::Rgb) or [`Bgra<u8>`](crate::Bgra).
+//! [`YuvFrameMut`] converts the other way. The components are 8-bit, and the
+//! [`Matrix`] and [`ColorRange`] work the same as in [the `ycbcr` module](crate::ycbcr).
+//!
+//! Frames can have any width and height, including odd ones, and rows can have padding (stride).
+//! Chroma samples are assumed to be horizontally aligned with the even luma columns, and, for 4:2:0, vertically
+//! halfway between the luma rows. This is the default for MPEG-2, H.264, and later codecs.
+//!
+//! ```
+//! use rgb::Rgb;
+//! use rgb::ycbcr::{ColorRange, Matrix};
+//! use rgb::yuv::{ChromaUpsampling, YuvFrame, YuvFrameMut, YuvLayout};
+//!
+//! let (width, height) = (3, 2);
+//! let pixels = [Rgb::new(255u8, 0, 0); 6];
+//! let mut nv12 = vec![0; YuvLayout::Nv12.buffer_len(width, height)];
+//! YuvFrameMut::new(YuvLayout::Nv12, &mut nv12, width, height).from_rgb(&pixels, width, Matrix::Bt709, ColorRange::Limited);
+//!
+//! let mut decoded = [Rgb::new(0u8, 0, 0); 6];
+//! let frame = YuvFrame::new(YuvLayout::Nv12, &nv12, width, height);
+//! frame.to_rgb(&mut decoded, width, Matrix::Bt709, ColorRange::Limited, ChromaUpsampling::Bilinear);
+//! // YUV has fewer distinct colors, so the result is approximate
+//! assert_eq!(decoded[5].r, 255);
+//! assert!(decoded[5].g <= 1 && decoded[5].b <= 1);
+//! ```
+
+use crate::depth::ConvertDepth;
+use crate::ycbcr::{ColorRange, Matrix};
+use crate::{Rgb, Rgba, Ycbcr};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// The arrangement of luma and chroma samples in memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum YuvLayout {
+    /// 4:2:0 with three planes: Y, U, and V. Also known as YUV420P.
+    I420,
+    /// 4:2:0 with three planes: Y, V, and U
+    Yv12,
+    /// 4:2:0 with two planes: Y, and interleaved UV
+    Nv12,
+    /// 4:2:0 with two planes: Y, and interleaved VU
+    Nv21,
+    /// 4:2:2 with one plane, in `Y0 U Y1 V` order. Also known as YUY2.
+    Yuyv,
+    /// 4:2:2 with one plane, in `U Y0 V Y1` order
+    Uyvy,
+}
+
+impl YuvLayout {
+    /// The number of planes
+    #[must_use]
+    pub const fn num_planes(self) -> usize {
+        match self {
+            Self::I420 | Self::Yv12 => 3,
+            Self::Nv12 | Self::Nv21 => 2,
+            Self::Yuyv | Self::Uyvy => 1,
+        }
+    }
+
+    /// The number of bytes of a frame with all planes stored back to back, without padding.
+    ///
+    /// This is what [`YuvFrame::new()`] and [`YuvFrameMut::new()`] expect.
+    #[must_use]
+    pub fn buffer_len(self, width: usize, height: usize) -> usize {
+        (0..self.num_planes()).map(|plane| {
+            let (row_len, rows) = self.plane_size(plane, width, height);
+            row_len * rows
+        }).sum()
+    }
+
+    /// Bytes per row and number of rows of a plane
+    fn plane_size(self, plane: usize, width: usize, height: usize) -> (usize, usize) {
+        let chroma_width = width.div_ceil(2);
+        match (self, plane) {
+            (Self::Yuyv | Self::Uyvy, _) => (4 * chroma_width, height),
+            (_, 0) => (width, height),
+            (Self::Nv12 | Self::Nv21, _) => (2 * chroma_width, height.div_ceil(2)),
+            _ => (chroma_width, height.div_ceil(2)),
+        }
+    }
+
+    const fn is_420(self) -> bool {
+        !matches!(self, Self::Yuyv | Self::Uyvy)
+    }
+
+    /// Plane and byte offset of a luma sample
+    #[inline]
+    fn luma_index(self, strides: &[usize; 3], x: usize, y: usize) -> (usize, usize) {
+        match self {
+            Self::Yuyv => (0, y * strides[0] + 2 * x),
+            Self::Uyvy => (0, y * strides[0] + 2 * x + 1),
+            _ => (0, y * strides[0] + x),
+        }
+    }
+
+    /// Plane and byte offset of the U and V samples
+    #[inline]
+    fn chroma_index(self, strides: &[usize; 3], x: usize, y: usize) -> [(usize, usize); 2] {
+        match self {
+            Self::I420 => [(1, y * strides[1] + x), (2, y * strides[2] + x)],
+            Self::Yv12 => [(2, y * strides[2] + x), (1, y * strides[1] + x)],
+            Self::Nv12 => [(1, y * strides[1] + 2 * x), (1, y * strides[1] + 2 * x + 1)],
+            Self::Nv21 => [(1, y * strides[1] + 2 * x + 1), (1, y * strides[1] + 2 * x)],
+            Self::Yuyv => [(0, y * strides[0] + 4 * x + 1), (0, y * strides[0] + 4 * x + 3)],
+            Self::Uyvy => [(0, y * strides[0] + 4 * x), (0, y * strides[0] + 4 * x + 2)],
+        }
+    }
+
+    /// Splits a contiguous buffer into planes
+    fn split(self, width: usize, height: usize) -> ([usize; 3], [usize; 3]) {
+        let mut strides = [0; 3];
+        let mut lens = [0; 3];
+        for plane in 0..self.num_planes() {
+            let (row_len, rows) = self.plane_size(plane, width, height);
+            strides[plane] = row_len;
+            lens[plane] = row_len * rows;
+        }
+        (strides, lens)
+    }
+
+    fn check_planes(self, lens: &[usize], strides: &[usize], width: usize, height: usize) {
+        for (plane, (&len, &stride)) in lens.iter().zip(strides).enumerate() {
+            let (row_len, rows) = self.plane_size(plane, width, height);
+            assert!(stride >= row_len, "stride of plane {plane} is too small");
+            assert!(rows == 0 || len >= (rows - 1) * stride + row_len, "plane {plane} is too small");
+        }
+    }
+}
+
+/// How to compute chroma for luma samples that don't have their own.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChromaUpsampling {
+    /// Uses the closest chroma sample. It's the fastest, but edges of colors are blocky.
+    Nearest,
+    /// Interpolates between the closest chroma samples.
+    Bilinear,
+}
+
+/// A borrowed YUV frame, for converting to RGB.
+#[derive(Debug, Copy, Clone)]
+pub struct YuvFrame<'a> {
+    layout: YuvLayout,
+    width: usize,
+    height: usize,
+    planes: [&'a [u8]; 3],
+    strides: [usize; 3],
+}
+
+impl<'a> YuvFrame<'a> {
+    /// A frame with all planes stored back to back in `data`, without padding.
+    ///
+    /// The size is given in pixels. See [`YuvLayout::buffer_len()`].
+    ///
+    /// # Panics
+    ///
+    /// If `data` is too small.
+    #[must_use]
+    pub fn new(layout: YuvLayout, data: &'a [u8], width: usize, height: usize) -> Self {
+        let (strides, lens) = layout.split(width, height);
+        assert!(data.len() >= lens.iter().sum(), "the frame is too small");
+        let (y, rest) = data.split_at(lens[0]);
+        let (u, v) = rest.split_at(lens[1]);
+        Self { layout, width, height, planes: [y, u, &v[..lens[2]]], strides }
+    }
+
+    /// A frame with separate planes, which can have padding at the end of each row.
+    ///
+    /// The `planes` are in the order of the layout, e.g. `[y, v, u]` for [`YuvLayout::Yv12`].
+    /// Planes that the layout doesn't have are ignored, and can be empty. The strides are in bytes.
+    ///
+    /// # Panics
+    ///
+    /// If the planes are too small.
+    #[must_use]
+    pub fn from_planes(layout: YuvLayout, planes: [&'a [u8]; 3], strides: [usize; 3], width: usize, height: usize) -> Self {
+        let n = layout.num_planes();
+        let lens = planes.map(<[u8]>::len);
+        layout.check_planes(&lens[..n], &strides[..n], width, height);
+        Self { layout, width, height, planes, strides }
+    }
+
+    /// Width in pixels
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in pixels
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    fn luma(&self, x: usize, y: usize) -> f32 {
+        let (plane, i) = self.layout.luma_index(&self.strides, x, y);
+        f32::from(self.planes[plane][i])
+    }
+
+    #[inline]
+    fn chroma(&self, x: usize, y: usize) -> [f32; 2] {
+        self.layout.chroma_index(&self.strides, x, y).map(|(plane, i)| f32::from(self.planes[plane][i]))
+    }
+
+    /// Chroma at a luma sample
+    fn upsampled_chroma(&self, x: usize, y: usize, upsampling: ChromaUpsampling) -> [f32; 2] {
+        let max_x = self.width.div_ceil(2) - 1;
+        let is_420 = self.layout.is_420();
+        match upsampling {
+            ChromaUpsampling::Nearest => self.chroma(x / 2, if is_420 { y / 2 } else { y }),
+            ChromaUpsampling::Bilinear => {
+                // chroma is aligned with even columns
+                let (x0, x1) = (x / 2, (x / 2 + x % 2).min(max_x));
+                let row = |y| {
+                    let [u0, v0] = self.chroma(x0, y);
+                    let [u1, v1] = self.chroma(x1, y);
+                    [(u0 + u1) / 2., (v0 + v1) / 2.]
+                };
+                if !is_420 {
+                    return row(y);
+                }
+                // chroma rows are between luma rows, so 1/4 from the nearest one
+                let max_y = self.height.div_ceil(2) - 1;
+                let near = y / 2;
+                let far = if y % 2 == 0 { near.saturating_sub(1) } else { (near + 1).min(max_y) };
+                let ([u0, v0], [u1, v1]) = (row(near), row(far));
+                [u0 * 0.75 + u1 * 0.25, v0 * 0.75 + v1 * 0.25]
+            },
+        }
+    }
+
+    /// Converts the frame to RGB, writing it to `dst`, which has `dst_stride` pixels per row.
+    ///
+    /// # Panics
+    ///
+    /// If `dst_stride` is smaller than the width, or `dst` is too small.
+    pub fn to_rgb<P>(&self, dst: &mut [P], dst_stride: usize, matrix: Matrix, range: ColorRange, upsampling: ChromaUpsampling)
+    where
+        P: From<Rgba<u8>>,
+    {
+        assert!(dst_stride >= self.width, "stride is smaller than the width");
+        assert!(self.height == 0 || dst.len() >= (self.height - 1) * dst_stride + self.width, "the destination is too small");
+        for (y, row) in dst.chunks_mut(dst_stride).take(self.height).enumerate() {
+            for (x, px) in row[..self.width].iter_mut().enumerate() {
+                let [u, v] = self.upsampled_chroma(x, y, upsampling);
+                *px = P::from(yuv_to_rgba(self.luma(x, y), u, v, matrix, range));
+            }
+        }
+    }
+
+    /// Converts the frame to a new `Vec` of RGB pixels, without padding.
+    ///
+    /// The target pixel type usually needs to be specified explicitly.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn to_rgb_vec<P>(&self, matrix: Matrix, range: ColorRange, upsampling: ChromaUpsampling) -> Vec<P>
+    where
+        P: From<Rgba<u8>> + Copy,
+    {
+        let mut dst = vec![P::from(Rgba::new(0, 0, 0, 0)); self.width * self.height];
+        self.to_rgb(&mut dst, self.width, matrix, range, upsampling);
+        dst
+    }
+}
+
+/// A mutably borrowed YUV frame, for converting from RGB.
+#[derive(Debug)]
+pub struct YuvFrameMut<'a> {
+    layout: YuvLayout,
+    width: usize,
+    height: usize,
+    planes: [&'a mut [u8]; 3],
+    strides: [usize; 3],
+}
+
+impl<'a> YuvFrameMut<'a> {
+    /// A frame with all planes stored back to back in `data`, without padding.
+    ///
+    /// The size is given in pixels. See [`YuvLayout::buffer_len()`].
+    ///
+    /// # Panics
+    ///
+    /// If `data` is too small.
+    #[must_use]
+    pub fn new(layout: YuvLayout, data: &'a mut [u8], width: usize, height: usize) -> Self {
+        let (strides, lens) = layout.split(width, height);
+        assert!(data.len() >= lens.iter().sum(), "the frame is too small");
+        let (y, rest) = data.split_at_mut(lens[0]);
+        let (u, v) = rest.split_at_mut(lens[1]);
+        Self { layout, width, height, planes: [y, u, &mut v[..lens[2]]], strides }
+    }
+
+    /// A frame with separate planes, which can have padding at the end of each row.
+    ///
+    /// The `planes` are in the order of the layout, e.g. `[y, v, u]` for [`YuvLayout::Yv12`].
+    /// Planes that the layout doesn't have are ignored, and can be empty. The strides are in bytes.
+    ///
+    /// # Panics
+    ///
+    /// If the planes are too small.
+    #[must_use]
+    pub fn from_planes(layout: YuvLayout, planes: [&'a mut [u8]; 3], strides: [usize; 3], width: usize, height: usize) -> Self {
+        let n = layout.num_planes();
+        let lens = planes.each_ref().map(|p| p.len());
+        layout.check_planes(&lens[..n], &strides[..n], width, height);
+        Self { layout, width, height, planes, strides }
+    }
+
+    #[inline]
+    fn set(&mut self, (plane, i): (usize, usize), value: f32) {
+        self.planes[plane][i] = value.convert_depth();
+    }
+
+    /// Converts `src`, which has `src_stride` pixels per row, to YUV, writing it to the frame.
+    ///
+    /// Each chroma sample is the average of the pixels it covers. Padding bytes are not written.
+    ///
+    /// # Panics
+    ///
+    /// If `src_stride` is smaller than the width, or `src` is too small.
+    pub fn from_rgb<P>(&mut self, src: &[P], src_stride: usize, matrix: Matrix, range: ColorRange)
+    where
+        P: Copy + Into<Rgb<u8>>,
+    {
+        let (width, height) = (self.width, self.height);
+        assert!(src_stride >= width, "stride is smaller than the width");
+        assert!(height == 0 || src.len() >= (height - 1) * src_stride + width, "the source is too small");
+        let rows_per_chroma = if self.layout.is_420() { 2 } else { 1 };
+        for cy in 0..height.div_ceil(rows_per_chroma) {
+            for cx in 0..width.div_ceil(2) {
+                let mut sum = [0f32; 2];
+                let mut count = 0.;
+                for y in cy * rows_per_chroma..((cy + 1) * rows_per_chroma).min(height) {
+                    for x in 2 * cx..(2 * cx + 2).min(width) {
+                        let [luma, u, v] = rgb_to_yuv(src[y * src_stride + x].into(), matrix, range);
+                        self.set(self.layout.luma_index(&self.strides, x, y), luma);
+                        sum[0] += u;
+                        sum[1] += v;
+                        count += 1.;
+                    }
+                }
+                let [u_index, v_index] = self.layout.chroma_index(&self.strides, cx, cy);
+                self.set(u_index, sum[0] / count);
+                self.set(v_index, sum[1] / count);
+            }
+        }
+    }
+}
+
+/// 8-bit YUV codes to RGB
+#[inline]
+fn yuv_to_rgba(y: f32, u: f32, v: f32, matrix: Matrix, range: ColorRange) -> Rgba<u8> {
+    // codes divided by 255 are the f32 convention of `Ycbcr`
+    let rgb = Ycbcr::new(y / 255., (u - 128.) / 255., (v - 128.) / 255.).to_rgb(matrix, range);
+    Rgba::new(rgb.r.convert_depth(), rgb.g.convert_depth(), rgb.b.convert_depth(), 255)
+}
+
+/// RGB to unrounded YUV codes, in `0.0..=1.0` for `convert_depth`
+#[inline]
+fn rgb_to_yuv(rgb: Rgb<u8>, matrix: Matrix, range: ColorRange) -> [f32; 3] {
+    let rgb = Rgb::new(rgb.r.convert_depth(), rgb.g.convert_depth(), rgb.b.convert_depth());
+    let px: Ycbcr<f32> = Ycbcr::from_rgb(rgb, matrix, range);
+    [px.y, px.cb + 128. / 255., px.cr + 128. / 255.]
+}
+
+#[test]
+fn yuv_layouts() {
+    use crate::Bgra;
+
+    let (width, height) = (5, 3);
+    let pixels: [Rgb<u8>; 15] = core::array::from_fn(|i| Rgb::new((i * 17) as u8, 200 - (i * 5) as u8, if i % 2 == 0 { 30 } else { 90 }));
+    let layouts = [YuvLayout::I420, YuvLayout::Yv12, YuvLayout::Nv12, YuvLayout::Nv21, YuvLayout::Yuyv, YuvLayout::Uyvy];
+    for upsampling in [ChromaUpsampling::Nearest, ChromaUpsampling::Bilinear] {
+        let mut decoded_420 = None;
+        for layout in layouts {
+            let mut buf = vec![0; layout.buffer_len(width, height)];
+            YuvFrameMut::new(layout, &mut buf, width, height).from_rgb(&pixels, width, Matrix::Bt601, ColorRange::Full);
+            let mut decoded = [Bgra::new_bgra(0, 0, 0, 0); 15];
+            YuvFrame::new(layout, &buf, width, height).to_rgb(&mut decoded, width, Matrix::Bt601, ColorRange::Full, upsampling);
+            for (a, b) in pixels.iter().zip(&decoded) {
+                // luma is exact, but chroma is shared
+                let luma = |r: u8, g: u8, b: u8| 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b);
+                assert!((luma(a.r, a.g, a.b) - luma(b.r, b.g, b.b)).abs() <= 1.5, "{layout:?} {a} {b}");
+                assert_eq!(b.a, 255);
+            }
+            if layout.is_420() {
+                // all 4:2:0 layouts give the same result
+                assert_eq!(*decoded_420.get_or_insert(decoded), decoded, "{layout:?}");
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn yuv_strides() {
+    let (width, height) = (3, 3);
+    let pixels = [Rgb::new(0u8, 0, 255), Rgb::new(0, 255, 0), Rgb::new(255, 255, 255)].repeat(3);
+    let mut y = [7; 4 * 3];
+    let mut v = [7; 3 * 2];
+    let mut u = [7; 3 * 2];
+    let mut frame = YuvFrameMut::from_planes(YuvLayout::Yv12, [&mut y, &mut v, &mut u], [4, 3, 3], width, height);
+    frame.from_rgb(&pixels, width, Matrix::Bt709, ColorRange::Limited);
+    assert_eq!(y, [32, 173, 235, 7, 32, 173, 235, 7, 32, 173, 235, 7]);
+    assert_eq!([u[1], v[1], u[4], v[4]], [128; 4]);
+    assert_eq!(u[2], 7);
+
+    let mut padded = vec![Rgb::new(1u8, 2, 3); 4 * 3];
+    let frame = YuvFrame::from_planes(YuvLayout::Yv12, [&y, &v, &u], [4, 3, 3], width, height);
+    frame.to_rgb(&mut padded, 4, Matrix::Bt709, ColorRange::Limited, ChromaUpsampling::Nearest);
+    assert_eq!(padded[2], Rgb::new(255, 255, 255));
+    assert_eq!(padded[3], Rgb::new(1, 2, 3));
+    assert_eq!(padded[10], Rgb::new(255, 255, 255));
+
+    // constant chroma is the same with any upsampling
+    let gray = [Rgb::new(100u8, 100, 100); 7 * 5];
+    let mut buf = vec![0; YuvLayout::Uyvy.buffer_len(7, 5)];
+    YuvFrameMut::new(YuvLayout::Uyvy, &mut buf, 7, 5).from_rgb(&gray, 7, Matrix::Bt2020, ColorRange::Limited);
+    let frame = YuvFrame::new(YuvLayout::Uyvy, &buf, 7, 5);
+    for upsampling in [ChromaUpsampling::Nearest, ChromaUpsampling::Bilinear] {
+        let decoded: Vec<Rgb<u8>> = frame.to_rgb_vec(Matrix::Bt2020, ColorRange::Limited, upsampling);
+        assert_eq!(decoded, gray);
+    }
+}